@@ -4,6 +4,7 @@ use std::{io::Write, path::PathBuf};
 
 use clap::{CommandFactory, Parser, Subcommand};
 use clipcat_base::{ClipEntryMetadata, ClipboardKind};
+use clipcat_cli::check::{to_redacted_toml, Report};
 use clipcat_client::{Client, Manager, System};
 use clipcat_external_editor::ExternalEditor;
 use snafu::ResultExt;
@@ -68,6 +69,10 @@ pub enum Commands {
     #[clap(about = "Output default configuration")]
     DefaultConfig,
 
+    #[clap(about = "Check configuration file and output the effective configuration with \
+                    secrets redacted")]
+    CheckConfig,
+
     #[clap(about = "Print available text finders")]
     ListFinder,
 
@@ -100,6 +105,7 @@ impl Default for Cli {
 }
 
 impl Cli {
+    #[allow(clippy::too_many_lines)]
     pub fn run(self) -> Result<(), Error> {
         let Self {
             commands,
@@ -131,6 +137,16 @@ impl Cli {
                     .expect("Failed to write to stdout");
                 return Ok(());
             }
+            Some(Commands::CheckConfig) => {
+                return check_config(
+                    config_file.unwrap_or_else(Config::search_config_file_path),
+                    log_level,
+                    finder,
+                    rofi_config,
+                    dmenu_config,
+                    custom_finder_config,
+                );
+            }
             Some(Commands::ListFinder) => {
                 for ty in FinderType::available_types() {
                     println!("{ty}");
@@ -153,7 +169,8 @@ impl Cli {
         let fut = async move {
             let client = {
                 let access_token = config.access_token();
-                Client::new(config.server_endpoint, access_token).await?
+                let tls_config = config.tls_config();
+                Client::new(config.server_endpoint, access_token, tls_config).await?
             };
             let clips = client.list(config.preview_length).await?;
 
@@ -211,6 +228,46 @@ impl Cli {
     }
 }
 
+fn check_config(
+    file_path: PathBuf,
+    log_level: Option<tracing::Level>,
+    finder: Option<FinderType>,
+    rofi_config: config::RofiConfig,
+    dmenu_config: config::DmenuConfig,
+    custom_finder_config: config::CustomFinderConfig,
+) -> Result<(), Error> {
+    let mut report = Report::new(&file_path);
+    if let Some(config) = report.parse::<Config>(&[]) {
+        config.check_file_contents(&mut report);
+        match Config::load(&file_path) {
+            Ok(mut config) => {
+                if let Some(log_level) = log_level {
+                    config.log.level = log_level;
+                }
+                merge_finder_options(
+                    finder,
+                    rofi_config,
+                    dmenu_config,
+                    custom_finder_config,
+                    &mut config,
+                );
+                config.check(&mut report);
+                std::io::stdout()
+                    .write_all(to_redacted_toml(&config).as_bytes())
+                    .expect("Failed to write to stdout");
+            }
+            Err(err) => report.error("", err),
+        }
+    }
+    report.print();
+
+    if report.has_errors() {
+        Err(Error::InvalidConfig { file_path })
+    } else {
+        Ok(())
+    }
+}
+
 async fn insert_clip(
     clips: &[ClipEntryMetadata],
     finder: &FinderRunner,
@@ -312,6 +369,62 @@ fn build_finder(
     finder
 }
 
+// the options of the finder given by command line arguments are merged into
+// the configuration, the effective configuration is checked by `check-config`
+fn merge_finder_options(
+    finder: Option<FinderType>,
+    rofi_config: config::RofiConfig,
+    dmenu_config: config::DmenuConfig,
+    custom_finder_config: config::CustomFinderConfig,
+    config: &mut Config,
+) {
+    if let Some(finder) = finder {
+        config.finder = finder;
+    }
+
+    match config.finder {
+        FinderType::Rofi => {
+            let rofi = config.rofi.get_or_insert_with(Default::default);
+            if let Some(line_length) = rofi_config.line_length {
+                rofi.line_length = line_length;
+            }
+
+            if let Some(menu_length) = rofi_config.menu_length {
+                rofi.menu_length = menu_length;
+            }
+
+            if let Some(args) = rofi_config.extra_arguments {
+                rofi.extra_arguments = args.split(',').map(ToString::to_string).collect();
+            }
+        }
+        FinderType::Dmenu => {
+            let dmenu = config.dmenu.get_or_insert_with(Default::default);
+            if let Some(line_length) = dmenu_config.line_length {
+                dmenu.line_length = line_length;
+            }
+
+            if let Some(menu_length) = dmenu_config.menu_length {
+                dmenu.menu_length = menu_length;
+            }
+
+            if let Some(args) = dmenu_config.extra_arguments {
+                dmenu.extra_arguments = args.split(',').map(ToString::to_string).collect();
+            }
+        }
+        FinderType::Custom => {
+            let custom_finder = config.custom_finder.get_or_insert_with(Default::default);
+            if let Some(path) = custom_finder_config.program_path {
+                custom_finder.program = path.display().to_string();
+            }
+
+            if let Some(args) = &custom_finder_config.arguments {
+                custom_finder.args = args.split(',').map(ToString::to_string).collect();
+            }
+        }
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use clap::Parser;
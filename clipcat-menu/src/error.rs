@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use snafu::Snafu;
 
 use crate::finder::FinderError;
@@ -19,6 +21,9 @@ pub enum Error {
 
     #[snafu(display("Error occurs while interacting with server, error: {error}"))]
     Operation { error: String },
+
+    #[snafu(display("Configuration file `{}` is invalid", file_path.display()))]
+    InvalidConfig { file_path: PathBuf },
 }
 
 impl From<clipcat_external_editor::Error> for Error {
@@ -220,4 +220,11 @@ impl FinderRunner {
             external.set_extra_arguments(arguments);
         }
     }
+
+    /// Returns the program of the external finder, `None` if the builtin
+    /// finder is used.
+    #[inline]
+    pub fn program(&self) -> Option<String> {
+        self.external.as_ref().map(|external| external.program())
+    }
 }
@@ -1,8 +1,12 @@
 use std::{io::Write, num::ParseIntError, path::PathBuf};
 
-use clap::{CommandFactory, Parser, Subcommand};
-use clipcat_base::{ClipEntryMetadata, ClipboardKind, ClipboardWatcherState};
-use clipcat_client::{Client, Manager as _, System, Watcher as _};
+use clap::{builder::PossibleValuesParser, ArgGroup, CommandFactory, Parser, Subcommand};
+use clipcat_base::{AuditRecord, ClipEntryMetadata, ClipboardKind, ClipboardWatcherState};
+use clipcat_cli::check::{to_redacted_toml, Report};
+use clipcat_client::{
+    error::{GetClipError, GetCurrentClipError},
+    Audit as _, Client, Manager as _, System, Watcher as _,
+};
 use clipcat_external_editor::ExternalEditor;
 use snafu::ResultExt;
 use tokio::{
@@ -13,6 +17,10 @@ use tokio::{
 use crate::{
     config::Config,
     error::{self, Error},
+    output::{
+        ClipRecord, EventRecord, LengthRecord, OutputOptions, Record as _, VersionRecord,
+        WatcherStateRecord,
+    },
     shadow,
 };
 
@@ -54,6 +62,9 @@ pub enum Commands {
     Version {
         #[clap(long = "client", help = "If true, shows client version only (no server required).")]
         client: bool,
+
+        #[clap(flatten)]
+        output: OutputOptions,
     },
 
     #[clap(about = "Output shell completion code for the specified shell (bash, zsh, fish)")]
@@ -62,6 +73,10 @@ pub enum Commands {
     #[clap(about = "Output default configuration")]
     DefaultConfig,
 
+    #[clap(about = "Check configuration file and output the effective configuration with \
+                    secrets redacted")]
+    CheckConfig,
+
     #[clap(about = "Insert new clip into clipboard")]
     Insert {
         #[clap(
@@ -111,10 +126,42 @@ pub enum Commands {
         file_path: Option<PathBuf>,
     },
 
-    #[clap(about = "Print clip with <id>")]
+    #[clap(
+        about = "Print clip with <id>",
+        group(ArgGroup::new("exact_content").args(["raw", "file_path"]).multiple(true))
+    )]
     Get {
         #[clap(value_parser = parse_hex)]
         id: Option<u64>,
+
+        #[clap(long = "raw", help = "Print the exact content of the clip instead of its preview")]
+        raw: bool,
+
+        #[clap(
+            long = "mime",
+            requires = "exact_content",
+            help = "Specify the MIME type to encode an image with (\"image/png\", \"image/jpeg\", \
+                    \"image/gif\", \"image/bmp\"), the MIME type of the clip is used by default"
+        )]
+        mime: Option<mime::Mime>,
+
+        #[clap(
+            long = "file",
+            short = 'f',
+            help = "Write the exact content of the clip into <FILE_PATH>, implies `--raw`"
+        )]
+        file_path: Option<PathBuf>,
+
+        #[clap(
+            long = "field",
+            value_parser = PossibleValuesParser::new(ClipRecord::FIELDS),
+            conflicts_with_all = ["raw", "file_path", "format", "template"],
+            help = "Print only <FIELD> of the metadata of the clip"
+        )]
+        field: Option<String>,
+
+        #[clap(flatten)]
+        output: OutputOptions,
     },
 
     #[clap(
@@ -124,6 +171,9 @@ pub enum Commands {
     List {
         #[clap(long)]
         no_id: bool,
+
+        #[clap(flatten)]
+        output: OutputOptions,
     },
 
     #[clap(about = "Update clip with <id>")]
@@ -172,7 +222,10 @@ pub enum Commands {
         aliases = &["count", "len"],
         about = "Print length of clipboard history"
     )]
-    Length,
+    Length {
+        #[clap(flatten)]
+        output: OutputOptions,
+    },
 
     #[clap(aliases = &["enable"], about = "Enable clipboard watcher")]
     EnableWatcher,
@@ -184,7 +237,44 @@ pub enum Commands {
     ToggleWatcher,
 
     #[clap(aliases = &["watcher-state"], about = "Get clipboard watcher state")]
-    GetWatcherState,
+    GetWatcherState {
+        #[clap(flatten)]
+        output: OutputOptions,
+    },
+
+    #[clap(about = "Print records of the audit log")]
+    Audit {
+        #[clap(
+            long = "limit",
+            short = 'n',
+            default_value = "20",
+            help = "Specify the number of the newest records to print, all records are printed if \
+                    it is 0"
+        )]
+        limit: usize,
+    },
+
+    #[clap(about = "Print events of clipboard history and clipboard watcher as they happen")]
+    Watch {
+        #[clap(
+            long = "exec",
+            help = "Run <COMMAND> with `sh -c` for each event, the event is written to its stdin \
+                    in JSON and its name is set in `CLIPCAT_EVENT`"
+        )]
+        command: Option<String>,
+
+        #[clap(
+            long = "no-reconnect",
+            help = "Exit when the connection to server is lost instead of reconnecting"
+        )]
+        no_reconnect: bool,
+
+        #[clap(flatten)]
+        output: OutputOptions,
+    },
+
+    #[clap(aliases = &["reload"], about = "Reload configuration of clipcatd")]
+    ReloadConfig,
 }
 
 impl Default for Cli {
@@ -192,10 +282,16 @@ impl Default for Cli {
 }
 
 impl Cli {
+    fn config_file_path(&self) -> PathBuf {
+        self.config_file.clone().unwrap_or_else(Config::search_config_file_path)
+    }
+
     fn load_config(&self) -> Config {
-        let mut config = Config::load_or_default(
-            self.config_file.clone().unwrap_or_else(Config::search_config_file_path),
-        );
+        let config = Config::load_or_default(self.config_file_path());
+        self.apply_overrides(config)
+    }
+
+    fn apply_overrides(&self, mut config: Config) -> Config {
         if let Some(endpoint) = &self.server_endpoint {
             config.server_endpoint = endpoint.clone();
         }
@@ -207,11 +303,38 @@ impl Cli {
         config
     }
 
+    fn check_config(&self) -> i32 {
+        let file_path = self.config_file_path();
+        let mut report = Report::new(&file_path);
+        if let Some(config) = report.parse::<Config>(&[]) {
+            config.check_file_contents(&mut report);
+            match Config::load(&file_path) {
+                Ok(config) => {
+                    let config = self.apply_overrides(config);
+                    config.check(&mut report);
+                    std::io::stdout()
+                        .write_all(to_redacted_toml(&config).as_bytes())
+                        .expect("Failed to write to stdout");
+                }
+                Err(err) => report.error("", err),
+            }
+        }
+        report.print();
+        i32::from(report.has_errors())
+    }
+
     #[allow(clippy::too_many_lines)]
     pub fn run(self) -> Result<i32, Error> {
         let client_version = Self::command().get_version().unwrap_or_default().to_string();
         match self.commands {
-            Some(Commands::Version { client }) if client => {
+            Some(Commands::Version { client, ref output }) if client => {
+                if let Some(printer) = output.printer::<VersionRecord>()? {
+                    let record = VersionRecord { client_version, server_version: None };
+                    std::io::stdout()
+                        .write_all(printer.render_one(&record).as_bytes())
+                        .expect("Failed to write to stdout");
+                    return Ok(0);
+                }
                 std::io::stdout()
                     .write_all(Self::command().render_long_version().as_bytes())
                     .expect("Failed to write to stdout");
@@ -235,6 +358,7 @@ impl Cli {
                     .expect("Failed to write to stdout");
                 return Ok(0);
             }
+            Some(Commands::CheckConfig) => return Ok(self.check_config()),
             _ => {}
         }
 
@@ -244,7 +368,8 @@ impl Cli {
         let fut = async move {
             let client = {
                 let access_token = config.access_token();
-                Client::new(config.server_endpoint, access_token).await?
+                let tls_config = config.tls_config();
+                Client::new(config.server_endpoint, access_token, tls_config).await?
             };
             let server_version = client
                 .get_version()
@@ -252,7 +377,13 @@ impl Cli {
                 .map_or_else(|_err| "unknown".to_string(), |version| version.to_string());
 
             match self.commands {
-                Some(Commands::Version { .. }) => {
+                Some(Commands::Version { output, .. }) => {
+                    if let Some(printer) = output.printer::<VersionRecord>()? {
+                        let record =
+                            VersionRecord { client_version, server_version: Some(server_version) };
+                        write_stdout(&printer.render_one(&record)).await?;
+                        return Ok(0);
+                    }
                     let info = format!(
                         "Client Version: {client_version}\nServer Version: {server_version}\n",
                     );
@@ -268,20 +399,51 @@ impl Cli {
                 None => {
                     print_list(&client, config.preview_length, false).await?;
                 }
-                Some(Commands::List { no_id }) => {
-                    print_list(&client, config.preview_length, no_id).await?;
+                Some(Commands::List { no_id, output }) => {
+                    if let Some(printer) = output.printer::<ClipRecord>()? {
+                        let records = client
+                            .list(config.preview_length)
+                            .await?
+                            .into_iter()
+                            .map(ClipRecord::from)
+                            .collect::<Vec<_>>();
+                        write_stdout(&printer.render_list(&records)).await?;
+                    } else {
+                        print_list(&client, config.preview_length, no_id).await?;
+                    }
                 }
-                Some(Commands::Get { id }) => {
+                Some(Commands::Get { id, raw, mime, file_path, field, output }) => {
+                    if raw || file_path.is_some() {
+                        let clip = if let Some(id) = id {
+                            client.get(id).await?
+                        } else {
+                            client.get_current_clip(ClipboardKind::Clipboard).await?
+                        };
+                        let data = clip.encoded_as(&mime.unwrap_or_else(|| clip.mime()))?;
+                        save_file_or_write_stdout(file_path, data).await?;
+                        return Ok(0);
+                    }
+                    if let Some(field) = field {
+                        if let Some(metadata) = get_metadata(&client, id).await? {
+                            let value = ClipRecord::from(metadata).field(&field);
+                            write_stdout(&format!("{}\n", value.unwrap_or_default())).await?;
+                        }
+                        return Ok(0);
+                    }
+                    if let Some(printer) = output.printer::<ClipRecord>()? {
+                        if let Some(metadata) = get_metadata(&client, id).await? {
+                            write_stdout(&printer.render_one(&ClipRecord::from(metadata))).await?;
+                        }
+                        return Ok(0);
+                    }
                     let data = if let Some(id) = id {
                         client.get(id).await?.preview_information(None)
                     } else {
-                        client
-                            .list(config.preview_length)
-                            .await?
-                            .into_iter()
-                            .find(|metadata| metadata.kind == ClipboardKind::Clipboard)
-                            .map(|metadata| metadata.preview)
-                            .unwrap_or_default()
+                        match client.get_current_clip(ClipboardKind::Clipboard).await {
+                            Ok(clip) => clip.preview_information(None),
+                            Err(GetCurrentClipError::Empty) => String::new(),
+                            Err(err) => return Err(err.into()),
+                        }
                     };
 
                     println!("{data}");
@@ -292,8 +454,13 @@ impl Cli {
                             client.insert(data.as_bytes(), mime::TEXT_PLAIN_UTF_8, kind).await?;
                     }
                 }
-                Some(Commands::Length) => {
-                    println!("{len}", len = client.length().await?);
+                Some(Commands::Length { output }) => {
+                    let length = client.length().await?;
+                    if let Some(printer) = output.printer::<LengthRecord>()? {
+                        write_stdout(&printer.render_one(&LengthRecord { length })).await?;
+                    } else {
+                        println!("{length}");
+                    }
                 }
                 Some(Commands::Load { kinds, file_path, mime }) => {
                     let (data, mime) = load_file_or_read_stdin(file_path, mime).await?;
@@ -376,8 +543,35 @@ impl Cli {
                 Some(Commands::ToggleWatcher) => {
                     print_watcher_state(client.toggle_watcher().await?);
                 }
-                Some(Commands::GetWatcherState) => {
-                    print_watcher_state(client.get_watcher_state().await?);
+                Some(Commands::GetWatcherState { output }) => {
+                    let state = client.get_watcher_state().await?;
+                    if let Some(printer) = output.printer::<WatcherStateRecord>()? {
+                        let record = WatcherStateRecord::from(state);
+                        write_stdout(&printer.render_one(&record)).await?;
+                    } else {
+                        print_watcher_state(state);
+                    }
+                }
+                Some(Commands::Audit { limit }) => {
+                    print_audit_records(&client.list_audit_records(limit).await?).await?;
+                }
+                Some(Commands::Watch { command, no_reconnect, output }) => {
+                    let printer = output.printer::<EventRecord>()?;
+                    let mut subscription = client.subscribe().await?.with_reconnect(!no_reconnect);
+                    while let Some(update) = subscription.next().await? {
+                        let record = EventRecord::from(update);
+                        let output = printer.as_ref().map_or_else(
+                            || format!("{}\n", record.to_text()),
+                            |printer| printer.render_one(&record),
+                        );
+                        write_stdout(&output).await?;
+                        if let Some(ref command) = command {
+                            run_event_command(command, &record).await?;
+                        }
+                    }
+                }
+                Some(Commands::ReloadConfig) => {
+                    print_restart_required(&client.reload_config().await?);
                 }
                 _ => unreachable!(),
             }
@@ -454,6 +648,14 @@ fn print_watcher_state(state: ClipboardWatcherState) {
     println!("{msg}");
 }
 
+#[inline]
+fn print_restart_required(settings: &[String]) {
+    println!("Configuration is reloaded.");
+    if !settings.is_empty() {
+        println!("Restart {} to apply: {}", clipcat_base::DAEMON_PROGRAM_NAME, settings.join(", "));
+    }
+}
+
 async fn print_list(client: &Client, preview_length: usize, no_id: bool) -> Result<(), Error> {
     let metadata_list = client.list(preview_length).await?;
     for metadata in metadata_list {
@@ -464,5 +666,109 @@ async fn print_list(client: &Client, preview_length: usize, no_id: bool) -> Resu
     Ok(())
 }
 
+/// Returns the metadata of the clip with `id`, or of the current clip of the
+/// clipboard if `id` is not given, the preview is not truncated.
+async fn get_metadata(
+    client: &Client,
+    id: Option<u64>,
+) -> Result<Option<ClipEntryMetadata>, Error> {
+    let clip = match id {
+        Some(id) => client.get(id).await?,
+        None => match client.get_current_clip(ClipboardKind::Clipboard).await {
+            Ok(clip) => clip,
+            Err(GetCurrentClipError::Empty) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        },
+    };
+    // only the pinned state and the source are taken, the preview is replaced
+    // with the whole contents
+    let metadata = match client.get_metadata(clip.id(), 1).await {
+        Ok(metadata) => metadata,
+        Err(GetClipError::Empty) => clip.metadata(None),
+        Err(err) => return Err(err.into()),
+    };
+    Ok(Some(ClipEntryMetadata { preview: clip.preview_information(None), ..metadata }))
+}
+
+async fn write_stdout(output: &str) -> Result<(), Error> {
+    tokio::io::stdout().write_all(output.as_bytes()).await.context(error::WriteStdoutSnafu)
+}
+
+/// Runs `command` with the shell and waits for it, `record` is written to its
+/// stdin in JSON.
+async fn run_event_command(command: &str, record: &EventRecord) -> Result<(), Error> {
+    let mut child = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("CLIPCAT_EVENT", record.event)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .context(error::RunCommandSnafu { command })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let json = serde_json::to_string(record).expect("record is serializable");
+        // the command may exit without reading its stdin
+        let _unused = stdin.write_all(json.as_bytes()).await;
+    }
+    let status = child.wait().await.context(error::RunCommandSnafu { command })?;
+    if !status.success() {
+        tracing::warn!("Command `{command}` for event `{}` exits with {status}", record.event);
+    }
+    Ok(())
+}
+
+async fn print_audit_records(records: &[AuditRecord]) -> Result<(), Error> {
+    use std::fmt::Write as _;
+
+    let mut output = String::new();
+    for record in records {
+        let timestamp = record
+            .timestamp
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_default();
+        let mut line = format!("{timestamp} {} {}", record.transport, record.operation);
+        if let Some(token_name) = &record.token_name {
+            let _unused = write!(line, " token={token_name}");
+        }
+        if let Some(uid) = record.peer_uid {
+            let _unused = write!(line, " uid={uid}");
+        }
+        if let Some(pid) = record.peer_pid {
+            let _unused = write!(line, " pid={pid}");
+        }
+        if let Some(address) = &record.peer_address {
+            let _unused = write!(line, " peer={address}");
+        }
+        if !record.clip_ids.is_empty() {
+            let ids = record.clip_ids.iter().map(|id| format!("{id:016x}")).collect::<Vec<_>>();
+            let _unused = write!(line, " clips={}", ids.join(","));
+        }
+        output.push_str(&line);
+        output.push('\n');
+    }
+    tokio::io::stdout().write_all(output.as_bytes()).await.context(error::WriteStdoutSnafu)
+}
+
 #[inline]
 fn parse_hex(src: &str) -> Result<u64, ParseIntError> { u64::from_str_radix(src, 16) }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::cli::{Cli, Commands};
+
+    #[test]
+    fn test_get_mime() {
+        let parse = |args: &[&str]| Cli::try_parse_from(["clipcatctl", "get"].iter().chain(args));
+
+        // `--file` implies `--raw`
+        let cli = parse(&["ab", "--file", "clip.jpg", "--mime", "image/jpeg"]).unwrap();
+        assert!(matches!(
+            cli.commands,
+            Some(Commands::Get { id: Some(0xab), mime: Some(ref mime), .. }) if mime.essence_str() == "image/jpeg"
+        ));
+        assert!(parse(&["ab", "--raw", "--mime", "image/png"]).is_ok());
+        assert!(parse(&["--raw", "--file", "clip.png"]).is_ok());
+        assert!(parse(&["ab", "--mime", "image/png"]).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use std::path::{Path, PathBuf};
 
+use clipcat_cli::check::Report;
 use resolve_path::PathResolveExt;
 use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
@@ -13,6 +14,9 @@ pub struct Config {
 
     pub access_token_file_path: Option<PathBuf>,
 
+    #[serde(default)]
+    pub tls: Option<clipcat_cli::config::TlsConfig>,
+
     #[serde(default)]
     pub preview_length: usize,
 
@@ -26,6 +30,7 @@ impl Default for Config {
             server_endpoint: clipcat_base::config::default_server_endpoint(),
             access_token: None,
             access_token_file_path: None,
+            tls: None,
             preview_length: 100,
             log: clipcat_cli::config::LogConfig::default(),
         }
@@ -96,6 +101,15 @@ impl Config {
             }
         }
 
+        if let Some(ref mut tls) = config.tls {
+            for file_path in tls.file_paths_mut() {
+                *file_path = file_path
+                    .try_resolve()
+                    .map(|path| path.to_path_buf())
+                    .with_context(|_| ResolveFilePathSnafu { file_path: file_path.clone() })?;
+            }
+        }
+
         Ok(config)
     }
 
@@ -103,6 +117,38 @@ impl Config {
     pub fn load_or_default<P: AsRef<Path>>(path: P) -> Self { Self::load(path).unwrap_or_default() }
 
     pub fn access_token(&self) -> Option<String> { self.access_token.clone() }
+
+    pub fn tls_config(&self) -> Option<clipcat_client::TlsConfig> {
+        self.tls.clone().map(clipcat_client::TlsConfig::from)
+    }
+
+    /// Reports options which are overridden while loading, `self` is the
+    /// configuration as it is written in the file.
+    pub fn check_file_contents(&self, report: &mut Report) {
+        if self.access_token.is_some() && self.access_token_file_path.is_some() {
+            report.warning(
+                "access_token",
+                "overridden by the content of `access_token_file_path` if it is readable",
+            );
+        }
+    }
+
+    /// Reports missing files and conflicting options of the effective
+    /// configuration.
+    pub fn check(&self, report: &mut Report) {
+        if let Some(ref file_path) = self.access_token_file_path {
+            report.check_file("access_token_file_path", file_path);
+        }
+        if let Some(ref tls) = self.tls {
+            tls.check("tls", report);
+            if self.server_endpoint.scheme() != Some(&http::uri::Scheme::HTTPS) {
+                report.warning("tls", "ignored as the scheme of `server_endpoint` is not `https`");
+            }
+        }
+        if let Some(ref file_path) = self.log.file_path {
+            report.check_writable_file("log.file_path", file_path);
+        }
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -32,6 +32,15 @@ pub enum Error {
 
     #[snafu(display("{source}"))]
     CheckUtf8String { source: Utf8Error },
+
+    #[snafu(display("Could not run command `{command}`, error: {source}"))]
+    RunCommand { command: String, source: std::io::Error },
+
+    #[snafu(display("`--output template` requires a template given with `--format`"))]
+    MissingTemplate,
+
+    #[snafu(display("Invalid template, {message}"))]
+    InvalidTemplate { message: String },
 }
 
 impl From<clipcat_external_editor::Error> for Error {
@@ -126,6 +135,24 @@ impl From<clipcat_client::error::GetWatcherStateError> for Error {
     }
 }
 
+impl From<clipcat_client::error::ListAuditRecordsError> for Error {
+    fn from(err: clipcat_client::error::ListAuditRecordsError) -> Self {
+        Self::Operation { error: err.to_string() }
+    }
+}
+
+impl From<clipcat_client::error::SubscribeError> for Error {
+    fn from(err: clipcat_client::error::SubscribeError) -> Self {
+        Self::Operation { error: err.to_string() }
+    }
+}
+
+impl From<clipcat_client::error::ReloadConfigError> for Error {
+    fn from(err: clipcat_client::error::ReloadConfigError) -> Self {
+        Self::Operation { error: err.to_string() }
+    }
+}
+
 impl From<clipcat_base::ClipEntryError> for Error {
     fn from(error: clipcat_base::ClipEntryError) -> Self { Self::EncodeData { error } }
 }
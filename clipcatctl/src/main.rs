@@ -1,6 +1,7 @@
 mod cli;
 mod config;
 mod error;
+mod output;
 mod shadow {
     #![allow(clippy::needless_raw_string_hashes)]
     use shadow_rs::shadow;
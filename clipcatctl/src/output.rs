@@ -0,0 +1,601 @@
+use clap::{Args, ValueEnum};
+use clipcat_base::{ClipEntryMetadata, ClipboardEvent, ClipboardWatcherState};
+use clipcat_client::SubscriptionUpdate;
+use serde::Serialize;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::error::Error;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+
+    /// One JSON document, lists are printed as an array.
+    Json,
+
+    /// One JSON object per line.
+    Ndjson,
+
+    /// Tab-separated values, one record per line.
+    Tsv,
+
+    /// The template given with `--format`, one record per line.
+    Template,
+}
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct OutputOptions {
+    #[clap(long = "output", value_enum, default_value_t, help = "Specify the output format")]
+    format: OutputFormat,
+
+    #[clap(
+        long = "format",
+        help = "Specify the template of each record, e.g. '{id} {kind} {preview}', implies \
+                `--output template`"
+    )]
+    template: Option<String>,
+}
+
+impl OutputOptions {
+    /// Returns `None` if the records are printed as human-readable text.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the template is invalid.
+    pub fn printer<R>(&self) -> Result<Option<Printer<R>>, Error>
+    where
+        R: Record,
+    {
+        let format = match (self.format, &self.template) {
+            (OutputFormat::Text, None) => return Ok(None),
+            (OutputFormat::Text | OutputFormat::Template, Some(template)) => {
+                Format::Template(Template::parse::<R>(template)?)
+            }
+            (OutputFormat::Template, None) => return Err(Error::MissingTemplate),
+            (OutputFormat::Json, _) => Format::Json,
+            (OutputFormat::Ndjson, _) => Format::Ndjson,
+            (OutputFormat::Tsv, _) => Format::Tsv,
+        };
+        Ok(Some(Printer { format, _record: std::marker::PhantomData }))
+    }
+}
+
+/// A record printed in the structured output formats.
+pub trait Record: Serialize {
+    /// Names of the fields, in the order of the columns of TSV.
+    const FIELDS: &'static [&'static str];
+
+    /// Values of the fields in the order of [`Record::FIELDS`].
+    fn values(&self) -> Vec<String>;
+
+    /// Value of the field `name`, `None` if there is no such field.
+    fn field(&self, name: &str) -> Option<String> {
+        let index = Self::FIELDS.iter().position(|field| *field == name)?;
+        self.values().into_iter().nth(index)
+    }
+}
+
+enum Format {
+    Json,
+
+    Ndjson,
+
+    Tsv,
+
+    Template(Template),
+}
+
+pub struct Printer<R> {
+    format: Format,
+
+    _record: std::marker::PhantomData<R>,
+}
+
+impl<R> Printer<R>
+where
+    R: Record,
+{
+    pub fn render_one(&self, record: &R) -> String {
+        match self.format {
+            Format::Json => {
+                let mut output =
+                    serde_json::to_string_pretty(record).expect("record is serializable");
+                output.push('\n');
+                output
+            }
+            _ => self.render_list(std::slice::from_ref(record)),
+        }
+    }
+
+    pub fn render_list(&self, records: &[R]) -> String {
+        let mut output = match self.format {
+            Format::Json => serde_json::to_string_pretty(records).expect("record is serializable"),
+            Format::Ndjson => records
+                .iter()
+                .map(|record| serde_json::to_string(record).expect("record is serializable"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Format::Tsv => records
+                .iter()
+                .map(|record| {
+                    record.values().iter().map(|value| escape(value)).collect::<Vec<_>>().join("\t")
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Format::Template(ref template) => records
+                .iter()
+                .map(|record| template.render(&record.values()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output
+    }
+}
+
+enum Segment {
+    Text(String),
+
+    // index into `Record::FIELDS`
+    Field(usize),
+}
+
+/// Template with `{field}` placeholders, `{{` and `}}` are literal braces.
+struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    fn parse<R>(template: &str) -> Result<Self, Error>
+    where
+        R: Record,
+    {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    let _unused = chars.next();
+                    text.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    let _unused = chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest.find('}').ok_or_else(|| Error::InvalidTemplate {
+                        message: "unclosed `{`".to_string(),
+                    })?;
+                    let name = &rest[..end];
+                    let index =
+                        R::FIELDS.iter().position(|field| *field == name).ok_or_else(|| {
+                            Error::InvalidTemplate {
+                                message: format!(
+                                    "unknown field `{name}`, available fields: {}",
+                                    R::FIELDS.join(", ")
+                                ),
+                            }
+                        })?;
+                    chars = rest[end + 1..].chars();
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Field(index));
+                }
+                '}' => {
+                    return Err(Error::InvalidTemplate { message: "unmatched `}`".to_string() });
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Self { segments })
+    }
+
+    fn render(&self, values: &[String]) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.as_str(),
+                Segment::Field(index) => values[*index].as_str(),
+            })
+            .collect()
+    }
+}
+
+// a value never spans more than one line or column, previews are escaped by
+// `clipcatd` in the same way
+fn escape(value: &str) -> String {
+    value.replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClipRecord {
+    id: String,
+
+    kind: String,
+
+    mime: String,
+
+    timestamp: String,
+
+    size: usize,
+
+    pinned: bool,
+
+    source: String,
+
+    preview: String,
+}
+
+impl From<ClipEntryMetadata> for ClipRecord {
+    fn from(
+        ClipEntryMetadata { id, kind, timestamp, mime, preview, size, pinned, source }: ClipEntryMetadata,
+    ) -> Self {
+        Self {
+            id: format!("{id:016x}"),
+            kind: kind.as_str().to_lowercase(),
+            mime: mime.essence_str().to_string(),
+            timestamp: timestamp.format(&Rfc3339).unwrap_or_default(),
+            size,
+            pinned,
+            source: source.to_string(),
+            preview,
+        }
+    }
+}
+
+impl Record for ClipRecord {
+    const FIELDS: &'static [&'static str] =
+        &["id", "kind", "mime", "timestamp", "size", "pinned", "source", "preview"];
+
+    fn values(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.kind.clone(),
+            self.mime.clone(),
+            self.timestamp.clone(),
+            self.size.to_string(),
+            self.pinned.to_string(),
+            self.source.clone(),
+            self.preview.clone(),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LengthRecord {
+    pub length: usize,
+}
+
+impl Record for LengthRecord {
+    const FIELDS: &'static [&'static str] = &["length"];
+
+    fn values(&self) -> Vec<String> { vec![self.length.to_string()] }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatcherStateRecord {
+    state: &'static str,
+}
+
+impl From<ClipboardWatcherState> for WatcherStateRecord {
+    fn from(state: ClipboardWatcherState) -> Self { Self { state: watcher_state_str(state) } }
+}
+
+impl Record for WatcherStateRecord {
+    const FIELDS: &'static [&'static str] = &["state"];
+
+    fn values(&self) -> Vec<String> { vec![self.state.to_string()] }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersionRecord {
+    pub client_version: String,
+
+    /// `None` if only the client version is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+}
+
+impl Record for VersionRecord {
+    const FIELDS: &'static [&'static str] = &["client_version", "server_version"];
+
+    fn values(&self) -> Vec<String> {
+        vec![self.client_version.clone(), self.server_version.clone().unwrap_or_default()]
+    }
+}
+
+/// An update of `clipcatctl watch`, fields not related to the event are
+/// omitted.
+#[derive(Debug, Default, Serialize)]
+pub struct EventRecord {
+    pub event: &'static str,
+
+    // the time when the event is received
+    pub timestamp: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<&'static str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missed: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+impl From<SubscriptionUpdate> for EventRecord {
+    fn from(update: SubscriptionUpdate) -> Self {
+        let timestamp = OffsetDateTime::now_utc().format(&Rfc3339).unwrap_or_default();
+        let event = match update {
+            SubscriptionUpdate::Event(event) => event,
+            SubscriptionUpdate::Disconnected { reason } => {
+                return Self {
+                    event: "disconnected",
+                    timestamp,
+                    reason: Some(reason),
+                    ..Self::default()
+                };
+            }
+            SubscriptionUpdate::Reconnected => {
+                return Self { event: "reconnected", timestamp, ..Self::default() };
+            }
+        };
+        let record = Self { event: event.name(), timestamp, ..Self::default() };
+        match event {
+            ClipboardEvent::ClipAdded { metadata } => {
+                let ClipRecord { id, kind, mime, size, pinned, source, preview, .. } =
+                    ClipRecord::from(metadata);
+                Self {
+                    id: Some(id),
+                    kind: Some(kind),
+                    mime: Some(mime),
+                    size: Some(size),
+                    pinned: Some(pinned),
+                    source: Some(source),
+                    preview: Some(preview),
+                    ..record
+                }
+            }
+            ClipboardEvent::ClipRemoved { id } => Self { id: Some(format!("{id:016x}")), ..record },
+            ClipboardEvent::ClipMarked { id, kind } => Self {
+                id: Some(format!("{id:016x}")),
+                kind: Some(kind.as_str().to_lowercase()),
+                ..record
+            },
+            ClipboardEvent::ClipPinned { id, pinned } => {
+                Self { id: Some(format!("{id:016x}")), pinned: Some(pinned), ..record }
+            }
+            ClipboardEvent::HistoryCleared => record,
+            ClipboardEvent::WatcherStateChanged { state } => {
+                Self { state: Some(watcher_state_str(state)), ..record }
+            }
+            ClipboardEvent::Lagged { missed } => Self { missed: Some(missed), ..record },
+        }
+    }
+}
+
+impl EventRecord {
+    /// Human-readable form, one line without the trailing newline.
+    pub fn to_text(&self) -> String {
+        use std::fmt::Write as _;
+
+        let mut text = format!("{} {}", self.timestamp, self.event);
+        for value in [&self.id, &self.kind, &self.mime].into_iter().flatten() {
+            text.push(' ');
+            text.push_str(value);
+        }
+        if let Some(pinned) = self.pinned {
+            text.push_str(if pinned { " pinned" } else { " unpinned" });
+        }
+        if let Some(state) = self.state {
+            text.push(' ');
+            text.push_str(state);
+        }
+        if let Some(missed) = self.missed {
+            let _unused = write!(text, " missed={missed}");
+        }
+        for value in [&self.reason, &self.preview].into_iter().flatten() {
+            let _unused = write!(text, ": {value}");
+        }
+        text
+    }
+}
+
+impl Record for EventRecord {
+    const FIELDS: &'static [&'static str] = &[
+        "event",
+        "timestamp",
+        "id",
+        "kind",
+        "mime",
+        "size",
+        "pinned",
+        "source",
+        "state",
+        "missed",
+        "reason",
+        "preview",
+    ];
+
+    fn values(&self) -> Vec<String> {
+        vec![
+            self.event.to_string(),
+            self.timestamp.clone(),
+            self.id.clone().unwrap_or_default(),
+            self.kind.clone().unwrap_or_default(),
+            self.mime.clone().unwrap_or_default(),
+            self.size.map_or_else(String::new, |size| size.to_string()),
+            self.pinned.map_or_else(String::new, |pinned| pinned.to_string()),
+            self.source.clone().unwrap_or_default(),
+            self.state.unwrap_or_default().to_string(),
+            self.missed.map_or_else(String::new, |missed| missed.to_string()),
+            self.reason.clone().unwrap_or_default(),
+            self.preview.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+const fn watcher_state_str(state: ClipboardWatcherState) -> &'static str {
+    match state {
+        ClipboardWatcherState::Enabled => "enabled",
+        ClipboardWatcherState::Disabled => "disabled",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clipcat_base::{ClipEntryMetadata, ClipSource, ClipboardKind};
+    use time::OffsetDateTime;
+
+    use super::{escape, ClipRecord, OutputFormat, OutputOptions, Printer, Record, Template};
+    use crate::error::Error;
+
+    fn records() -> Vec<ClipRecord> {
+        let metadata = |id, preview: &str, pinned| ClipEntryMetadata {
+            id,
+            kind: ClipboardKind::Primary,
+            timestamp: OffsetDateTime::UNIX_EPOCH,
+            mime: mime::TEXT_PLAIN_UTF_8,
+            preview: preview.to_string(),
+            size: preview.len(),
+            pinned,
+            source: ClipSource::History,
+        };
+        vec![
+            ClipRecord::from(metadata(0xab, "clipcat", true)),
+            ClipRecord::from(metadata(0xcd, "a\tb\nc", false)),
+        ]
+    }
+
+    fn printer(format: OutputFormat, template: Option<&str>) -> Printer<ClipRecord> {
+        let options = OutputOptions { format, template: template.map(ToString::to_string) };
+        options.printer().unwrap().unwrap()
+    }
+
+    fn render(template: &str) -> Result<String, Error> {
+        let template = Template::parse::<ClipRecord>(template)?;
+        Ok(template.render(&records()[0].values()))
+    }
+
+    #[test]
+    fn test_template() {
+        assert_eq!(render("{id} {kind}: {preview}").unwrap(), "00000000000000ab primary: clipcat");
+        assert_eq!(render("{{id}} }}{size}{{").unwrap(), "{id} }7{");
+        assert_eq!(render("").unwrap(), "");
+
+        let message = |template| match render(template) {
+            Err(Error::InvalidTemplate { message }) => message,
+            result => panic!("unexpected result: {result:?}"),
+        };
+        assert!(message("{id} {unknown}").starts_with("unknown field `unknown`"));
+        assert_eq!(message("{id"), "unclosed `{`");
+        assert_eq!(message("id}"), "unmatched `}`");
+    }
+
+    #[test]
+    fn test_printer() {
+        let options = OutputOptions::default();
+        assert!(options.printer::<ClipRecord>().unwrap().is_none());
+        let options = OutputOptions { format: OutputFormat::Template, template: None };
+        assert!(matches!(options.printer::<ClipRecord>(), Err(Error::MissingTemplate)));
+        // `--format` implies `--output template`
+        let output = printer(OutputFormat::Text, Some("{id}")).render_list(&records());
+        assert_eq!(output, "00000000000000ab\n00000000000000cd\n");
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a\tb\nc\r\n"), "a\\tb\\nc\\r\\n");
+        assert_eq!(escape("clipcat"), "clipcat");
+    }
+
+    #[test]
+    fn test_field() {
+        let records = records();
+        assert_eq!(records[0].field("id").as_deref(), Some("00000000000000ab"));
+        assert_eq!(records[0].field("pinned").as_deref(), Some("true"));
+        assert_eq!(records[1].field("preview").as_deref(), Some("a\tb\nc"));
+        assert_eq!(records[0].field("unknown"), None);
+    }
+
+    #[test]
+    fn test_tsv() {
+        let output = printer(OutputFormat::Tsv, None).render_list(&records());
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        let columns = lines[1].split('\t').collect::<Vec<_>>();
+        assert_eq!(columns.len(), ClipRecord::FIELDS.len());
+        assert_eq!(columns[0], "00000000000000cd");
+        assert_eq!(columns[7], "a\\tb\\nc");
+
+        assert_eq!(printer(OutputFormat::Tsv, None).render_list(&[]), "");
+    }
+
+    #[test]
+    fn test_json() {
+        let records = records();
+
+        let output = printer(OutputFormat::Json, None).render_list(&records);
+        let value = serde_json::from_str::<serde_json::Value>(&output).unwrap();
+        let array = value.as_array().unwrap();
+        assert_eq!(array.len(), 2);
+        assert_eq!(
+            array[0],
+            serde_json::json!({
+                "id": "00000000000000ab",
+                "kind": "primary",
+                "mime": "text/plain",
+                "timestamp": "1970-01-01T00:00:00Z",
+                "size": 7,
+                "pinned": true,
+                "source": "history",
+                "preview": "clipcat",
+            })
+        );
+
+        // a single record is an object rather than an array
+        let output = printer(OutputFormat::Json, None).render_one(&records[1]);
+        let value = serde_json::from_str::<serde_json::Value>(&output).unwrap();
+        assert_eq!(value["preview"], "a\tb\nc");
+
+        let output = printer(OutputFormat::Ndjson, None).render_list(&records);
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        for (line, record) in lines.into_iter().zip(&records) {
+            let value = serde_json::from_str::<serde_json::Value>(line).unwrap();
+            assert!(value.is_object());
+            assert_eq!(value["id"], record.id);
+        }
+    }
+}
@@ -1,12 +1,20 @@
 use std::{io::Write, net::IpAddr, path::PathBuf, time::Duration};
 
 use clap::{CommandFactory, Parser, Subcommand};
+use clipcat_cli::check::{to_redacted_toml, Report};
 use snafu::ResultExt;
 use tokio::runtime::Runtime;
 
-use crate::{config::Config, error, error::Error, pid_file::PidFile, shadow};
+use crate::{
+    config::Config,
+    error,
+    error::Error,
+    pid_file::PidFile,
+    search_provider::{self, SearchProvider},
+    shadow,
+};
 
-#[derive(Parser)]
+#[derive(Clone, Parser)]
 #[command(
     name = clipcat_base::DAEMON_PROGRAM_NAME,
     author,
@@ -64,6 +72,20 @@ pub enum Commands {
 
     #[clap(about = "Output default configuration")]
     DefaultConfig,
+
+    #[clap(about = "Check configuration file and output the effective configuration with \
+                    secrets redacted")]
+    CheckConfig,
+
+    #[clap(about = "Install the GNOME Shell search provider for clipboard history")]
+    InstallSearchProvider {
+        #[clap(
+            long = "data-dir",
+            help = "Specify the data directory, `/usr/local/share` is used if this value is \
+                    omitted"
+        )]
+        data_dir: Option<PathBuf>,
+    },
 }
 
 impl Default for Cli {
@@ -94,15 +116,69 @@ impl Cli {
                     .expect("failed to write to stdout");
                 Ok(())
             }
+            Some(Commands::CheckConfig) => self.check_config(),
+            Some(Commands::InstallSearchProvider { ref data_dir }) => {
+                let config = self.load_config()?;
+                let data_dir = data_dir
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from(search_provider::DEFAULT_DATA_DIR));
+                let search_provider = SearchProvider::new(config.dbus.identifier.as_deref());
+                for path in search_provider.install(&data_dir)? {
+                    println!("Installed `{}`", path.display());
+                }
+                Ok(())
+            }
             None => {
                 let config = self.load_config()?;
-                run_clipcatd(config, self.replace)
+                let config_loader = self.config_loader(config.watch_config_file);
+                run_clipcatd(config, config_loader, self.replace)
+            }
+        }
+    }
+
+    fn check_config(&self) -> Result<(), Error> {
+        let file_path = self.config_file_path();
+        let mut report = Report::new(&file_path);
+        if let Some(config) = report.parse::<Config>(&Config::KEY_ALIASES) {
+            config.check_file_contents(&mut report);
+            match self.load_config() {
+                Ok(config) => {
+                    config.check(&mut report);
+                    std::io::stdout()
+                        .write_all(to_redacted_toml(&config).as_bytes())
+                        .expect("failed to write to stdout");
+                }
+                Err(err) => report.error("", err),
             }
         }
+        report.print();
+
+        if report.has_errors() {
+            Err(Error::InvalidConfig { file_path })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn config_file_path(&self) -> PathBuf {
+        self.config_file.clone().unwrap_or_else(Config::search_config_file_path)
+    }
+
+    // the configuration is reloaded with the same command line arguments
+    fn config_loader(&self, watch_config_file: bool) -> clipcat_server::ConfigLoader {
+        let cli = self.clone();
+        let config_loader = clipcat_server::ConfigLoader::new(move || {
+            cli.load_config().map(clipcat_server::Config::from).map_err(|err| err.to_string())
+        });
+        if watch_config_file {
+            config_loader.with_watch_file_path(self.config_file_path())
+        } else {
+            config_loader
+        }
     }
 
     fn load_config(&self) -> Result<Config, Error> {
-        let config_file = &self.config_file.clone().unwrap_or_else(Config::search_config_file_path);
+        let config_file = &self.config_file_path();
         let mut config = Config::load(config_file)?;
 
         config.daemonize = !self.no_daemon;
@@ -139,7 +215,11 @@ impl Cli {
 }
 
 #[allow(clippy::cognitive_complexity)]
-fn run_clipcatd(config: Config, replace: bool) -> Result<(), Error> {
+fn run_clipcatd(
+    config: Config,
+    config_loader: clipcat_server::ConfigLoader,
+    replace: bool,
+) -> Result<(), Error> {
     let pid_file = PidFile::from(config.pid_file.clone());
     if pid_file.exists() {
         let pid = pid_file.try_load()?;
@@ -175,6 +255,9 @@ fn run_clipcatd(config: Config, replace: bool) -> Result<(), Error> {
         pid_file.create()?;
     }
 
+    // before the Tokio runtime spawns its threads
+    clipcat_server::take_systemd_environment();
+
     let config = clipcat_server::Config::from(config);
 
     tracing::info!(
@@ -186,9 +269,9 @@ fn run_clipcatd(config: Config, replace: bool) -> Result<(), Error> {
     tracing::info!("Initializing Tokio runtime");
 
     let exit_status = match Runtime::new().context(error::InitializeTokioRuntimeSnafu) {
-        Ok(runtime) => {
-            runtime.block_on(clipcat_server::serve_with_shutdown(config)).map_err(Error::from)
-        }
+        Ok(runtime) => runtime
+            .block_on(clipcat_server::serve_with_shutdown(config, Some(config_loader)))
+            .map_err(Error::from),
         Err(err) => Err(err),
     };
 
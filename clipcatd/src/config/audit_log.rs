@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AuditLogConfig {
+    #[serde(default = "AuditLogConfig::default_enable")]
+    pub enable: bool,
+
+    #[serde(default = "AuditLogConfig::default_file_path")]
+    pub file_path: PathBuf,
+
+    #[serde(default = "AuditLogConfig::default_max_file_size")]
+    pub max_file_size: u64,
+
+    #[serde(default = "AuditLogConfig::default_max_files")]
+    pub max_files: usize,
+}
+
+impl AuditLogConfig {
+    #[inline]
+    pub const fn default_enable() -> bool { false }
+
+    #[inline]
+    pub fn default_file_path() -> PathBuf {
+        let base_dirs = BaseDirs::new().expect("`BaseDirs::new` always success");
+        [
+            PathBuf::from(base_dirs.data_local_dir()),
+            PathBuf::from(clipcat_base::PROJECT_NAME),
+            PathBuf::from(clipcat_base::DAEMON_AUDIT_LOG_FILE_NAME),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[inline]
+    pub const fn default_max_file_size() -> u64 { 10 * 1024 * 1024 }
+
+    #[inline]
+    pub const fn default_max_files() -> usize { 5 }
+}
+
+impl Default for AuditLogConfig {
+    fn default() -> Self {
+        Self {
+            enable: Self::default_enable(),
+            file_path: Self::default_file_path(),
+            max_file_size: Self::default_max_file_size(),
+            max_files: Self::default_max_files(),
+        }
+    }
+}
+
+impl From<AuditLogConfig> for Option<clipcat_server::config::AuditLogConfig> {
+    fn from(config: AuditLogConfig) -> Self {
+        config.enable.then_some(clipcat_server::config::AuditLogConfig {
+            file_path: config.file_path,
+            max_file_size: config.max_file_size,
+            max_files: config.max_files,
+        })
+    }
+}
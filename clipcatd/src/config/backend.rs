@@ -0,0 +1,193 @@
+use std::{path::PathBuf, time::Duration};
+
+use clipcat_base::ClipboardKind;
+use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackendKind {
+    #[default]
+    Default,
+
+    Osc52,
+
+    File,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Osc52Config {
+    pub tty: Option<PathBuf>,
+
+    #[serde(default = "Osc52Config::default_tmux_passthrough")]
+    pub tmux_passthrough: bool,
+
+    #[serde(default = "Osc52Config::default_enable_query")]
+    pub enable_query: bool,
+
+    #[serde(default = "Osc52Config::default_query_timeout_ms")]
+    pub query_timeout_ms: u64,
+}
+
+impl Osc52Config {
+    #[inline]
+    pub fn default_tmux_passthrough() -> bool { std::env::var_os("TMUX").is_some() }
+
+    #[inline]
+    pub const fn default_enable_query() -> bool { false }
+
+    #[inline]
+    pub const fn default_query_timeout_ms() -> u64 { 500 }
+
+    /// Detects the terminal `clipcatd` is started from, it must be resolved
+    /// before `clipcatd` detaches from its controlling terminal.
+    pub fn detect_tty() -> PathBuf {
+        if let Some(tty) = std::env::var_os("SSH_TTY") {
+            return PathBuf::from(tty);
+        }
+
+        std::fs::read_link("/proc/self/fd/0")
+            .ok()
+            .filter(|path| path.starts_with("/dev/pts") || path.starts_with("/dev/tty"))
+            .unwrap_or_else(|| PathBuf::from("/dev/tty"))
+    }
+}
+
+impl Default for Osc52Config {
+    fn default() -> Self {
+        Self {
+            tty: None,
+            tmux_passthrough: Self::default_tmux_passthrough(),
+            enable_query: Self::default_enable_query(),
+            query_timeout_ms: Self::default_query_timeout_ms(),
+        }
+    }
+}
+
+impl From<Osc52Config> for clipcat_server::config::Osc52Config {
+    fn from(
+        Osc52Config { tty, tmux_passthrough, enable_query, query_timeout_ms }: Osc52Config,
+    ) -> Self {
+        Self {
+            tty: tty.unwrap_or_else(Osc52Config::detect_tty),
+            tmux_passthrough,
+            enable_query,
+            query_timeout: Duration::from_millis(query_timeout_ms),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TmuxConfig {
+    #[serde(default = "TmuxConfig::default_enable")]
+    pub enable: bool,
+
+    pub socket_path: Option<PathBuf>,
+
+    #[serde(
+        default = "TmuxConfig::default_clipboard_kind",
+        with = "clipcat_base::serde::clipboard_kind"
+    )]
+    pub clipboard_kind: ClipboardKind,
+
+    #[serde(default = "TmuxConfig::default_polling_interval_ms")]
+    pub polling_interval_ms: u64,
+
+    #[serde(default = "TmuxConfig::default_set_buffer_on_mark")]
+    pub set_buffer_on_mark: bool,
+}
+
+impl TmuxConfig {
+    #[inline]
+    pub const fn default_enable() -> bool { false }
+
+    #[inline]
+    pub const fn default_clipboard_kind() -> ClipboardKind { ClipboardKind::Clipboard }
+
+    #[inline]
+    pub const fn default_polling_interval_ms() -> u64 { 500 }
+
+    #[inline]
+    pub const fn default_set_buffer_on_mark() -> bool { true }
+}
+
+impl Default for TmuxConfig {
+    fn default() -> Self {
+        Self {
+            enable: Self::default_enable(),
+            socket_path: None,
+            clipboard_kind: Self::default_clipboard_kind(),
+            polling_interval_ms: Self::default_polling_interval_ms(),
+            set_buffer_on_mark: Self::default_set_buffer_on_mark(),
+        }
+    }
+}
+
+impl From<TmuxConfig> for clipcat_server::config::TmuxConfig {
+    fn from(
+        TmuxConfig {
+            enable,
+            socket_path,
+            clipboard_kind,
+            polling_interval_ms,
+            set_buffer_on_mark,
+        }: TmuxConfig,
+    ) -> Self {
+        Self {
+            enable,
+            socket_path,
+            clipboard_kind,
+            polling_interval: Duration::from_millis(polling_interval_ms),
+            set_buffer_on_store: set_buffer_on_mark,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct FileBackendConfig {
+    #[serde(default = "FileBackendConfig::default_path")]
+    pub path: PathBuf,
+
+    pub output_directory: Option<PathBuf>,
+
+    #[serde(
+        default = "FileBackendConfig::default_clipboard_kind",
+        with = "clipcat_base::serde::clipboard_kind"
+    )]
+    pub clipboard_kind: ClipboardKind,
+}
+
+impl FileBackendConfig {
+    #[inline]
+    pub fn default_path() -> PathBuf {
+        let base_dirs = BaseDirs::new().expect("`BaseDirs::new` always success");
+        [
+            base_dirs.runtime_dir().map_or_else(std::env::temp_dir, PathBuf::from),
+            PathBuf::from(clipcat_base::PROJECT_NAME),
+            PathBuf::from("clipboard"),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[inline]
+    pub const fn default_clipboard_kind() -> ClipboardKind { ClipboardKind::Clipboard }
+}
+
+impl Default for FileBackendConfig {
+    fn default() -> Self {
+        Self {
+            path: Self::default_path(),
+            output_directory: None,
+            clipboard_kind: Self::default_clipboard_kind(),
+        }
+    }
+}
+
+impl From<FileBackendConfig> for clipcat_server::config::FileBackendConfig {
+    fn from(
+        FileBackendConfig { path, output_directory, clipboard_kind }: FileBackendConfig,
+    ) -> Self {
+        Self { path, output_directory, clipboard_kind }
+    }
+}
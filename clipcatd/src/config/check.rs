@@ -0,0 +1,385 @@
+use std::{collections::HashSet, net::SocketAddr, os::unix::fs::FileTypeExt, path::Path};
+
+use clipcat_cli::check::Report;
+
+use crate::config::{
+    backend::BackendKind, synchronization::SynchronizationRuleConfig, Config, SnippetConfig,
+};
+
+impl Config {
+    /// Aliases of keys at the top level.
+    pub const KEY_ALIASES: [(&'static str, &'static str); 1] = [("monitor", "watcher")];
+
+    /// Reports deprecated keys and options which are overridden while loading,
+    /// `self` is the configuration as it is written in the file.
+    pub fn check_file_contents(&self, report: &mut Report) {
+        if self.primary_threshold_ms.is_some() {
+            report.warning(
+                "primary_threshold_ms",
+                "deprecated and ignored, use `watcher.primary_debounce_ms` instead",
+            );
+        }
+        if self.synchronize_selection_with_clipboard.is_some() {
+            report.warning(
+                "synchronize_selection_with_clipboard",
+                "deprecated, use `synchronization.clipboard_to_primary.enable` instead",
+            );
+        }
+        if self.watcher.sensitive_x11_atoms.is_some() {
+            report.warning(
+                "watcher.sensitive_x11_atoms",
+                "deprecated, use `watcher.sensitive_mime_types` instead",
+            );
+        }
+        if !self.grpc.enable_http && !self.grpc.enable_local_socket {
+            report.warning(
+                "grpc",
+                "both `enable_http` and `enable_local_socket` are disabled, gRPC over local \
+                 socket is enabled anyway",
+            );
+        }
+    }
+
+    /// Reports invalid values, missing files and conflicting options of the
+    /// effective configuration.
+    pub fn check(&self, report: &mut Report) {
+        self.check_watcher(report);
+        self.check_synchronization(report);
+        self.check_grpc(report);
+        self.check_listen_addresses(report);
+        self.check_paths(report);
+    }
+
+    fn check_watcher(&self, report: &mut Report) {
+        let watcher = &self.watcher;
+        check_regex_patterns(
+            report,
+            "watcher.denied_text_regex_patterns",
+            &watcher.denied_text_regex_patterns,
+        );
+        check_length_range(
+            report,
+            "watcher",
+            watcher.filter_text_min_length,
+            watcher.filter_text_max_length,
+        );
+        if !watcher.enable_clipboard && !watcher.enable_primary && !watcher.enable_secondary {
+            report.warning("watcher", "no clipboard is watched");
+        }
+    }
+
+    fn check_synchronization(&self, report: &mut Report) {
+        let watcher = &self.watcher;
+        let rules = [
+            (
+                "clipboard_to_primary",
+                &self.synchronization.clipboard_to_primary,
+                watcher.enable_clipboard,
+            ),
+            (
+                "primary_to_clipboard",
+                &self.synchronization.primary_to_clipboard,
+                watcher.enable_primary,
+            ),
+            (
+                "clipboard_to_secondary",
+                &self.synchronization.clipboard_to_secondary,
+                watcher.enable_clipboard,
+            ),
+            (
+                "secondary_to_clipboard",
+                &self.synchronization.secondary_to_clipboard,
+                watcher.enable_secondary,
+            ),
+        ];
+        for (name, rule, is_source_watched) in rules {
+            let SynchronizationRuleConfig {
+                enable,
+                filter_text_min_length,
+                filter_text_max_length,
+                denied_text_regex_patterns,
+                ..
+            } = rule;
+            let key = format!("synchronization.{name}");
+            check_regex_patterns(
+                report,
+                &format!("{key}.denied_text_regex_patterns"),
+                denied_text_regex_patterns,
+            );
+            check_length_range(report, &key, *filter_text_min_length, *filter_text_max_length);
+            if *enable && !is_source_watched {
+                report.warning(key, "the source selection is not watched, nothing is synchronized");
+            }
+        }
+    }
+
+    fn check_grpc(&self, report: &mut Report) {
+        let grpc = &self.grpc;
+        if let Some(ref file_path) = grpc.access_token_file_path {
+            report.check_file("grpc.access_token_file_path", file_path);
+        }
+        for (index, token) in grpc.scoped_access_tokens.iter().enumerate() {
+            let key = format!("grpc.scoped_access_tokens[{index}]");
+            let has_token_file = token.token_file_path.as_deref().is_some_and(Path::is_file);
+            if let Some(ref file_path) = token.token_file_path {
+                if !has_token_file {
+                    report.warning(
+                        format!("{key}.token_file_path"),
+                        format_args!("file `{}` does not exist", file_path.display()),
+                    );
+                }
+            }
+            if !has_token_file && token.token.as_deref().map_or(true, str::is_empty) {
+                report.warning(
+                    key,
+                    format_args!("access token `{}` has no value, it is ignored", token.name),
+                );
+            }
+        }
+        for (index, path) in grpc.local_socket_allowed_executables.iter().enumerate() {
+            report.check_file(format!("grpc.local_socket_allowed_executables[{index}]"), path);
+        }
+        if let Some(ref tls) = grpc.tls {
+            report.check_file("grpc.tls.certificate_file_path", &tls.certificate_file_path);
+            report.check_file("grpc.tls.key_file_path", &tls.key_file_path);
+            if let Some(ref file_path) = tls.client_ca_file_path {
+                report.check_file("grpc.tls.client_ca_file_path", file_path);
+            }
+            if !grpc.enable_http {
+                report.warning("grpc.tls", "ignored as `grpc.enable_http` is disabled");
+            }
+        }
+        if grpc.enable_local_socket
+            && self.http_api.enable_local_socket
+            && grpc.local_socket == self.http_api.local_socket
+        {
+            report.error(
+                "http_api.local_socket",
+                "conflicts with `grpc.local_socket`, the same socket could not be shared",
+            );
+        }
+    }
+
+    fn check_listen_addresses(&self, report: &mut Report) {
+        let listen_addresses: [(&str, bool, SocketAddr); 4] = [
+            ("grpc", self.grpc.enable_http, self.grpc.socket_address()),
+            ("web_ui", self.web_ui.enable, self.web_ui.socket_address()),
+            ("http_api", self.http_api.enable_http, self.http_api.socket_address()),
+            ("metrics", self.metrics.enable, self.metrics.socket_address()),
+        ];
+        let mut seen = Vec::<(&str, SocketAddr)>::new();
+        for (name, enable, listen_address) in listen_addresses {
+            if !enable {
+                continue;
+            }
+            if let Some((other, _)) = seen.iter().find(|(_, other)| *other == listen_address) {
+                report.error(
+                    name,
+                    format_args!("listen address {listen_address} conflicts with `{other}`"),
+                );
+            }
+            seen.push((name, listen_address));
+        }
+
+        // the same check is done by `clipcat_server` while starting
+        let has_access_token = self.grpc.access_token.is_some()
+            || self.grpc.access_token_file_path.is_some()
+            || !self.grpc.scoped_access_tokens.is_empty();
+        let http_servers = [
+            ("web_ui", self.web_ui.enable, self.web_ui.socket_address()),
+            ("http_api", self.http_api.enable_http, self.http_api.socket_address()),
+        ];
+        for (name, enable, listen_address) in http_servers {
+            if enable && !has_access_token && !listen_address.ip().is_loopback() {
+                report.error(
+                    format!("{name}.host"),
+                    format_args!(
+                        "{listen_address} is not a loopback address, an access token of `grpc` is \
+                         required"
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_notifications(&self, report: &mut Report) {
+        let notifications = &self.notifications;
+        if notifications.event_log.enable {
+            report.check_writable_file(
+                "notifications.event_log.file_path",
+                &notifications.event_log.file_path,
+            );
+        }
+        if notifications.command.enable {
+            if notifications.command.program.is_empty() {
+                report.error("notifications.command.program", "no program is specified");
+            } else {
+                report
+                    .check_program("notifications.command.program", &notifications.command.program);
+            }
+        }
+        if notifications.bell.enable {
+            match notifications.bell.tty {
+                Some(ref tty) if !tty.exists() => report.error(
+                    "notifications.bell.tty",
+                    format_args!("`{}` does not exist", tty.display()),
+                ),
+                Some(_) => {}
+                None => report.error("notifications.bell.tty", "no terminal is specified"),
+            }
+        }
+    }
+
+    fn check_paths(&self, report: &mut Report) {
+        report.check_writable_file("history_file_path", &self.history_file_path);
+        report.check_writable_file("pid_file", &self.pid_file);
+        if let Some(ref file_path) = self.log.file_path {
+            report.check_writable_file("log.file_path", file_path);
+        }
+        if self.audit_log.enable {
+            report.check_writable_file("audit_log.file_path", &self.audit_log.file_path);
+        }
+        self.check_notifications(report);
+
+        match self.backend {
+            BackendKind::Default => {}
+            BackendKind::Osc52 => {
+                if let Some(ref tty) = self.osc52.tty {
+                    if !tty.exists() {
+                        report
+                            .error("osc52.tty", format_args!("`{}` does not exist", tty.display()));
+                    }
+                }
+            }
+            BackendKind::File => {
+                // the directory is created if it does not exist
+                let is_supported = std::fs::metadata(&self.file.path)
+                    .map_or(true, |metadata| metadata.is_dir() || metadata.file_type().is_fifo());
+                if !is_supported {
+                    report.error(
+                        "file.path",
+                        format_args!(
+                            "`{}` is neither a directory nor a named pipe",
+                            self.file.path.display()
+                        ),
+                    );
+                }
+            }
+        }
+
+        for (index, snippet) in self.snippets.iter().enumerate() {
+            let key = format!("snippets[{index}]");
+            match snippet {
+                SnippetConfig::Text { name, content } if content.is_empty() => {
+                    report.warning(key, format_args!("snippet `{name}` is empty, it is ignored"));
+                }
+                SnippetConfig::Text { .. } => {}
+                SnippetConfig::File { path, .. } => report.check_file(key, path),
+                SnippetConfig::Directory { path, .. } => report.check_directory(key, path),
+            }
+        }
+    }
+}
+
+fn check_regex_patterns(report: &mut Report, key: &str, patterns: &HashSet<String>) {
+    let mut patterns = patterns.iter().collect::<Vec<_>>();
+    patterns.sort_unstable();
+    for pattern in patterns {
+        if let Err(err) = regex::Regex::new(pattern) {
+            report.error(key, format_args!("invalid regular expression `{pattern}`, {err}"));
+        }
+    }
+}
+
+fn check_length_range(report: &mut Report, key: &str, min_length: usize, max_length: usize) {
+    if min_length > max_length {
+        report.error(
+            format!("{key}.filter_text_min_length"),
+            format_args!(
+                "{min_length} is greater than `filter_text_max_length` ({max_length}), all text \
+                 is filtered out"
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clipcat_cli::check::{to_redacted_toml, Report, Severity};
+
+    use crate::config::Config;
+
+    fn check(data: &str) -> (Option<Config>, Report) {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), data).unwrap();
+        let mut report = Report::new(file.path());
+        let config = report.parse::<Config>(&Config::KEY_ALIASES);
+        if let Some(ref config) = config {
+            config.check_file_contents(&mut report);
+        }
+        (config, report)
+    }
+
+    #[test]
+    fn test_check_file_contents() {
+        let (config, report) = check(
+            r#"
+daemonize = false
+primary_threshold_ms = 5000
+
+[monitor]
+enable_clipbaord = true
+
+[grpc]
+access_token = "secret"
+"#,
+        );
+        let config = config.unwrap();
+        let issues = report
+            .issues()
+            .iter()
+            .map(|issue| (issue.severity, issue.key.as_str(), issue.message.as_str()))
+            .collect::<Vec<_>>();
+        // keys under an alias are checked against the aliased table
+        assert!(issues.contains(&(
+            Severity::Error,
+            "monitor.enable_clipbaord",
+            "unknown key, did you mean `enable_clipboard`?"
+        )));
+        assert!(issues
+            .iter()
+            .any(|(severity, key, _)| *severity == Severity::Warning
+                && *key == "primary_threshold_ms"));
+
+        let data = to_redacted_toml(&config);
+        assert!(!data.contains("secret"));
+        assert!(data.contains("access_token = \"<redacted>\""));
+    }
+
+    #[test]
+    fn test_check_unprotected_http_servers() {
+        let data = r#"
+daemonize = false
+
+[web_ui]
+enable = true
+host = "0.0.0.0"
+
+[http_api]
+enable_http = true
+"#;
+        let host_errors = |data: &str| {
+            let (config, mut report) = check(data);
+            config.unwrap().check(&mut report);
+            report
+                .issues()
+                .iter()
+                .filter(|issue| issue.severity == Severity::Error && issue.key.contains("host"))
+                .map(|issue| issue.key.clone())
+                .collect::<Vec<_>>()
+        };
+        // the JSON HTTP API listens on a loopback address
+        assert_eq!(host_errors(data), ["web_ui.host"]);
+        assert!(host_errors(&format!("{data}\n[grpc]\naccess_token = \"secret\"\n")).is_empty());
+    }
+}
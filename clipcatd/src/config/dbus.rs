@@ -6,6 +6,9 @@ pub struct DBusConfig {
     pub enable: bool,
 
     pub identifier: Option<String>,
+
+    #[serde(default)]
+    pub klipper_compatible: bool,
 }
 
 impl DBusConfig {
@@ -14,9 +17,13 @@ impl DBusConfig {
 }
 
 impl Default for DBusConfig {
-    fn default() -> Self { Self { enable: Self::default_enable(), identifier: None } }
+    fn default() -> Self {
+        Self { enable: Self::default_enable(), identifier: None, klipper_compatible: false }
+    }
 }
 
 impl From<DBusConfig> for clipcat_server::config::DBusConfig {
-    fn from(DBusConfig { enable, identifier }: DBusConfig) -> Self { Self { enable, identifier } }
+    fn from(DBusConfig { enable, identifier, klipper_compatible }: DBusConfig) -> Self {
+        Self { enable, identifier, klipper_compatible }
+    }
 }
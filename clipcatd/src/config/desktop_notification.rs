@@ -17,6 +17,28 @@ pub struct DesktopNotificationConfig {
 
     #[serde(default = "DesktopNotificationConfig::default_long_plaintext_length")]
     pub long_plaintext_length: usize,
+
+    /// Maximum number of characters of the preview of new plaintext, the
+    /// preview is omitted if it is 0.
+    #[serde(default = "DesktopNotificationConfig::default_preview_length")]
+    pub preview_length: usize,
+
+    /// Maximum width and height of the thumbnail of new images, the thumbnail
+    /// is omitted if it is 0.
+    #[serde(default = "DesktopNotificationConfig::default_thumbnail_size")]
+    pub thumbnail_size: u32,
+
+    /// Shows "Undo capture", "Pin", "Remove" and "Pause watcher" buttons on
+    /// notifications of new clips.
+    #[serde(default = "DesktopNotificationConfig::default_enable_actions")]
+    pub enable_actions: bool,
+
+    /// Clips fetched within this interval are notified in one notification.
+    #[serde(default = "DesktopNotificationConfig::default_coalesce_interval_ms")]
+    pub coalesce_interval_ms: u64,
+
+    #[serde(default)]
+    pub events: NotificationEventsConfig,
 }
 
 impl DesktopNotificationConfig {
@@ -28,6 +50,14 @@ impl DesktopNotificationConfig {
 
     pub const fn default_long_plaintext_length() -> usize { 2000 }
 
+    pub const fn default_preview_length() -> usize { 100 }
+
+    pub const fn default_thumbnail_size() -> u32 { 128 }
+
+    pub const fn default_enable_actions() -> bool { true }
+
+    pub const fn default_coalesce_interval_ms() -> u64 { 500 }
+
     pub fn search_icon(&self) -> PathBuf {
         let icon_path = PathBuf::from(&self.icon);
         if icon_path.exists() {
@@ -65,6 +95,11 @@ impl Default for DesktopNotificationConfig {
             icon: Self::default_icon(),
             timeout_ms: Self::default_timeout_ms(),
             long_plaintext_length: Self::default_long_plaintext_length(),
+            preview_length: Self::default_preview_length(),
+            thumbnail_size: Self::default_thumbnail_size(),
+            enable_actions: Self::default_enable_actions(),
+            coalesce_interval_ms: Self::default_coalesce_interval_ms(),
+            events: NotificationEventsConfig::default(),
         }
     }
 }
@@ -72,8 +107,95 @@ impl Default for DesktopNotificationConfig {
 impl From<DesktopNotificationConfig> for clipcat_server::config::DesktopNotificationConfig {
     fn from(config: DesktopNotificationConfig) -> Self {
         let icon = config.search_icon();
-        let DesktopNotificationConfig { enable, timeout_ms, long_plaintext_length, .. } = config;
+        let DesktopNotificationConfig {
+            enable,
+            timeout_ms,
+            long_plaintext_length,
+            preview_length,
+            thumbnail_size,
+            enable_actions,
+            coalesce_interval_ms,
+            events,
+            ..
+        } = config;
+
+        Self {
+            enable,
+            icon,
+            timeout: Duration::from_millis(timeout_ms),
+            long_plaintext_length,
+            preview_length,
+            thumbnail_size,
+            enable_actions,
+            coalesce_interval: Duration::from_millis(coalesce_interval_ms),
+            events: events.into(),
+        }
+    }
+}
+
+/// Kinds of events notified with desktop notifications and notification sinks.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct NotificationEventsConfig {
+    /// The daemon is started or shutting down.
+    #[serde(default = "NotificationEventsConfig::default_enable")]
+    pub daemon_status: bool,
+
+    #[serde(default = "NotificationEventsConfig::default_enable")]
+    pub history_cleared: bool,
 
-        Self { enable, icon, timeout: Duration::from_millis(timeout_ms), long_plaintext_length }
+    /// The watcher is enabled or disabled.
+    #[serde(default = "NotificationEventsConfig::default_enable")]
+    pub watcher_toggled: bool,
+
+    /// Connected to X11 or Wayland server.
+    #[serde(default = "NotificationEventsConfig::default_enable")]
+    pub backend_connected: bool,
+
+    #[serde(default = "NotificationEventsConfig::default_enable")]
+    pub image_fetched: bool,
+
+    /// Only plaintext longer than `long_plaintext_length` is notified with
+    /// desktop notifications.
+    #[serde(default = "NotificationEventsConfig::default_enable")]
+    pub plaintext_fetched: bool,
+}
+
+impl NotificationEventsConfig {
+    pub const fn default_enable() -> bool { true }
+}
+
+impl Default for NotificationEventsConfig {
+    fn default() -> Self {
+        Self {
+            daemon_status: Self::default_enable(),
+            history_cleared: Self::default_enable(),
+            watcher_toggled: Self::default_enable(),
+            backend_connected: Self::default_enable(),
+            image_fetched: Self::default_enable(),
+            plaintext_fetched: Self::default_enable(),
+        }
+    }
+}
+
+impl From<NotificationEventsConfig> for clipcat_server::config::NotificationEvents {
+    fn from(
+        NotificationEventsConfig {
+            daemon_status,
+            history_cleared,
+            watcher_toggled,
+            backend_connected,
+            image_fetched,
+            plaintext_fetched,
+        }: NotificationEventsConfig,
+    ) -> Self {
+        Self {
+            daemon_status,
+            history_cleared,
+            watcher_toggled,
+            backend_connected,
+            image_fetched,
+            plaintext_fetched,
+        }
     }
 }
@@ -1,8 +1,10 @@
 use std::{
+    collections::HashSet,
     net::{IpAddr, SocketAddr},
     path::PathBuf,
 };
 
+use clipcat_base::AccessScope;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -22,11 +24,23 @@ pub struct GrpcConfig {
     #[serde(default = "clipcat_base::config::default_unix_domain_socket")]
     pub local_socket: PathBuf,
 
+    #[serde(default)]
+    pub local_socket_allowed_uids: Vec<u32>,
+
+    #[serde(default)]
+    pub local_socket_allowed_executables: Vec<PathBuf>,
+
     #[serde(default = "GrpcConfig::default_access_token")]
     pub access_token: Option<String>,
 
     #[serde(default = "GrpcConfig::default_access_token_file_path")]
     pub access_token_file_path: Option<PathBuf>,
+
+    #[serde(default)]
+    pub scoped_access_tokens: Vec<ScopedAccessTokenConfig>,
+
+    #[serde(default)]
+    pub tls: Option<GrpcTlsConfig>,
 }
 
 impl GrpcConfig {
@@ -60,8 +74,61 @@ impl Default for GrpcConfig {
             host: Self::default_host(),
             port: Self::default_port(),
             local_socket: clipcat_base::config::default_unix_domain_socket(),
+            local_socket_allowed_uids: Vec::new(),
+            local_socket_allowed_executables: Vec::new(),
             access_token: Self::default_access_token(),
             access_token_file_path: Self::default_access_token_file_path(),
+            scoped_access_tokens: Vec::new(),
+            tls: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ScopedAccessTokenConfig {
+    pub name: String,
+
+    #[serde(default)]
+    pub token: Option<String>,
+
+    #[serde(default)]
+    pub token_file_path: Option<PathBuf>,
+
+    pub scopes: HashSet<AccessScope>,
+}
+
+impl ScopedAccessTokenConfig {
+    /// Reads the token from `token_file_path` if it is readable, falls back to
+    /// `token`.
+    pub fn into_scoped_access_token(self) -> Option<clipcat_server::config::ScopedAccessToken> {
+        let Self { name, token, token_file_path, scopes } = self;
+        let token = token_file_path
+            .and_then(|file_path| std::fs::read_to_string(file_path).ok())
+            .map(|token| token.trim_end().to_string())
+            .or(token)
+            .filter(|token| !token.is_empty());
+        if token.is_none() {
+            tracing::warn!("Access token `{name}` has no value, it is ignored");
         }
+        Some(clipcat_server::config::ScopedAccessToken { name, token: token?, scopes })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[allow(clippy::struct_field_names)]
+pub struct GrpcTlsConfig {
+    pub certificate_file_path: PathBuf,
+
+    pub key_file_path: PathBuf,
+
+    #[serde(default)]
+    pub client_ca_file_path: Option<PathBuf>,
+}
+
+impl From<GrpcTlsConfig> for clipcat_server::config::GrpcTlsConfig {
+    fn from(
+        GrpcTlsConfig { certificate_file_path, key_file_path, client_ca_file_path }: GrpcTlsConfig,
+    ) -> Self {
+        Self { certificate_file_path, key_file_path, client_ca_file_path }
     }
 }
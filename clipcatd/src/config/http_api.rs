@@ -0,0 +1,62 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HttpApiConfig {
+    #[serde(default = "HttpApiConfig::default_enable_http")]
+    pub enable_http: bool,
+
+    #[serde(default = "HttpApiConfig::default_enable_local_socket")]
+    pub enable_local_socket: bool,
+
+    #[serde(default = "HttpApiConfig::default_host")]
+    pub host: IpAddr,
+
+    #[serde(default = "HttpApiConfig::default_port")]
+    pub port: u16,
+
+    #[serde(default = "clipcat_base::config::default_http_api_unix_domain_socket")]
+    pub local_socket: PathBuf,
+}
+
+impl HttpApiConfig {
+    #[inline]
+    pub const fn socket_address(&self) -> SocketAddr { SocketAddr::new(self.host, self.port) }
+
+    #[inline]
+    pub const fn default_enable_http() -> bool { false }
+
+    #[inline]
+    pub const fn default_enable_local_socket() -> bool { false }
+
+    #[inline]
+    pub const fn default_host() -> IpAddr { clipcat_base::DEFAULT_HTTP_API_HOST }
+
+    #[inline]
+    pub const fn default_port() -> u16 { clipcat_base::DEFAULT_HTTP_API_PORT }
+}
+
+impl Default for HttpApiConfig {
+    fn default() -> Self {
+        Self {
+            enable_http: Self::default_enable_http(),
+            enable_local_socket: Self::default_enable_local_socket(),
+            host: Self::default_host(),
+            port: Self::default_port(),
+            local_socket: clipcat_base::config::default_http_api_unix_domain_socket(),
+        }
+    }
+}
+
+impl From<HttpApiConfig> for clipcat_server::config::HttpApiConfig {
+    fn from(config: HttpApiConfig) -> Self {
+        Self {
+            listen_address: config.enable_http.then_some(config.socket_address()),
+            local_socket: config.enable_local_socket.then_some(config.local_socket),
+        }
+    }
+}
@@ -1,10 +1,17 @@
+mod audit_log;
+mod backend;
+mod check;
 mod dbus;
 mod desktop_notification;
 mod error;
 mod grpc;
+mod http_api;
 mod metrics;
+mod notifications;
 mod snippet;
+mod synchronization;
 mod watcher;
+mod web_ui;
 
 use std::path::{Path, PathBuf};
 
@@ -15,8 +22,18 @@ use snafu::ResultExt;
 
 pub use self::error::Error;
 use self::{
-    dbus::DBusConfig, desktop_notification::DesktopNotificationConfig, grpc::GrpcConfig,
-    metrics::MetricsConfig, snippet::SnippetConfig, watcher::WatcherConfig,
+    audit_log::AuditLogConfig,
+    backend::{BackendKind, FileBackendConfig, Osc52Config, TmuxConfig},
+    dbus::DBusConfig,
+    desktop_notification::DesktopNotificationConfig,
+    grpc::{GrpcConfig, ScopedAccessTokenConfig},
+    http_api::HttpApiConfig,
+    metrics::MetricsConfig,
+    notifications::NotificationsConfig,
+    snippet::SnippetConfig,
+    synchronization::SynchronizationConfig,
+    watcher::WatcherConfig,
+    web_ui::WebUiConfig,
 };
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -26,14 +43,19 @@ pub struct Config {
     #[serde(default = "Config::default_pid_file_path")]
     pub pid_file: PathBuf,
 
-    #[serde(default = "Config::default_primary_threshold_ms")]
-    pub primary_threshold_ms: i64,
+    /// Reloads the configuration whenever the configuration file is changed.
+    #[serde(default)]
+    pub watch_config_file: bool,
+
+    pub primary_threshold_ms: Option<i64>,
 
     #[serde(default = "Config::default_max_history")]
     pub max_history: usize,
 
-    #[serde(default = "Config::default_synchronize_selection_with_clipboard")]
-    pub synchronize_selection_with_clipboard: bool,
+    #[serde(default)]
+    pub backend: BackendKind,
+
+    pub synchronize_selection_with_clipboard: Option<bool>,
 
     #[serde(default = "Config::default_history_file_path")]
     pub history_file_path: PathBuf,
@@ -47,15 +69,39 @@ pub struct Config {
     #[serde(default)]
     pub grpc: GrpcConfig,
 
+    #[serde(default)]
+    pub synchronization: SynchronizationConfig,
+
     #[serde(default)]
     pub dbus: DBusConfig,
 
     #[serde(default)]
     pub metrics: MetricsConfig,
 
+    #[serde(default)]
+    pub web_ui: WebUiConfig,
+
+    #[serde(default)]
+    pub http_api: HttpApiConfig,
+
+    #[serde(default)]
+    pub audit_log: AuditLogConfig,
+
     #[serde(default)]
     pub desktop_notification: DesktopNotificationConfig,
 
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+
+    #[serde(default)]
+    pub osc52: Osc52Config,
+
+    #[serde(default)]
+    pub tmux: TmuxConfig,
+
+    #[serde(default)]
+    pub file: FileBackendConfig,
+
     #[serde(default)]
     pub snippets: Vec<SnippetConfig>,
 }
@@ -65,17 +111,26 @@ impl Default for Config {
         Self {
             daemonize: true,
             pid_file: Self::default_pid_file_path(),
-            primary_threshold_ms: Self::default_primary_threshold_ms(),
+            watch_config_file: false,
+            primary_threshold_ms: None,
             max_history: Self::default_max_history(),
+            backend: BackendKind::default(),
             history_file_path: Self::default_history_file_path(),
-            synchronize_selection_with_clipboard:
-                Self::default_synchronize_selection_with_clipboard(),
+            synchronize_selection_with_clipboard: None,
+            synchronization: SynchronizationConfig::default(),
             log: clipcat_cli::config::LogConfig::default(),
             watcher: WatcherConfig::default(),
             grpc: GrpcConfig::default(),
             desktop_notification: DesktopNotificationConfig::default(),
+            notifications: NotificationsConfig::default(),
             dbus: DBusConfig::default(),
             metrics: MetricsConfig::default(),
+            web_ui: WebUiConfig::default(),
+            http_api: HttpApiConfig::default(),
+            audit_log: AuditLogConfig::default(),
+            osc52: Osc52Config::default(),
+            tmux: TmuxConfig::default(),
+            file: FileBackendConfig::default(),
             snippets: Vec::new(),
         }
     }
@@ -125,12 +180,6 @@ impl Config {
         .collect()
     }
 
-    #[inline]
-    pub const fn default_synchronize_selection_with_clipboard() -> bool { true }
-
-    #[inline]
-    pub const fn default_primary_threshold_ms() -> i64 { 5000 }
-
     #[inline]
     pub const fn default_max_history() -> usize { 50 }
 
@@ -184,21 +233,59 @@ impl Config {
                 None => None,
             };
 
+        config.grpc.local_socket_allowed_executables = config
+            .grpc
+            .local_socket_allowed_executables
+            .iter()
+            .map(resolve_path)
+            .collect::<Result<_, _>>()?;
+
+        for token in &mut config.grpc.scoped_access_tokens {
+            token.token_file_path = token.token_file_path.as_ref().map(resolve_path).transpose()?;
+        }
+
+        if let Some(ref mut tls) = config.grpc.tls {
+            tls.certificate_file_path = resolve_path(&tls.certificate_file_path)?;
+            tls.key_file_path = resolve_path(&tls.key_file_path)?;
+            tls.client_ca_file_path =
+                tls.client_ca_file_path.as_ref().map(resolve_path).transpose()?;
+        }
+
         config.history_file_path = resolve_path(&config.history_file_path)?;
+        config.audit_log.file_path = resolve_path(&config.audit_log.file_path)?;
+        config.notifications.event_log.file_path =
+            resolve_path(&config.notifications.event_log.file_path)?;
 
-        if let Some(x11_atoms) = config.watcher.sensitive_x11_atoms {
+        config.file.path = resolve_path(&config.file.path)?;
+        config.file.output_directory =
+            config.file.output_directory.map(resolve_path).transpose()?;
+
+        if config.backend == BackendKind::Osc52 && config.osc52.tty.is_none() {
+            config.osc52.tty = Some(Osc52Config::detect_tty());
+        }
+
+        config.migrate_deprecated_keys();
+
+        Ok(config)
+    }
+
+    fn migrate_deprecated_keys(&mut self) {
+        if self.primary_threshold_ms.take().is_some() {
             tracing::warn!(
-                "Found deprecated config key sensitive_x11_atoms, use sensitive_mime_types instead"
+                "Found deprecated config key primary_threshold_ms, it is ignored, use \
+                 watcher.primary_debounce_ms instead"
             );
-            if config.watcher.sensitive_mime_types == WatcherConfig::default_sensitive_mime_types()
-            {
-                tracing::info!("Overwriting sensitive_mime_types with sensitive_x11_atoms");
-                config.watcher.sensitive_mime_types = x11_atoms;
-            }
-            config.watcher.sensitive_x11_atoms = None;
         }
 
-        Ok(config)
+        if let Some(synchronize) = self.synchronize_selection_with_clipboard.take() {
+            tracing::warn!(
+                "Found deprecated config key synchronize_selection_with_clipboard, use \
+                 synchronization.clipboard_to_primary.enable instead"
+            );
+            self.synchronization.clipboard_to_primary.enable = synchronize;
+        }
+
+        self.watcher.migrate_deprecated_keys();
     }
 }
 
@@ -206,19 +293,25 @@ impl From<Config> for clipcat_server::Config {
     fn from(
         Config {
             grpc,
-            primary_threshold_ms,
             max_history,
-            synchronize_selection_with_clipboard,
+            backend,
+            synchronization,
             history_file_path,
             watcher,
             desktop_notification,
+            notifications,
             dbus,
             metrics,
+            web_ui,
+            http_api,
+            audit_log,
+            osc52,
+            tmux,
+            file,
             snippets,
             ..
         }: Config,
     ) -> Self {
-        let primary_threshold = time::Duration::milliseconds(primary_threshold_ms);
         let grpc_listen_address = grpc.enable_http.then_some(grpc.socket_address());
         let grpc_local_socket = grpc.enable_local_socket.then_some(grpc.local_socket);
         let grpc_access_token = if let Some(file_path) = grpc.access_token_file_path {
@@ -230,26 +323,55 @@ impl From<Config> for clipcat_server::Config {
         } else {
             grpc.access_token
         };
+        let grpc_scoped_access_tokens = grpc
+            .scoped_access_tokens
+            .into_iter()
+            .filter_map(ScopedAccessTokenConfig::into_scoped_access_token)
+            .collect();
+        let grpc_local_socket_access = clipcat_server::config::LocalSocketAccessConfig {
+            allowed_uids: grpc.local_socket_allowed_uids.into_iter().collect(),
+            allowed_executables: grpc.local_socket_allowed_executables.into_iter().collect(),
+        };
+        let grpc_tls = grpc.tls.map(clipcat_server::config::GrpcTlsConfig::from);
+        let backend = match backend {
+            BackendKind::Default => clipcat_server::config::BackendConfig::Default,
+            BackendKind::Osc52 => clipcat_server::config::BackendConfig::Osc52(osc52.into()),
+            BackendKind::File => clipcat_server::config::BackendConfig::File(file.into()),
+        };
+        let tmux = clipcat_server::config::TmuxConfig::from(tmux);
+        let synchronization = clipcat_server::config::SynchronizationConfig::from(synchronization);
         let watcher = clipcat_server::ClipboardWatcherOptions::from(watcher);
         let desktop_notification =
             clipcat_server::config::DesktopNotificationConfig::from(desktop_notification);
+        let notifications = clipcat_server::config::NotificationSinksConfig::from(notifications);
         let dbus = clipcat_server::config::DBusConfig::from(dbus);
         let metrics = clipcat_server::config::MetricsConfig::from(metrics);
+        let web_ui = clipcat_server::config::WebUiConfig::from(web_ui);
+        let http_api = clipcat_server::config::HttpApiConfig::from(http_api);
+        let audit_log = audit_log.into();
         let snippets =
             snippets.into_iter().map(clipcat_server::config::SnippetConfig::from).collect();
 
         Self {
             grpc_listen_address,
             grpc_local_socket,
+            grpc_local_socket_access,
             grpc_access_token,
-            primary_threshold,
+            grpc_scoped_access_tokens,
+            grpc_tls,
             max_history,
-            synchronize_selection_with_clipboard,
+            backend,
+            tmux,
+            synchronization,
             history_file_path,
             watcher,
             dbus,
             desktop_notification,
+            notifications,
             metrics,
+            web_ui,
+            http_api,
+            audit_log,
             snippets,
         }
     }
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::config::desktop_notification::NotificationEventsConfig;
+
+/// Sinks which receive the same events as desktop notifications.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct NotificationsConfig {
+    /// Maximum number of characters of the preview of new plaintext, the
+    /// preview is omitted if it is 0.
+    #[serde(default)]
+    pub preview_length: usize,
+
+    #[serde(default)]
+    pub events: NotificationEventsConfig,
+
+    #[serde(default)]
+    pub event_log: EventLogSinkConfig,
+
+    #[serde(default)]
+    pub command: CommandSinkConfig,
+
+    #[serde(default)]
+    pub dbus: DBusSinkConfig,
+
+    #[serde(default)]
+    pub bell: BellSinkConfig,
+}
+
+impl From<NotificationsConfig> for clipcat_server::config::NotificationSinksConfig {
+    fn from(
+        NotificationsConfig { preview_length, events, event_log, command, dbus, bell }: NotificationsConfig,
+    ) -> Self {
+        Self {
+            events: events.into(),
+            preview_length,
+            event_log: event_log.enable.then_some(event_log.file_path),
+            command: command.enable.then(|| clipcat_server::config::NotificationCommandConfig {
+                program: PathBuf::from(command.program),
+                args: command.args,
+            }),
+            dbus: dbus.enable,
+            bell: bell.tty.filter(|_| bell.enable),
+        }
+    }
+}
+
+/// Appends events to a file, one JSON object per line.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EventLogSinkConfig {
+    #[serde(default)]
+    pub enable: bool,
+
+    #[serde(default = "EventLogSinkConfig::default_file_path")]
+    pub file_path: PathBuf,
+}
+
+impl EventLogSinkConfig {
+    #[inline]
+    pub fn default_file_path() -> PathBuf {
+        let base_dirs = BaseDirs::new().expect("`BaseDirs::new` always success");
+        [
+            PathBuf::from(base_dirs.data_local_dir()),
+            PathBuf::from(clipcat_base::PROJECT_NAME),
+            PathBuf::from(clipcat_base::DAEMON_EVENT_LOG_FILE_NAME),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+impl Default for EventLogSinkConfig {
+    fn default() -> Self { Self { enable: false, file_path: Self::default_file_path() } }
+}
+
+/// Runs a program for each event.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CommandSinkConfig {
+    #[serde(default)]
+    pub enable: bool,
+
+    #[serde(default)]
+    pub program: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Emits a D-Bus signal for each event.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DBusSinkConfig {
+    #[serde(default)]
+    pub enable: bool,
+}
+
+/// Rings the bell of a terminal for each event.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BellSinkConfig {
+    #[serde(default)]
+    pub enable: bool,
+
+    /// There is no default, `/dev/tty` could not be opened once `clipcatd` is
+    /// daemonized since it has no controlling terminal.
+    #[serde(default)]
+    pub tty: Option<PathBuf>,
+}
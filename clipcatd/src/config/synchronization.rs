@@ -0,0 +1,123 @@
+use std::{collections::HashSet, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SynchronizationConfig {
+    #[serde(default = "SynchronizationConfig::default_clipboard_to_primary")]
+    pub clipboard_to_primary: SynchronizationRuleConfig,
+
+    #[serde(default)]
+    pub primary_to_clipboard: SynchronizationRuleConfig,
+
+    #[serde(default)]
+    pub clipboard_to_secondary: SynchronizationRuleConfig,
+
+    #[serde(default)]
+    pub secondary_to_clipboard: SynchronizationRuleConfig,
+}
+
+impl SynchronizationConfig {
+    #[inline]
+    pub fn default_clipboard_to_primary() -> SynchronizationRuleConfig {
+        SynchronizationRuleConfig { enable: true, ..SynchronizationRuleConfig::default() }
+    }
+
+    // selections change on every character while the mouse is dragged, wait for the
+    // selection to settle before copying it into another selection
+    #[inline]
+    pub const fn default_primary_debounce_ms() -> u64 { 500 }
+}
+
+impl Default for SynchronizationConfig {
+    fn default() -> Self {
+        Self {
+            clipboard_to_primary: Self::default_clipboard_to_primary(),
+            primary_to_clipboard: SynchronizationRuleConfig::default(),
+            clipboard_to_secondary: SynchronizationRuleConfig::default(),
+            secondary_to_clipboard: SynchronizationRuleConfig::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SynchronizationRuleConfig {
+    #[serde(default)]
+    pub enable: bool,
+
+    pub debounce_ms: Option<u64>,
+
+    #[serde(default = "SynchronizationRuleConfig::default_synchronize_image")]
+    pub synchronize_image: bool,
+
+    #[serde(default = "SynchronizationRuleConfig::default_filter_text_min_length")]
+    pub filter_text_min_length: usize,
+
+    #[serde(default = "SynchronizationRuleConfig::default_filter_text_max_length")]
+    pub filter_text_max_length: usize,
+
+    #[serde(default)]
+    pub denied_text_regex_patterns: HashSet<String>,
+}
+
+impl SynchronizationRuleConfig {
+    #[inline]
+    pub const fn default_synchronize_image() -> bool { true }
+
+    #[inline]
+    pub const fn default_filter_text_min_length() -> usize { 0 }
+
+    #[inline]
+    pub const fn default_filter_text_max_length() -> usize { 20_000_000 }
+
+    fn into_rule(self, default_debounce_ms: u64) -> clipcat_server::config::SynchronizationRule {
+        let Self {
+            enable,
+            debounce_ms,
+            synchronize_image,
+            filter_text_min_length,
+            filter_text_max_length,
+            denied_text_regex_patterns,
+        } = self;
+        clipcat_server::config::SynchronizationRule {
+            enable,
+            debounce: Duration::from_millis(debounce_ms.unwrap_or(default_debounce_ms)),
+            synchronize_image,
+            filter_text_min_length,
+            filter_text_max_length,
+            denied_text_regex_patterns,
+        }
+    }
+}
+
+impl Default for SynchronizationRuleConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            debounce_ms: None,
+            synchronize_image: Self::default_synchronize_image(),
+            filter_text_min_length: Self::default_filter_text_min_length(),
+            filter_text_max_length: Self::default_filter_text_max_length(),
+            denied_text_regex_patterns: HashSet::new(),
+        }
+    }
+}
+
+impl From<SynchronizationConfig> for clipcat_server::config::SynchronizationConfig {
+    fn from(
+        SynchronizationConfig {
+            clipboard_to_primary,
+            primary_to_clipboard,
+            clipboard_to_secondary,
+            secondary_to_clipboard,
+        }: SynchronizationConfig,
+    ) -> Self {
+        Self {
+            clipboard_to_primary: clipboard_to_primary.into_rule(0),
+            primary_to_clipboard: primary_to_clipboard
+                .into_rule(SynchronizationConfig::default_primary_debounce_ms()),
+            clipboard_to_secondary: clipboard_to_secondary.into_rule(0),
+            secondary_to_clipboard: secondary_to_clipboard.into_rule(0),
+        }
+    }
+}
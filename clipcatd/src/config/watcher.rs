@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
 use serde::{Deserialize, Serialize};
 
@@ -34,6 +34,12 @@ pub struct WatcherConfig {
 
     #[serde(default = "WatcherConfig::default_filter_image_max_size")]
     pub filter_image_max_size: usize,
+
+    #[serde(default = "WatcherConfig::default_primary_debounce_ms")]
+    pub primary_debounce_ms: u64,
+
+    #[serde(default = "WatcherConfig::default_primary_wait_for_button_release")]
+    pub primary_wait_for_button_release: bool,
 }
 
 impl Default for WatcherConfig {
@@ -49,6 +55,8 @@ impl Default for WatcherConfig {
             filter_image_max_size: Self::default_filter_image_max_size(),
             sensitive_mime_types: Self::default_sensitive_mime_types(),
             sensitive_x11_atoms: None,
+            primary_debounce_ms: Self::default_primary_debounce_ms(),
+            primary_wait_for_button_release: Self::default_primary_wait_for_button_release(),
         }
     }
 }
@@ -65,6 +73,8 @@ impl From<WatcherConfig> for clipcat_server::ClipboardWatcherOptions {
             denied_text_regex_patterns,
             filter_image_max_size,
             sensitive_mime_types,
+            primary_debounce_ms,
+            primary_wait_for_button_release,
             ..
         }: WatcherConfig,
     ) -> Self {
@@ -78,6 +88,8 @@ impl From<WatcherConfig> for clipcat_server::ClipboardWatcherOptions {
             filter_image_max_size,
             denied_text_regex_patterns,
             sensitive_mime_types,
+            primary_debounce: Duration::from_millis(primary_debounce_ms),
+            primary_wait_for_button_release,
         }
     }
 }
@@ -94,7 +106,23 @@ impl WatcherConfig {
 
     pub const fn default_enable_secondary() -> bool { false }
 
+    pub const fn default_primary_debounce_ms() -> u64 { 300 }
+
+    pub const fn default_primary_wait_for_button_release() -> bool { true }
+
     pub fn default_sensitive_mime_types() -> HashSet<String> {
         HashSet::from(["x-kde-passwordManagerHint".to_string()])
     }
+
+    pub(super) fn migrate_deprecated_keys(&mut self) {
+        if let Some(x11_atoms) = self.sensitive_x11_atoms.take() {
+            tracing::warn!(
+                "Found deprecated config key sensitive_x11_atoms, use sensitive_mime_types instead"
+            );
+            if self.sensitive_mime_types == Self::default_sensitive_mime_types() {
+                tracing::info!("Overwriting sensitive_mime_types with sensitive_x11_atoms");
+                self.sensitive_mime_types = x11_atoms;
+            }
+        }
+    }
 }
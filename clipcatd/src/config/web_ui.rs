@@ -0,0 +1,45 @@
+use std::net::{IpAddr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WebUiConfig {
+    #[serde(default = "WebUiConfig::default_enable")]
+    pub enable: bool,
+
+    #[serde(default = "WebUiConfig::default_host")]
+    pub host: IpAddr,
+
+    #[serde(default = "WebUiConfig::default_port")]
+    pub port: u16,
+}
+
+impl WebUiConfig {
+    #[inline]
+    pub const fn socket_address(&self) -> SocketAddr { SocketAddr::new(self.host, self.port) }
+
+    #[inline]
+    pub const fn default_enable() -> bool { false }
+
+    #[inline]
+    pub const fn default_host() -> IpAddr { clipcat_base::DEFAULT_WEBUI_HOST }
+
+    #[inline]
+    pub const fn default_port() -> u16 { clipcat_base::DEFAULT_WEBUI_PORT }
+}
+
+impl Default for WebUiConfig {
+    fn default() -> Self {
+        Self {
+            enable: Self::default_enable(),
+            host: Self::default_host(),
+            port: Self::default_port(),
+        }
+    }
+}
+
+impl From<WebUiConfig> for clipcat_server::config::WebUiConfig {
+    fn from(config: WebUiConfig) -> Self {
+        Self { enable: config.enable, listen_address: config.socket_address() }
+    }
+}
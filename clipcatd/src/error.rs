@@ -1,6 +1,8 @@
+use std::path::PathBuf;
+
 use snafu::Snafu;
 
-use crate::{config, pid_file};
+use crate::{config, pid_file, search_provider};
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
@@ -17,11 +19,17 @@ pub enum Error {
     #[snafu(display("{source}"))]
     PidFile { source: pid_file::Error },
 
+    #[snafu(display("{source}"))]
+    SearchProvider { source: search_provider::Error },
+
     #[snafu(display("Failed to daemonize, error: {source}"))]
     Daemonize { source: daemonize::Error },
 
     #[snafu(display("Failed to send `SIGTERM` to PID `{pid}`"))]
     SendSignalTermination { pid: libc::pid_t },
+
+    #[snafu(display("Configuration file `{}` is invalid", file_path.display()))]
+    InvalidConfig { file_path: PathBuf },
 }
 
 impl From<daemonize::Error> for Error {
@@ -46,15 +54,20 @@ pub trait CommandError {
     fn exit_code(&self) -> exitcode::ExitCode;
 }
 
+impl From<search_provider::Error> for Error {
+    fn from(source: search_provider::Error) -> Self { Self::SearchProvider { source } }
+}
+
 impl CommandError for Error {
     fn exit_code(&self) -> exitcode::ExitCode {
         match self {
             Self::Application { .. } => exitcode::SOFTWARE,
-            Self::Config { .. } => exitcode::CONFIG,
+            Self::Config { .. } | Self::InvalidConfig { .. } => exitcode::CONFIG,
             Self::InitializeTokioRuntime { .. }
             | Self::Daemonize { .. }
             | Self::SendSignalTermination { .. }
-            | Self::PidFile { .. } => exitcode::IOERR,
+            | Self::PidFile { .. }
+            | Self::SearchProvider { .. } => exitcode::IOERR,
         }
     }
 }
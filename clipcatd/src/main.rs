@@ -2,6 +2,7 @@ mod command;
 mod config;
 mod error;
 mod pid_file;
+mod search_provider;
 mod shadow {
     #![allow(clippy::needless_raw_string_hashes)]
     use shadow_rs::shadow;
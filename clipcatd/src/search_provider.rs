@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+
+use snafu::{ResultExt, Snafu};
+
+/// Desktop entry the search provider belongs to, GNOME Shell ignores search
+/// providers without an installed desktop entry. The entry starts the daemon
+/// providing the results and is hidden from application menus.
+const DESKTOP_ID: &str = "org.clipcat.clipcat.desktop";
+
+/// GNOME Shell loads search providers from the system data directories only,
+/// i.e. `$XDG_DATA_DIRS`, `$XDG_DATA_HOME` is not searched.
+pub const DEFAULT_DATA_DIR: &str = "/usr/local/share";
+
+/// Files registering the GNOME Shell search provider of `clipcatd`.
+#[derive(Debug)]
+pub struct SearchProvider {
+    dbus_service_name: String,
+}
+
+impl SearchProvider {
+    pub fn new(identifier: Option<&str>) -> Self {
+        let dbus_service_name = identifier.map_or_else(
+            || clipcat_base::DBUS_SERVICE_NAME.to_string(),
+            |identifier| format!("{}.{identifier}", clipcat_base::DBUS_SERVICE_NAME),
+        );
+        Self { dbus_service_name }
+    }
+
+    /// Writes the search provider file and the desktop entry into `data_dir`,
+    /// returns the paths of written files.
+    pub fn install(&self, data_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+        let files = [
+            (
+                data_dir
+                    .join("gnome-shell/search-providers")
+                    .join(format!("{}.search-provider.ini", self.dbus_service_name)),
+                self.provider_file(),
+            ),
+            (data_dir.join("applications").join(DESKTOP_ID), Self::desktop_entry()),
+        ];
+
+        let mut paths = Vec::with_capacity(files.len());
+        for (path, contents) in files {
+            if let Some(directory) = path.parent() {
+                std::fs::create_dir_all(directory)
+                    .context(CreateDirectorySnafu { path: directory.to_path_buf() })?;
+            }
+            std::fs::write(&path, contents).context(WriteFileSnafu { path: path.clone() })?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    fn provider_file(&self) -> String {
+        format!(
+            concat!(
+                "[Shell Search Provider]\n",
+                "DesktopId={}\n",
+                "BusName={}\n",
+                "ObjectPath={}\n",
+                "Version=2\n",
+            ),
+            DESKTOP_ID,
+            self.dbus_service_name,
+            clipcat_base::DBUS_SEARCH_PROVIDER_OBJECT_PATH
+        )
+    }
+
+    fn desktop_entry() -> String {
+        format!(
+            concat!(
+                "[Desktop Entry]\n",
+                "Type=Application\n",
+                "Name={}\n",
+                "Comment={}\n",
+                "Icon=edit-paste\n",
+                "Exec={}\n",
+                "Terminal=false\n",
+                "NoDisplay=true\n",
+                "Categories=Utility;\n",
+            ),
+            clipcat_base::PROJECT_NAME_WITH_INITIAL_CAPITAL,
+            clipcat_base::NOTIFICATION_SUMMARY,
+            clipcat_base::DAEMON_PROGRAM_NAME,
+        )
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum Error {
+    #[snafu(display("Could not create directory `{}`, error: {source}", path.display()))]
+    CreateDirectory { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not write file `{}`, error: {source}", path.display()))]
+    WriteFile { path: PathBuf, source: std::io::Error },
+}
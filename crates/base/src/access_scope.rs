@@ -0,0 +1,70 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Operations an access token is permitted to perform.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccessScope {
+    /// List clips with previews, get the length of history and the watcher
+    /// state.
+    ReadMetadata,
+
+    /// Get the whole contents of clips.
+    ReadContent,
+
+    Insert,
+
+    /// Mark and pin clips.
+    Mark,
+
+    /// Remove clips and clear history.
+    Delete,
+
+    /// Enable, disable and toggle the clipboard watcher.
+    WatcherControl,
+
+    /// Query the audit log.
+    ReadAudit,
+
+    /// Reload the configuration of the daemon.
+    ReloadConfig,
+}
+
+impl AccessScope {
+    pub const MAX_LENGTH: usize = 8;
+
+    #[inline]
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::ReadMetadata => "read-metadata",
+            Self::ReadContent => "read-content",
+            Self::Insert => "insert",
+            Self::Mark => "mark",
+            Self::Delete => "delete",
+            Self::WatcherControl => "watcher-control",
+            Self::ReadAudit => "read-audit",
+            Self::ReloadConfig => "reload-config",
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn all_scopes() -> [Self; Self::MAX_LENGTH] {
+        [
+            Self::ReadMetadata,
+            Self::ReadContent,
+            Self::Insert,
+            Self::Mark,
+            Self::Delete,
+            Self::WatcherControl,
+            Self::ReadAudit,
+            Self::ReloadConfig,
+        ]
+    }
+}
+
+impl fmt::Display for AccessScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
@@ -0,0 +1,115 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Entry of the audit log, it records who accessed which clips, but never the
+/// contents of clips.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Record {
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+
+    pub transport: Transport,
+
+    pub operation: Operation,
+
+    /// Name of the access token, `None` if the transport has no access token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_uid: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_pid: Option<i32>,
+
+    /// Socket address of TCP peers, or unique bus name of D-Bus peers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_address: Option<String>,
+
+    #[serde(default)]
+    pub clip_ids: Vec<u64>,
+}
+
+/// How the request reaches `clipcatd`.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transport {
+    GrpcHttp,
+
+    GrpcLocalSocket,
+
+    DBus,
+
+    HttpApi,
+
+    /// The D-Bus interface of Klipper.
+    Klipper,
+
+    /// The D-Bus search provider of GNOME Shell.
+    SearchProvider,
+
+    /// Actions invoked on desktop notifications.
+    DesktopNotification,
+}
+
+impl Transport {
+    #[inline]
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::GrpcHttp => "grpc-http",
+            Self::GrpcLocalSocket => "grpc-local-socket",
+            Self::DBus => "d-bus",
+            Self::HttpApi => "http-api",
+            Self::Klipper => "klipper",
+            Self::SearchProvider => "search-provider",
+            Self::DesktopNotification => "desktop-notification",
+        }
+    }
+}
+
+impl fmt::Display for Transport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
+
+/// Operations on the history which are audited.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Operation {
+    Get,
+
+    GetCurrentClip,
+
+    /// Marking a clip, including pinning and unpinning it.
+    Mark,
+
+    /// Removing a clip, including replacing it with new contents.
+    Remove,
+
+    Clear,
+
+    /// Reading the contents of many clips at once, e.g. listing clips without
+    /// limiting the length of previews.
+    Export,
+}
+
+impl Operation {
+    #[inline]
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::Get => "get",
+            Self::GetCurrentClip => "get-current-clip",
+            Self::Mark => "mark",
+            Self::Remove => "remove",
+            Self::Clear => "clear",
+            Self::Export => "export",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
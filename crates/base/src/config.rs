@@ -16,6 +16,14 @@ pub fn default_unix_domain_socket() -> PathBuf {
     .collect()
 }
 
+/// # Panics
+/// This function should never panic
+#[inline]
+#[must_use]
+pub fn default_http_api_unix_domain_socket() -> PathBuf {
+    default_unix_domain_socket().with_file_name("http.sock")
+}
+
 /// # Panics
 /// This function should never panic
 #[inline]
@@ -200,10 +200,52 @@ impl Entry {
         }
     }
 
+    /// Encodes the content as `mime`, images can be encoded as PNG, JPEG,
+    /// GIF or BMP.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the content could not be
+    /// represented as `mime`.
+    pub fn encoded_as(&self, mime: &mime::Mime) -> Result<Vec<u8>, Error> {
+        match &self.content {
+            ClipboardContent::Plaintext(text) if mime.type_() == mime::TEXT => {
+                Ok(text.as_bytes().to_vec())
+            }
+            ClipboardContent::Image { width, height, bytes } if mime.type_() == mime::IMAGE => {
+                let image_format = match mime.subtype() {
+                    mime::PNG => return encode_as_png(*width, *height, bytes),
+                    mime::JPEG => image::ImageFormat::Jpeg,
+                    mime::GIF => image::ImageFormat::Gif,
+                    mime::BMP => image::ImageFormat::Bmp,
+                    _ => return Err(Error::FormatNotAvailable),
+                };
+                let image = u32::try_from(*width)
+                    .ok()
+                    .zip(u32::try_from(*height).ok())
+                    .and_then(|(width, height)| {
+                        image::RgbaImage::from_raw(width, height, bytes.to_vec())
+                    })
+                    .ok_or(Error::EmptyImage)?;
+                // JPEG has no alpha channel
+                let image = if image_format == image::ImageFormat::Jpeg {
+                    image::DynamicImage::ImageRgb8(image::DynamicImage::ImageRgba8(image).to_rgb8())
+                } else {
+                    image::DynamicImage::ImageRgba8(image)
+                };
+                let mut encoded = std::io::Cursor::new(Vec::new());
+                image.write_to(&mut encoded, image_format).context(ConvertImageSnafu {})?;
+                Ok(encoded.into_inner())
+            }
+            _ => Err(Error::FormatNotAvailable),
+        }
+    }
+
     #[inline]
     #[must_use]
     pub const fn mime(&self) -> mime::Mime { self.content.mime() }
 
+    /// Returns the metadata of a clip in the history which is not pinned.
     #[inline]
     pub fn metadata(&self, preview_length: Option<usize>) -> Metadata {
         Metadata {
@@ -212,6 +254,9 @@ impl Entry {
             timestamp: self.timestamp,
             mime: self.mime(),
             preview: self.preview_information(preview_length),
+            size: self.len(),
+            pinned: false,
+            source: Source::History,
         }
     }
 
@@ -267,6 +312,13 @@ pub struct Metadata {
     pub mime: mime::Mime,
 
     pub preview: String,
+
+    /// Size of the contents in bytes.
+    pub size: usize,
+
+    pub pinned: bool,
+
+    pub source: Source,
 }
 
 impl PartialOrd for Metadata {
@@ -282,6 +334,32 @@ impl Ord for Metadata {
     }
 }
 
+/// Where a clip comes from.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Source {
+    /// Fetched from a clipboard or inserted by a client.
+    #[default]
+    History,
+
+    /// Loaded from the snippets of the configuration.
+    Snippet,
+}
+
+impl Source {
+    #[inline]
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::History => "history",
+            Self::Snippet => "snippet",
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
+
 fn encode_as_png(width: usize, height: usize, bytes: &[u8]) -> Result<Vec<u8>, Error> {
     let (width, height) =
         (u32::try_from(width).unwrap_or_default(), u32::try_from(height).unwrap_or_default());
@@ -320,3 +398,66 @@ pub enum Error {
     #[snafu(display("Error occurs while converting image, error: {source}"))]
     ConvertImage { source: image::ImageError },
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{Entry, Error};
+    use crate::{ClipboardContent, ClipboardKind};
+
+    // a 2x2 image with distinct and translucent pixels
+    const PIXELS: [u8; 16] = [255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 255, 255, 255, 255, 255, 0];
+
+    fn image() -> Entry {
+        let content =
+            ClipboardContent::Image { width: 2, height: 2, bytes: Bytes::from(PIXELS.to_vec()) };
+        Entry::from_clipboard_content(content, ClipboardKind::Clipboard, None)
+    }
+
+    #[test]
+    fn test_encoded_as_text() {
+        let entry = Entry::from_string("clipcat", ClipboardKind::Clipboard);
+        let data = entry.encoded_as(&mime::TEXT_PLAIN_UTF_8).unwrap();
+        assert_eq!(data, b"clipcat");
+
+        let decoded = Entry::new(&data, &mime::TEXT_PLAIN_UTF_8, ClipboardKind::Clipboard, None);
+        assert_eq!(decoded.unwrap().id(), entry.id());
+    }
+
+    #[test]
+    fn test_encoded_as_png() {
+        let entry = image();
+        let data = entry.encoded_as(&mime::IMAGE_PNG).unwrap();
+
+        // PNG is lossless, the pixels are kept
+        let decoded = Entry::new(&data, &mime::IMAGE_PNG, ClipboardKind::Clipboard, None).unwrap();
+        assert_eq!(decoded.id(), entry.id());
+        let ClipboardContent::Image { width, height, .. } = decoded.content else {
+            panic!("unexpected content");
+        };
+        assert_eq!((width, height), (2, 2));
+        assert_eq!(decoded.as_bytes(), PIXELS);
+    }
+
+    #[test]
+    fn test_encoded_as_jpeg() {
+        let data = image().encoded_as(&mime::IMAGE_JPEG).unwrap();
+
+        let decoded = image::load_from_memory_with_format(&data, image::ImageFormat::Jpeg).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (2, 2));
+        // JPEG is encoded without the alpha channel
+        assert_eq!(decoded.color(), image::ColorType::Rgb8);
+    }
+
+    #[test]
+    fn test_encoded_as_unsupported() {
+        let entry = image();
+        let webp = "image/webp".parse::<mime::Mime>().unwrap();
+        assert!(matches!(entry.encoded_as(&webp), Err(Error::FormatNotAvailable)));
+        assert!(matches!(entry.encoded_as(&mime::TEXT_PLAIN), Err(Error::FormatNotAvailable)));
+
+        let entry = Entry::from_string("clipcat", ClipboardKind::Clipboard);
+        assert!(matches!(entry.encoded_as(&mime::IMAGE_PNG), Err(Error::FormatNotAvailable)));
+    }
+}
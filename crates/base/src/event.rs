@@ -0,0 +1,51 @@
+use crate::{ClipEntryMetadata, ClipboardKind, ClipboardWatcherState};
+
+/// A change of the clipboard history or of the clipboard watcher, delivered
+/// to the subscribers of `clipcatd`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    ClipAdded {
+        metadata: ClipEntryMetadata,
+    },
+
+    ClipRemoved {
+        id: u64,
+    },
+
+    ClipMarked {
+        id: u64,
+        kind: ClipboardKind,
+    },
+
+    ClipPinned {
+        id: u64,
+        pinned: bool,
+    },
+
+    HistoryCleared,
+
+    WatcherStateChanged {
+        state: ClipboardWatcherState,
+    },
+
+    /// The subscriber is too slow, `missed` events are dropped.
+    Lagged {
+        missed: u64,
+    },
+}
+
+impl Event {
+    #[inline]
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::ClipAdded { .. } => "clip_added",
+            Self::ClipRemoved { .. } => "clip_removed",
+            Self::ClipMarked { .. } => "clip_marked",
+            Self::ClipPinned { .. } => "clip_pinned",
+            Self::HistoryCleared => "history_cleared",
+            Self::WatcherStateChanged { .. } => "watcher_state_changed",
+            Self::Lagged { .. } => "lagged",
+        }
+    }
+}
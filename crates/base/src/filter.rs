@@ -1,7 +1,40 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, fmt};
 
 use crate::ClipboardContent;
 
+/// Reason why clipboard content is filtered out.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Reason {
+    /// The text is too short or too long.
+    TextLength,
+
+    /// The text matches one of the regular expressions.
+    TextPattern,
+
+    /// Images are denied.
+    ImageDenied,
+
+    /// The image is too large.
+    ImageSize,
+}
+
+impl Reason {
+    #[inline]
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
+        match self {
+            Self::TextLength => "text-length",
+            Self::TextPattern => "text-pattern",
+            Self::ImageDenied => "image-denied",
+            Self::ImageSize => "image-size",
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+}
+
 // SAFETY: We need the prefix `fitler_`.
 #[allow(clippy::struct_field_names)]
 #[derive(Clone, Debug)]
@@ -54,16 +87,30 @@ impl Filter {
     pub fn deny_image(&mut self, deny_image: bool) { self.deny_image = deny_image; }
 
     pub fn filter_clipboard_content<C>(&self, content: C) -> bool
+    where
+        C: AsRef<ClipboardContent>,
+    {
+        self.rejection_reason(content).is_some()
+    }
+
+    /// Returns the reason why `content` is filtered out, `None` if it is
+    /// accepted.
+    pub fn rejection_reason<C>(&self, content: C) -> Option<Reason>
     where
         C: AsRef<ClipboardContent>,
     {
         match content.as_ref() {
-            ClipboardContent::Plaintext(text) => {
-                self.filter_by_text_size(text) || self.filter_text_by_regular_expression(text)
+            ClipboardContent::Plaintext(text) if self.filter_by_text_size(text) => {
+                Some(Reason::TextLength)
             }
-            ClipboardContent::Image { bytes, .. } => {
-                self.deny_image || self.filter_by_image_size(bytes)
+            ClipboardContent::Plaintext(text) if self.filter_text_by_regular_expression(text) => {
+                Some(Reason::TextPattern)
             }
+            ClipboardContent::Image { .. } if self.deny_image => Some(Reason::ImageDenied),
+            ClipboardContent::Image { bytes, .. } if self.filter_by_image_size(bytes) => {
+                Some(Reason::ImageSize)
+            }
+            _ => None,
         }
     }
 
@@ -131,3 +178,38 @@ impl Default for Filter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{Filter, Reason};
+    use crate::ClipboardContent;
+
+    fn text(text: &str) -> ClipboardContent { ClipboardContent::Plaintext(text.to_string()) }
+
+    fn image(size: usize) -> ClipboardContent {
+        ClipboardContent::Image { width: 1, height: 1, bytes: Bytes::from(vec![0; size]) }
+    }
+
+    #[test]
+    fn test_rejection_reason() {
+        let mut filter = Filter::new();
+        filter.set_text_min_length(2);
+        filter.set_text_max_length(8);
+        filter.set_image_max_size(16);
+        filter.set_regex_patterns(regex::RegexSet::new(["^password"]).unwrap());
+
+        assert_eq!(filter.rejection_reason(text("clipcat")), None);
+        assert_eq!(filter.rejection_reason(text("ab")), Some(Reason::TextLength));
+        assert_eq!(filter.rejection_reason(text("clipcat-menu")), Some(Reason::TextLength));
+        assert_eq!(filter.rejection_reason(text("password")), Some(Reason::TextPattern));
+        assert_eq!(filter.rejection_reason(image(16)), None);
+        assert_eq!(filter.rejection_reason(image(17)), Some(Reason::ImageSize));
+
+        filter.deny_image(true);
+        assert_eq!(filter.rejection_reason(image(16)), Some(Reason::ImageDenied));
+        assert!(filter.filter_clipboard_content(image(1)));
+        assert!(!filter.filter_clipboard_content(text("clipcat")));
+    }
+}
@@ -1,5 +1,8 @@
+mod access_scope;
+mod audit;
 pub mod config;
 mod entry;
+mod event;
 mod filter;
 mod kind;
 pub mod serde;
@@ -18,8 +21,14 @@ use directories::ProjectDirs;
 use once_cell::sync::Lazy;
 
 pub use self::{
-    entry::{Entry as ClipEntry, Error as ClipEntryError, Metadata as ClipEntryMetadata},
-    filter::Filter as ClipFilter,
+    access_scope::AccessScope,
+    audit::{Operation as AuditOperation, Record as AuditRecord, Transport as AuditTransport},
+    entry::{
+        Entry as ClipEntry, Error as ClipEntryError, Metadata as ClipEntryMetadata,
+        Source as ClipSource,
+    },
+    event::Event as ClipboardEvent,
+    filter::{Filter as ClipFilter, Reason as ClipFilterReason},
     kind::Kind as ClipboardKind,
     watcher_state::WatcherState as ClipboardWatcherState,
 };
@@ -31,6 +40,11 @@ pub const DBUS_OBJECT_PATH_PREFIX: &str = "/org/clipcat/clipcat";
 pub const DBUS_SYSTEM_OBJECT_PATH: &str = "/org/clipcat/clipcat/system";
 pub const DBUS_WATCHER_OBJECT_PATH: &str = "/org/clipcat/clipcat/watcher";
 pub const DBUS_MANAGER_OBJECT_PATH: &str = "/org/clipcat/clipcat/manager";
+pub const DBUS_SEARCH_PROVIDER_OBJECT_PATH: &str = "/org/clipcat/clipcat/search_provider";
+pub const DBUS_NOTIFICATION_OBJECT_PATH: &str = "/org/clipcat/clipcat/notification";
+pub const DBUS_NOTIFICATION_INTERFACE: &str = "org.clipcat.clipcat.Notification";
+pub const DBUS_KLIPPER_SERVICE_NAME: &str = "org.kde.klipper";
+pub const DBUS_KLIPPER_OBJECT_PATH: &str = "/klipper";
 
 pub static PROJECT_SEMVER: Lazy<semver::Version> = Lazy::new(|| {
     semver::Version::parse(PROJECT_VERSION).unwrap_or(semver::Version {
@@ -49,6 +63,8 @@ pub const NOTIFICATION_SUMMARY: &str = "Clipcat - Clipboard Manager";
 pub const DAEMON_PROGRAM_NAME: &str = "clipcatd";
 pub const DAEMON_CONFIG_NAME: &str = "clipcatd.toml";
 pub const DAEMON_HISTORY_FILE_NAME: &str = "clipcatd-history";
+pub const DAEMON_AUDIT_LOG_FILE_NAME: &str = "clipcatd-audit.log";
+pub const DAEMON_EVENT_LOG_FILE_NAME: &str = "clipcatd-events.jsonl";
 
 pub const CTL_PROGRAM_NAME: &str = "clipcatctl";
 pub const CTL_CONFIG_NAME: &str = "clipcatctl.toml";
@@ -67,6 +83,9 @@ pub const DEFAULT_WEBUI_HOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
 pub const DEFAULT_METRICS_PORT: u16 = 45047;
 pub const DEFAULT_METRICS_HOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
 
+pub const DEFAULT_HTTP_API_PORT: u16 = 45048;
+pub const DEFAULT_HTTP_API_HOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
 pub const DEFAULT_MENU_PROMPT: &str = "Clipcat";
 
 pub static PROJECT_CONFIG_DIR: Lazy<PathBuf> = Lazy::new(|| {
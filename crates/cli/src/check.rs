@@ -0,0 +1,401 @@
+//! Strict validation of configuration files, used by the `check-config`
+//! subcommands.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Keys holding secrets, their values are redacted while printing a
+/// configuration.
+const SECRET_KEYS: [&str; 2] = ["access_token", "token"];
+
+const REDACTED: &str = "<redacted>";
+
+// unknown keys which are similar enough to a known key get a suggestion
+const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Issue {
+    pub severity: Severity,
+
+    /// Dotted path of the key, empty if the issue is about the whole file.
+    pub key: String,
+
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { severity, key, message } = self;
+        if key.is_empty() {
+            write!(f, "{severity}: {message}")
+        } else {
+            write!(f, "{severity}: `{key}`: {message}")
+        }
+    }
+}
+
+/// Issues found in a configuration file.
+#[derive(Clone, Debug)]
+pub struct Report {
+    file_path: PathBuf,
+
+    issues: Vec<Issue>,
+}
+
+impl Report {
+    pub fn new<P>(file_path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { file_path: file_path.into(), issues: Vec::new() }
+    }
+
+    pub fn error<K, M>(&mut self, key: K, message: M)
+    where
+        K: Into<String>,
+        M: fmt::Display,
+    {
+        self.push(Severity::Error, key.into(), message.to_string());
+    }
+
+    pub fn warning<K, M>(&mut self, key: K, message: M)
+    where
+        K: Into<String>,
+        M: fmt::Display,
+    {
+        self.push(Severity::Warning, key.into(), message.to_string());
+    }
+
+    fn push(&mut self, severity: Severity, key: String, message: String) {
+        self.issues.push(Issue { severity, key, message });
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn issues(&self) -> &[Issue] { &self.issues }
+
+    #[inline]
+    #[must_use]
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|issue| issue.severity == Severity::Error)
+    }
+
+    /// Reads and parses the configuration file, keys which are not fields of
+    /// `Config` are reported.
+    ///
+    /// `aliases` maps the aliases of keys at the top level to the keys.
+    pub fn parse<Config>(&mut self, aliases: &[(&str, &str)]) -> Option<Config>
+    where
+        Config: DeserializeOwned + Serialize,
+    {
+        let data = match std::fs::read_to_string(&self.file_path) {
+            Ok(data) => data,
+            Err(err) => {
+                let message = format!("could not read `{}`, {err}", self.file_path.display());
+                self.error("", message);
+                return None;
+            }
+        };
+
+        let table = match toml::from_str::<toml::Table>(&data) {
+            Ok(table) => table,
+            Err(err) => {
+                self.error("", err.message());
+                return None;
+            }
+        };
+        let config = match toml::Value::Table(table.clone()).try_into::<Config>() {
+            Ok(config) => config,
+            Err(err) => {
+                self.error("", err.message());
+                return None;
+            }
+        };
+
+        // the keys of a parsed configuration are the known keys, optional keys
+        // without a value are not serialized, they are never suggested
+        if let Ok(toml::Value::Table(known)) = toml::Value::try_from(&config) {
+            self.check_unknown_keys("", &table, &known, aliases);
+        }
+        Some(config)
+    }
+
+    fn check_unknown_keys(
+        &mut self,
+        prefix: &str,
+        table: &toml::Table,
+        known: &toml::Table,
+        aliases: &[(&str, &str)],
+    ) {
+        for (key, value) in table {
+            let name = aliases
+                .iter()
+                .find(|(alias, _)| alias == key)
+                .map_or(key.as_str(), |(_, name)| name);
+            let path = if prefix.is_empty() { key.clone() } else { format!("{prefix}.{key}") };
+            match (value, known.get(name)) {
+                (_, None) => {
+                    let message = suggest(key, known.keys()).map_or_else(
+                        || "unknown key".to_string(),
+                        |suggestion| format!("unknown key, did you mean `{suggestion}`?"),
+                    );
+                    self.error(path, message);
+                }
+                (toml::Value::Table(table), Some(toml::Value::Table(known))) => {
+                    self.check_unknown_keys(&path, table, known, &[]);
+                }
+                (toml::Value::Array(values), Some(toml::Value::Array(known_values))) => {
+                    for (index, (value, known)) in values.iter().zip(known_values).enumerate() {
+                        if let (toml::Value::Table(table), toml::Value::Table(known)) =
+                            (value, known)
+                        {
+                            self.check_unknown_keys(&format!("{path}[{index}]"), table, known, &[]);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reports an error if `path` is not an existing file.
+    pub fn check_file<K>(&mut self, key: K, path: &Path)
+    where
+        K: Into<String>,
+    {
+        if !path.is_file() {
+            self.error(key, format_args!("file `{}` does not exist", path.display()));
+        }
+    }
+
+    /// Reports an error if `path` is not an existing directory.
+    pub fn check_directory<K>(&mut self, key: K, path: &Path)
+    where
+        K: Into<String>,
+    {
+        if !path.is_dir() {
+            self.error(key, format_args!("directory `{}` does not exist", path.display()));
+        }
+    }
+
+    /// Reports an error if `program` is neither an existing file nor found in
+    /// `PATH`.
+    pub fn check_program<K>(&mut self, key: K, program: &str)
+    where
+        K: Into<String>,
+    {
+        let found = if program.contains(std::path::MAIN_SEPARATOR) {
+            Path::new(program).is_file()
+        } else {
+            std::env::var_os("PATH").is_some_and(|paths| {
+                std::env::split_paths(&paths).any(|path| path.join(program).is_file())
+            })
+        };
+        if !found {
+            self.error(key, format_args!("program `{program}` is not found"));
+        }
+    }
+
+    /// Reports an error if `path` is a directory, the file at `path` is
+    /// created if it does not exist.
+    pub fn check_writable_file<K>(&mut self, key: K, path: &Path)
+    where
+        K: Into<String>,
+    {
+        if path.is_dir() {
+            self.error(key, format_args!("`{}` is a directory", path.display()));
+        }
+    }
+
+    /// Prints the issues and a summary to stderr.
+    pub fn print(&self) {
+        for issue in &self.issues {
+            eprintln!("{issue}");
+        }
+
+        let file_path = self.file_path.display();
+        let errors = self.issues.iter().filter(|issue| issue.severity == Severity::Error).count();
+        let warnings = self.issues.len() - errors;
+        if self.issues.is_empty() {
+            eprintln!("`{file_path}` is valid");
+        } else {
+            eprintln!("`{file_path}`: {errors} error(s), {warnings} warning(s)");
+        }
+    }
+}
+
+/// Serializes `config` into TOML with the values of secrets redacted.
+///
+/// # Panics
+///
+/// Panics if `config` is not serializable into TOML.
+pub fn to_redacted_toml<Config>(config: &Config) -> String
+where
+    Config: Serialize,
+{
+    let mut value = toml::Value::try_from(config).expect("`Config` is serializable");
+    redact(&mut value);
+    toml::to_string_pretty(&value).expect("`Config` is serializable")
+}
+
+fn redact(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                if value.is_str() && SECRET_KEYS.contains(&key.as_str()) {
+                    *value = toml::Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        toml::Value::Array(values) => values.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn suggest<'a, I>(key: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::jaro_winkler(key, candidate), candidate))
+        .filter(|(similarity, _)| *similarity >= SUGGESTION_THRESHOLD)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{to_redacted_toml, Report, Severity, REDACTED};
+
+    #[derive(Debug, Default, Deserialize, Serialize)]
+    struct Config {
+        #[serde(default, alias = "history")]
+        max_history: usize,
+
+        #[serde(default)]
+        grpc: GrpcConfig,
+
+        #[serde(default)]
+        tokens: Vec<TokenConfig>,
+    }
+
+    #[derive(Debug, Default, Deserialize, Serialize)]
+    struct GrpcConfig {
+        #[serde(default)]
+        access_token: Option<String>,
+
+        #[serde(default)]
+        enable_http: bool,
+    }
+
+    #[derive(Debug, Default, Deserialize, Serialize)]
+    struct TokenConfig {
+        name: String,
+
+        #[serde(default)]
+        token: Option<String>,
+    }
+
+    fn parse(data: &str) -> (Option<Config>, Report) {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), data).unwrap();
+        let mut report = Report::new(file.path());
+        let config = report.parse::<Config>(&[("history", "max_history")]);
+        (config, report)
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let (config, report) = parse(
+            r#"
+history = 10
+unrelated = 1
+
+[grpc]
+enable_htpp = true
+access_tokn = "secret"
+
+[[tokens]]
+name = "default"
+token = "secret"
+naem = "other"
+"#,
+        );
+        assert_eq!(config.unwrap().max_history, 10);
+        assert!(report.has_errors());
+
+        let issues = report
+            .issues()
+            .iter()
+            .map(|issue| (issue.severity, issue.key.as_str(), issue.message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(issues.len(), 4, "{issues:?}");
+        assert!(issues.contains(&(
+            Severity::Error,
+            "grpc.enable_htpp",
+            "unknown key, did you mean `enable_http`?"
+        )));
+        // `access_token` has no value, it is unknown to the parsed configuration
+        assert!(issues.contains(&(Severity::Error, "grpc.access_tokn", "unknown key")));
+        assert!(issues.contains(&(
+            Severity::Error,
+            "tokens[0].naem",
+            "unknown key, did you mean `name`?"
+        )));
+        // no known key is similar enough
+        assert!(issues.contains(&(Severity::Error, "unrelated", "unknown key")));
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let (config, report) = parse("max_history = \"ten\"");
+        assert!(config.is_none());
+        assert!(report.has_errors());
+
+        let mut report = Report::new("/nonexistent/clipcat.toml");
+        assert!(report.parse::<Config>(&[]).is_none());
+        assert_eq!(report.issues()[0].key, "");
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn test_to_redacted_toml() {
+        let config = Config {
+            grpc: GrpcConfig { access_token: Some("secret".to_string()), enable_http: true },
+            tokens: vec![TokenConfig {
+                name: "default".to_string(),
+                token: Some("secret".to_string()),
+            }],
+            ..Config::default()
+        };
+        let data = to_redacted_toml(&config);
+        assert!(!data.contains("secret"));
+        assert_eq!(data.matches(REDACTED).count(), 2);
+        // other values are kept
+        assert!(data.contains("name = \"default\""));
+    }
+}
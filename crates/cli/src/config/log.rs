@@ -59,18 +59,20 @@ impl LogConfig {
     #[must_use]
     pub const fn default_emit_stderr() -> bool { false }
 
+    /// Initializes the global subscriber, it is initialized only once and
+    /// subsequent calls are ignored.
     pub fn registry(&self) {
         let Self { emit_journald, file_path, emit_stdout, emit_stderr, level: log_level } = self;
 
         let filter_layer = tracing_subscriber::filter::LevelFilter::from_level(*log_level);
 
-        tracing_subscriber::registry()
+        let _unused = tracing_subscriber::registry()
             .with(filter_layer)
             .with(emit_journald.then(|| LogDriver::Journald.layer()))
             .with(file_path.clone().map(|path| LogDriver::File(path).layer()))
             .with(emit_stdout.then(|| LogDriver::Stdout.layer()))
             .with(emit_stderr.then(|| LogDriver::Stderr.layer()))
-            .init();
+            .try_init();
     }
 }
 
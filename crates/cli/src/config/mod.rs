@@ -1,3 +1,4 @@
 mod log;
+mod tls;
 
-pub use self::log::LogConfig;
+pub use self::{log::LogConfig, tls::TlsConfig};
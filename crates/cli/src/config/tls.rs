@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::check::Report;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TlsConfig {
+    pub ca_certificate_file_path: Option<PathBuf>,
+
+    pub certificate_file_path: Option<PathBuf>,
+
+    pub key_file_path: Option<PathBuf>,
+
+    pub domain_name: Option<String>,
+}
+
+impl TlsConfig {
+    pub fn file_paths_mut(&mut self) -> impl Iterator<Item = &mut PathBuf> {
+        [
+            &mut self.ca_certificate_file_path,
+            &mut self.certificate_file_path,
+            &mut self.key_file_path,
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Reports missing files, `key` is the key of this table.
+    pub fn check(&self, key: &str, report: &mut Report) {
+        let Self { ca_certificate_file_path, certificate_file_path, key_file_path, .. } = self;
+        for (name, file_path) in [
+            ("ca_certificate_file_path", ca_certificate_file_path),
+            ("certificate_file_path", certificate_file_path),
+            ("key_file_path", key_file_path),
+        ] {
+            if let Some(file_path) = file_path {
+                report.check_file(format!("{key}.{name}"), file_path);
+            }
+        }
+        if certificate_file_path.is_some() != key_file_path.is_some() {
+            report.error(
+                key,
+                "`certificate_file_path` and `key_file_path` must be specified together",
+            );
+        }
+    }
+}
+
+impl From<TlsConfig> for clipcat_client::TlsConfig {
+    fn from(
+        TlsConfig { ca_certificate_file_path, certificate_file_path, key_file_path, domain_name }: TlsConfig,
+    ) -> Self {
+        Self { ca_certificate_file_path, certificate_file_path, key_file_path, domain_name }
+    }
+}
@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use clipcat_base::AuditRecord;
+use clipcat_proto as proto;
+use tonic::Request;
+
+use crate::{error::ListAuditRecordsError, Client};
+
+#[async_trait]
+pub trait Audit {
+    /// Returns the newest `limit` records of the audit log, all records are
+    /// returned if `limit` is 0.
+    async fn list_audit_records(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<AuditRecord>, ListAuditRecordsError>;
+}
+
+#[async_trait]
+impl Audit for Client {
+    async fn list_audit_records(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<AuditRecord>, ListAuditRecordsError> {
+        let proto::ListAuditRecordsResponse { records } =
+            proto::AuditClient::with_interceptor(self.channel.clone(), self.interceptor.clone())
+                .list_records(Request::new(proto::ListAuditRecordsRequest { limit: limit as u64 }))
+                .await
+                .map_err(|source| ListAuditRecordsError::Status { source })?
+                .into_inner();
+        Ok(records.into_iter().map(AuditRecord::from).collect())
+    }
+}
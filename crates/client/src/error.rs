@@ -30,6 +30,12 @@ pub enum Error {
         source: tonic::transport::Error,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Could not read TLS file `{}`, error: {source}", path.display()))]
+    ReadTlsFile { path: PathBuf, source: std::io::Error, backtrace: Backtrace },
+
+    #[snafu(display("Both the client certificate and its private key are required for TLS"))]
+    IncompleteTlsIdentity { backtrace: Backtrace },
 }
 
 #[derive(Debug)]
@@ -166,6 +172,19 @@ impl fmt::Display for ListClipError {
     }
 }
 
+#[derive(Debug)]
+pub enum SubscribeError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for SubscribeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum EnableWatcherError {
     Status { source: tonic::Status },
@@ -230,3 +249,29 @@ impl fmt::Display for GetSystemVersionError {
         }
     }
 }
+
+#[derive(Debug)]
+pub enum ReloadConfigError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for ReloadConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ListAuditRecordsError {
+    Status { source: tonic::Status },
+}
+
+impl fmt::Display for ListAuditRecordsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status { source } => source.fmt(f),
+        }
+    }
+}
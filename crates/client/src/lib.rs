@@ -1,7 +1,10 @@
+mod audit;
 pub mod error;
 mod interceptor;
 mod manager;
+mod subscription;
 mod system;
+mod tls;
 mod watcher;
 
 use std::fmt;
@@ -11,9 +14,12 @@ use tokio::net::UnixStream;
 
 use self::interceptor::Interceptor;
 pub use self::{
+    audit::Audit,
     error::{Error, Result},
     manager::Manager,
+    subscription::{Subscription, SubscriptionUpdate},
     system::System,
+    tls::TlsConfig,
     watcher::Watcher,
 };
 
@@ -24,8 +30,15 @@ pub struct Client {
 }
 
 impl Client {
+    /// Connects via HTTP, HTTPS with `tls_config` or local socket, depending
+    /// on the scheme of `grpc_endpoint`.
+    ///
     /// # Errors
-    pub async fn new<A>(grpc_endpoint: http::Uri, access_token: Option<A>) -> Result<Self>
+    pub async fn new<A>(
+        grpc_endpoint: http::Uri,
+        access_token: Option<A>,
+        tls_config: Option<TlsConfig>,
+    ) -> Result<Self>
     where
         A: fmt::Display + Send,
     {
@@ -33,6 +46,8 @@ impl Client {
         let scheme = grpc_endpoint.scheme();
         if scheme == Some(&http::uri::Scheme::HTTP) {
             Self::connect_http(grpc_endpoint, access_token).await
+        } else if scheme == Some(&http::uri::Scheme::HTTPS) {
+            Self::connect_https(grpc_endpoint, access_token, &tls_config.unwrap_or_default()).await
         } else {
             Self::connect_local_socket(grpc_endpoint, access_token).await
         }
@@ -58,6 +73,35 @@ impl Client {
         Ok(Self { channel, interceptor })
     }
 
+    /// # Errors
+    ///
+    /// This function will an error if the TLS files could not be loaded or the
+    /// server is not connected.
+    // SAFETY: it will never panic because `grpc_endpoint` is a valid URL
+    #[allow(clippy::missing_panics_doc)]
+    pub async fn connect_https<A>(
+        grpc_endpoint: http::Uri,
+        access_token: Option<A>,
+        tls_config: &TlsConfig,
+    ) -> Result<Self>
+    where
+        A: fmt::Display + Send,
+    {
+        let interceptor = Interceptor::new(access_token);
+        let channel = tonic::transport::Endpoint::from_shared(grpc_endpoint.to_string())
+            .expect("`grpc_endpoint` is a valid URL; qed")
+            .tls_config(tls_config.load().await?)
+            .with_context(|_| error::ConnectToClipcatServerViaHttpSnafu {
+                endpoint: grpc_endpoint.clone(),
+            })?
+            .connect()
+            .await
+            .with_context(|_| error::ConnectToClipcatServerViaHttpSnafu {
+                endpoint: grpc_endpoint.clone(),
+            })?;
+        Ok(Self { channel, interceptor })
+    }
+
     /// # Errors
     ///
     /// This function will an error if the server is not connected.
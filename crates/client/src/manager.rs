@@ -6,15 +6,22 @@ use tonic::Request;
 use crate::{
     error::{
         BatchRemoveClipError, ClearClipError, GetClipError, GetCurrentClipError, GetLengthError,
-        InsertClipError, ListClipError, MarkClipError, RemoveClipError, UpdateClipError,
+        InsertClipError, ListClipError, MarkClipError, RemoveClipError, SubscribeError,
+        UpdateClipError,
     },
-    Client,
+    Client, Subscription,
 };
 
 #[async_trait]
 pub trait Manager {
     async fn get(&self, id: u64) -> Result<ClipEntry, GetClipError>;
 
+    async fn get_metadata(
+        &self,
+        id: u64,
+        preview_length: usize,
+    ) -> Result<ClipEntryMetadata, GetClipError>;
+
     async fn get_current_clip(&self, kind: ClipboardKind)
         -> Result<ClipEntry, GetCurrentClipError>;
 
@@ -55,6 +62,10 @@ pub trait Manager {
     async fn batch_remove(&self, ids: &[u64]) -> Result<Vec<u64>, BatchRemoveClipError>;
 
     async fn clear(&self) -> Result<(), ClearClipError>;
+
+    /// Subscribes to the events of the clipboard history and of the clipboard
+    /// watcher.
+    async fn subscribe(&self) -> Result<Subscription, SubscribeError>;
 }
 
 #[async_trait]
@@ -69,6 +80,23 @@ impl Manager for Client {
             .map_or_else(|| Err(GetClipError::Empty), |data| Ok(data.into()))
     }
 
+    async fn get_metadata(
+        &self,
+        id: u64,
+        preview_length: usize,
+    ) -> Result<ClipEntryMetadata, GetClipError> {
+        proto::ManagerClient::with_interceptor(self.channel.clone(), self.interceptor.clone())
+            .get_metadata(Request::new(proto::GetMetadataRequest {
+                id,
+                preview_length: u64::try_from(preview_length).unwrap_or(30),
+            }))
+            .await
+            .map_err(|source| GetClipError::Status { source, id })?
+            .into_inner()
+            .metadata
+            .map_or_else(|| Err(GetClipError::Empty), |metadata| Ok(metadata.into()))
+    }
+
     async fn get_current_clip(
         &self,
         kind: ClipboardKind,
@@ -184,4 +212,8 @@ impl Manager for Client {
             .map(|_| ())
             .map_err(|source| ClearClipError::Status { source })
     }
+
+    async fn subscribe(&self) -> Result<Subscription, SubscribeError> {
+        Subscription::new(self.clone()).await
+    }
 }
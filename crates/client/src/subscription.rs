@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use clipcat_base::ClipboardEvent;
+use clipcat_proto as proto;
+use tonic::{Request, Streaming};
+
+use crate::{error::SubscribeError, Client};
+
+/// Delay before subscribing again after the connection is lost.
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// The delay is doubled after every failed attempt, up to this.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// An item of a [`Subscription`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubscriptionUpdate {
+    Event(ClipboardEvent),
+
+    /// The connection to the server is lost, e.g. `clipcatd` is restarting.
+    /// Events are missed until [`SubscriptionUpdate::Reconnected`].
+    Disconnected {
+        reason: String,
+    },
+
+    Reconnected,
+}
+
+/// Follows the events of the server.
+///
+/// If reconnecting is enabled, the subscription is renewed with exponential
+/// backoff after the connection is lost, otherwise it ends.
+pub struct Subscription {
+    client: Client,
+
+    // `None` while disconnected
+    stream: Option<Streaming<proto::ClipboardEvent>>,
+
+    reconnect: bool,
+
+    reconnect_delay: Duration,
+}
+
+impl Subscription {
+    pub(crate) async fn new(client: Client) -> Result<Self, SubscribeError> {
+        let stream = subscribe(&client).await?;
+        Ok(Self {
+            client,
+            stream: Some(stream),
+            reconnect: false,
+            reconnect_delay: INITIAL_RECONNECT_DELAY,
+        })
+    }
+
+    #[must_use]
+    pub const fn with_reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// Waits for the next update, returns `None` if the server closes the
+    /// subscription and reconnecting is disabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the connection is lost and
+    /// reconnecting is disabled.
+    pub async fn next(&mut self) -> Result<Option<SubscriptionUpdate>, SubscribeError> {
+        loop {
+            let Some(ref mut stream) = self.stream else {
+                tokio::time::sleep(self.reconnect_delay).await;
+                match subscribe(&self.client).await {
+                    Ok(stream) => {
+                        self.stream = Some(stream);
+                        self.reconnect_delay = INITIAL_RECONNECT_DELAY;
+                        return Ok(Some(SubscriptionUpdate::Reconnected));
+                    }
+                    Err(err) => {
+                        tracing::debug!("Could not subscribe again, error: {err}");
+                        self.reconnect_delay = (self.reconnect_delay * 2).min(MAX_RECONNECT_DELAY);
+                        continue;
+                    }
+                }
+            };
+
+            let reason = match stream.message().await {
+                Ok(Some(proto::ClipboardEvent { event: Some(event) })) => {
+                    return Ok(Some(SubscriptionUpdate::Event(event.into())));
+                }
+                // events unknown to this client are skipped
+                Ok(Some(proto::ClipboardEvent { event: None })) => continue,
+                Ok(None) if self.reconnect => "Subscription is closed by server".to_string(),
+                Ok(None) => return Ok(None),
+                Err(source) if self.reconnect => source.message().to_string(),
+                Err(source) => return Err(SubscribeError::Status { source }),
+            };
+            self.stream = None;
+            return Ok(Some(SubscriptionUpdate::Disconnected { reason }));
+        }
+    }
+}
+
+async fn subscribe(client: &Client) -> Result<Streaming<proto::ClipboardEvent>, SubscribeError> {
+    Ok(proto::ManagerClient::with_interceptor(client.channel.clone(), client.interceptor.clone())
+        .subscribe(Request::new(()))
+        .await
+        .map_err(|source| SubscribeError::Status { source })?
+        .into_inner())
+}
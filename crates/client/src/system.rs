@@ -2,11 +2,18 @@ use async_trait::async_trait;
 use clipcat_proto as proto;
 use tonic::Request;
 
-use crate::{error::GetSystemVersionError, Client};
+use crate::{
+    error::{GetSystemVersionError, ReloadConfigError},
+    Client,
+};
 
 #[async_trait]
 pub trait System {
     async fn get_version(&self) -> Result<semver::Version, GetSystemVersionError>;
+
+    /// Reloads the configuration of the daemon, returns the settings which
+    /// take effect only after restarting the daemon.
+    async fn reload_config(&self) -> Result<Vec<String>, ReloadConfigError>;
 }
 
 #[async_trait]
@@ -26,4 +33,14 @@ impl System for Client {
             build: semver::BuildMetadata::EMPTY,
         })
     }
+
+    async fn reload_config(&self) -> Result<Vec<String>, ReloadConfigError> {
+        let proto::ReloadConfigResponse { restart_required } =
+            proto::SystemClient::with_interceptor(self.channel.clone(), self.interceptor.clone())
+                .reload_config(Request::new(()))
+                .await
+                .map_err(|source| ReloadConfigError::Status { source })?
+                .into_inner();
+        Ok(restart_required)
+    }
 }
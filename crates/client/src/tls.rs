@@ -0,0 +1,48 @@
+use std::path::{Path, PathBuf};
+
+use snafu::ResultExt;
+use tonic::transport::{Certificate, ClientTlsConfig, Identity};
+
+use crate::error::{self, Error};
+
+/// TLS settings for connecting to the gRPC endpoint over HTTPS.
+#[derive(Clone, Debug, Default)]
+pub struct TlsConfig {
+    /// CA certificate verifying the certificate of the server.
+    pub ca_certificate_file_path: Option<PathBuf>,
+
+    /// Client certificate presented to servers requiring mutual TLS.
+    pub certificate_file_path: Option<PathBuf>,
+
+    /// Private key of the client certificate.
+    pub key_file_path: Option<PathBuf>,
+
+    /// Domain name verified against the certificate of the server, the host
+    /// of the endpoint is used if it is not set.
+    pub domain_name: Option<String>,
+}
+
+impl TlsConfig {
+    pub(crate) async fn load(&self) -> Result<ClientTlsConfig, Error> {
+        let mut tls_config = ClientTlsConfig::new();
+        if let Some(ref path) = self.ca_certificate_file_path {
+            tls_config = tls_config.ca_certificate(Certificate::from_pem(read(path).await?));
+        }
+        match (&self.certificate_file_path, &self.key_file_path) {
+            (Some(certificate), Some(key)) => {
+                tls_config = tls_config
+                    .identity(Identity::from_pem(read(certificate).await?, read(key).await?));
+            }
+            (None, None) => {}
+            _ => return error::IncompleteTlsIdentitySnafu.fail(),
+        }
+        if let Some(ref domain_name) = self.domain_name {
+            tls_config = tls_config.domain_name(domain_name);
+        }
+        Ok(tls_config)
+    }
+}
+
+async fn read(path: &Path) -> Result<Vec<u8>, Error> {
+    tokio::fs::read(path).await.context(error::ReadTlsFileSnafu { path: path.to_path_buf() })
+}
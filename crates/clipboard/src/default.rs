@@ -105,7 +105,12 @@ impl Clipboard {
                 tracing::info!(
                     "Build Wayland listener ({clipboard_kind}) with display `{display_name}`"
                 );
-                Arc::new(WaylandListener::new(clipboard_kind, clip_filter)?)
+                Arc::new(WaylandListener::new(
+                    display_name,
+                    clipboard_kind,
+                    clip_filter,
+                    event_observers,
+                )?)
             } else {
                 match std::env::var("DISPLAY") {
                     Ok(display_name) => {
@@ -16,7 +16,7 @@ pub use clipcat_base::ClipboardKind;
         target_os = "emscripten"
     ))
 ))]
-pub use self::listener::{WaylandListenerError, X11ListenerError};
+pub use self::listener::{WaylandListenerError, X11ListenerError, X11Pointer};
 pub use self::{
     default::Clipboard,
     error::Error,
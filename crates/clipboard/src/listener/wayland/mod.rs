@@ -17,7 +17,8 @@ use wl_clipboard_rs::paste::{
 pub use self::error::Error;
 use crate::{
     pubsub::{self, Subscriber},
-    ClipboardKind, ClipboardSubscribe,
+    traits::EventObserver,
+    ClipboardKind, ClipboardSubscribe, ListenerKind,
 };
 
 const POLLING_INTERVAL: Duration = Duration::from_millis(500);
@@ -31,8 +32,10 @@ pub struct Listener {
 
 impl Listener {
     pub fn new(
+        display_name: String,
         clipboard_kind: ClipboardKind,
         clip_filter: Arc<ClipFilter>,
+        event_observers: Vec<Arc<dyn EventObserver>>,
     ) -> Result<Self, crate::Error> {
         let (notifier, subscriber) = pubsub::new(clipboard_kind);
         let is_running = Arc::new(AtomicBool::new(true));
@@ -52,7 +55,15 @@ impl Listener {
             }
         }
 
-        let thread = build_thread(is_running.clone(), clipboard_type, notifier, clip_filter);
+        let thread = build_thread(
+            is_running.clone(),
+            display_name,
+            clipboard_kind,
+            clipboard_type,
+            notifier,
+            clip_filter,
+            event_observers,
+        );
         Ok(Self { is_running, thread: Some(thread), subscriber })
     }
 }
@@ -75,19 +86,54 @@ impl Drop for Listener {
 #[allow(clippy::cognitive_complexity)]
 fn build_thread(
     is_running: Arc<AtomicBool>,
+    display_name: String,
+    clipboard_kind: ClipboardKind,
     clipboard_type: wl_clipboard_rs::paste::ClipboardType,
     notifier: pubsub::Publisher,
     clip_filter: Arc<ClipFilter>,
+    event_observers: Vec<Arc<dyn EventObserver>>,
 ) -> thread::JoinHandle<Result<(), Error>> {
     // FIXME: re-implement this with event-driven mechanism,
     // polling is not a good enough
     thread::Builder::new()
         .name(format!("{clipboard_type:?}-listener"))
         .spawn(move || {
+            // a new connection is made for each polling, the connection is
+            // regarded as lost until the compositor responds again
+            let mut is_disconnected = false;
             while is_running.load(Ordering::Relaxed) {
                 tracing::trace!("Wait for readiness events");
 
-                match wl_clipboard_get_mime_types(clipboard_type, Seat::Unspecified) {
+                let result = wl_clipboard_get_mime_types(clipboard_type, Seat::Unspecified);
+                let is_connection_lost = matches!(
+                    result,
+                    Err(WaylandError::SocketOpenError(_)
+                        | WaylandError::WaylandConnection(_)
+                        | WaylandError::WaylandCommunication(_))
+                );
+                if is_connection_lost != is_disconnected {
+                    is_disconnected = is_connection_lost;
+                    if !is_disconnected {
+                        tracing::info!("Re-connected to Wayland compositor");
+                    }
+                    for observer in &event_observers {
+                        if is_disconnected {
+                            observer.on_disconnected(
+                                ListenerKind::Wayland,
+                                clipboard_kind,
+                                &display_name,
+                            );
+                        } else {
+                            observer.on_reconnected(
+                                ListenerKind::Wayland,
+                                clipboard_kind,
+                                &display_name,
+                            );
+                        }
+                    }
+                }
+
+                match result {
                     Ok(mime_types) => {
                         if clip_filter.filter_sensitive_mime_type(mime_types.iter()) {
                             tracing::info!("Sensitive content detected, ignore it");
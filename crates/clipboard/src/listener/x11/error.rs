@@ -168,6 +168,38 @@ pub enum Error {
         backtrace: snafu::Backtrace,
     },
 
+    #[snafu(display("XInput 2.1 is not present"))]
+    XInputNotPresent,
+
+    #[snafu(display("Could not query XInput version, error: {source}"))]
+    QueryXInputVersion {
+        source: x11rb::errors::ConnectionError,
+        backtrace: snafu::Backtrace,
+    },
+
+    #[snafu(display("Could not select XInput events, error: {source}"))]
+    SelectXInputEvents {
+        source: x11rb::errors::ConnectionError,
+        backtrace: snafu::Backtrace,
+    },
+
+    #[snafu(display("Could not spawn thread, error: {source}"))]
+    SpawnThread {
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Could not query pointer, error: {source}"))]
+    QueryPointer {
+        source: x11rb::errors::ConnectionError,
+        backtrace: snafu::Backtrace,
+    },
+
+    #[snafu(display("Could not get pointer reply, error: {source}"))]
+    QueryPointerReply {
+        source: x11rb::errors::ReplyError,
+        backtrace: snafu::Backtrace,
+    },
+
     #[snafu(display("Could not synchronise with X11 server, error: {source}"))]
     SynchroniseWithX11 {
         source: x11rb::errors::ReplyError,
@@ -1,5 +1,6 @@
 mod context;
 mod error;
+mod pointer;
 
 use std::{
     os::fd::AsRawFd,
@@ -16,7 +17,7 @@ use snafu::ResultExt;
 use x11rb::protocol::Event as X11Event;
 
 use self::context::Context;
-pub use self::error::Error;
+pub use self::{error::Error, pointer::Pointer};
 use crate::{
     listener::x11::error::InitializeMioPollSnafu,
     pubsub::{self, Subscriber},
@@ -143,6 +144,13 @@ fn build_thread(
                             Ok(_) | Err(Error::NoEvent) => {}
                             Err(err) => {
                                 tracing::warn!("{err}, try to re-connect");
+                                for observer in &event_observers {
+                                    observer.on_disconnected(
+                                        ListenerKind::X11,
+                                        context.clipboard_kind(),
+                                        &context.display_name(),
+                                    );
+                                }
                                 if let Err(err) = try_reconnect(
                                     &poll,
                                     &mut context,
@@ -153,6 +161,11 @@ fn build_thread(
                                     return Err(err);
                                 }
                                 for observer in &event_observers {
+                                    observer.on_reconnected(
+                                        ListenerKind::X11,
+                                        context.clipboard_kind(),
+                                        &context.display_name(),
+                                    );
                                     observer.on_connected(
                                         ListenerKind::X11,
                                         context.clipboard_kind(),
@@ -0,0 +1,189 @@
+use std::{
+    os::fd::AsRawFd,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use snafu::ResultExt;
+use x11rb::{
+    connection::Connection,
+    protocol::{
+        xinput::{self, ConnectionExt as _},
+        xproto::{self, ConnectionExt as _},
+        Event as X11Event,
+    },
+    rust_connection::RustConnection,
+};
+
+use crate::listener::x11::{error, Error};
+
+const CONNECTION_TOKEN: mio::Token = mio::Token(0);
+
+// `XIAllMasterDevices`
+const ALL_MASTER_DEVICES: xinput::DeviceId = 1;
+
+// the left button for dragging and the right button for extending a selection
+const SELECTING_BUTTONS: [u32; 2] = [1, 3];
+
+/// Follows the state of the pointer buttons on a X11 display.
+///
+/// Applications update the primary selection while the user drags the mouse,
+/// a selection is only complete after the button is released. Button presses
+/// and releases are received as `XInput` 2 raw events, which are delivered even
+/// while another client grabs the pointer.
+#[derive(Debug)]
+pub struct Pointer {
+    is_running: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Pointer {
+    /// Starts following the pointer buttons, `on_change` is called with the
+    /// initial state and whenever the user starts or stops selecting.
+    ///
+    /// # Errors
+    pub fn new<F>(display_name: Option<&str>, on_change: F) -> Result<Self, Error>
+    where
+        F: Fn(bool) + Send + 'static,
+    {
+        let (connection, screen_num) =
+            RustConnection::connect(display_name).context(error::ConnectSnafu)?;
+        let root = connection.setup().roots[screen_num].root;
+
+        let xinput = connection
+            .query_extension(xinput::X11_EXTENSION_NAME.as_bytes())
+            .with_context(|_| error::QueryExtensionSnafu {
+                extension_name: xinput::X11_EXTENSION_NAME.to_string(),
+            })?
+            .reply()
+            .context(error::ReplySnafu)?;
+        if !xinput.present {
+            return Err(Error::XInputNotPresent);
+        }
+        // raw events are delivered to the root window regardless of grabs since 2.1
+        let version = connection
+            .xinput_xi_query_version(2, 2)
+            .context(error::QueryXInputVersionSnafu)?
+            .reply()
+            .context(error::ReplySnafu)?;
+        if (version.major_version, version.minor_version) < (2, 1) {
+            return Err(Error::XInputNotPresent);
+        }
+        drop(
+            connection
+                .xinput_xi_select_events(
+                    root,
+                    &[xinput::EventMask {
+                        deviceid: ALL_MASTER_DEVICES,
+                        mask: vec![
+                            xinput::XIEventMask::RAW_BUTTON_PRESS
+                                | xinput::XIEventMask::RAW_BUTTON_RELEASE,
+                        ],
+                    }],
+                )
+                .context(error::SelectXInputEventsSnafu)?,
+        );
+        connection.flush().context(error::FlushConnectionSnafu)?;
+
+        // buttons may be held down already, events only report later changes
+        let reply = connection
+            .query_pointer(root)
+            .context(error::QueryPointerSnafu)?
+            .reply()
+            .context(error::QueryPointerReplySnafu)?;
+        let pressed = ButtonState::from_mask(reply.mask);
+        on_change(pressed.is_selecting());
+
+        let is_running = Arc::new(AtomicBool::new(true));
+        let thread = thread::Builder::new()
+            .name("pointer-listener".to_string())
+            .spawn({
+                let is_running = is_running.clone();
+                move || {
+                    if let Err(err) = follow_buttons(&connection, &is_running, pressed, &on_change)
+                    {
+                        tracing::warn!("Stop following pointer buttons, error: {err}");
+                    }
+                    // do not hold back selections forever
+                    on_change(false);
+                }
+            })
+            .context(error::SpawnThreadSnafu)?;
+
+        Ok(Self { is_running, thread: Some(thread) })
+    }
+}
+
+impl Drop for Pointer {
+    fn drop(&mut self) {
+        self.is_running.store(false, Ordering::Release);
+        drop(self.thread.take().map(thread::JoinHandle::join));
+    }
+}
+
+fn follow_buttons<F>(
+    connection: &RustConnection,
+    is_running: &AtomicBool,
+    mut pressed: ButtonState,
+    on_change: &F,
+) -> Result<(), Error>
+where
+    F: Fn(bool),
+{
+    let mut poll = mio::Poll::new().context(error::InitializeMioPollSnafu)?;
+    let mut events = mio::Events::with_capacity(16);
+    poll.registry()
+        .register(
+            &mut mio::unix::SourceFd(&connection.stream().as_raw_fd()),
+            CONNECTION_TOKEN,
+            mio::Interest::READABLE,
+        )
+        .context(error::RegisterIoResourceSnafu)?;
+
+    while is_running.load(Ordering::Relaxed) {
+        if let Err(err) = poll.poll(&mut events, Some(Duration::from_millis(200))) {
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                tracing::error!("Error occurred while polling for readiness event, error: {err}");
+            }
+            continue;
+        }
+
+        // events may be queued already, drain them all on every wake up
+        while let Some(event) = connection.poll_for_event().context(error::PollForEventSnafu)? {
+            let was_selecting = pressed.is_selecting();
+            match event {
+                X11Event::XinputRawButtonPress(event) => pressed.set(event.detail, true),
+                X11Event::XinputRawButtonRelease(event) => pressed.set(event.detail, false),
+                _ => continue,
+            }
+            if pressed.is_selecting() != was_selecting {
+                on_change(pressed.is_selecting());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct ButtonState([bool; SELECTING_BUTTONS.len()]);
+
+impl ButtonState {
+    fn from_mask(mask: xproto::KeyButMask) -> Self {
+        Self([
+            mask.contains(xproto::KeyButMask::BUTTON1),
+            mask.contains(xproto::KeyButMask::BUTTON3),
+        ])
+    }
+
+    fn set(&mut self, button: u32, pressed: bool) {
+        if let Some(index) = SELECTING_BUTTONS.iter().position(|&b| b == button) {
+            self.0[index] = pressed;
+        }
+    }
+
+    fn is_selecting(self) -> bool { self.0.contains(&true) }
+}
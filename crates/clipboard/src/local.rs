@@ -27,6 +27,14 @@ impl Clipboard {
     #[must_use]
     pub fn new() -> Self { Self::default() }
 
+    #[inline]
+    #[must_use]
+    pub fn with_kind(kind: ClipboardKind) -> Self {
+        let (publisher, subscriber) = pubsub::new(kind);
+        let data = Arc::default();
+        Self { publisher: Arc::new(publisher), subscriber, data }
+    }
+
     #[inline]
     #[must_use]
     pub fn with_content(content: ClipboardContent) -> Self {
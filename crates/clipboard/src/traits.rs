@@ -69,4 +69,24 @@ pub trait EventObserver: Send + Sync {
         _connection_info: &str,
     ) {
     }
+
+    /// Called when a listener loses its connection to the display server,
+    /// the listener keeps retrying until it is re-connected.
+    fn on_disconnected(
+        &self,
+        _backend_kind: ListenerKind,
+        _clipboard_kind: ClipboardKind,
+        _connection_info: &str,
+    ) {
+    }
+
+    /// Called when a listener recovers from a lost connection to the display
+    /// server.
+    fn on_reconnected(
+        &self,
+        _backend_kind: ListenerKind,
+        _clipboard_kind: ClipboardKind,
+        _connection_info: &str,
+    ) {
+    }
 }
@@ -19,6 +19,29 @@ pub struct Entry {
     timestamp: i64,
 }
 
+impl Entry {
+    #[inline]
+    #[must_use]
+    pub const fn id(&self) -> u64 { self.id }
+
+    #[inline]
+    #[must_use]
+    pub fn data(&self) -> &[u8] { &self.data }
+
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> ClipboardKind { self.clipboard_kind }
+
+    #[inline]
+    #[must_use]
+    pub fn mime(&self) -> &str { &self.mime }
+
+    /// Returns the timestamp in seconds since the Unix epoch.
+    #[inline]
+    #[must_use]
+    pub const fn timestamp(&self) -> i64 { self.timestamp }
+}
+
 impl From<clipcat_base::ClipEntry> for Entry {
     fn from(entry: clipcat_base::ClipEntry) -> Self {
         let mime = entry.mime().essence_str().to_owned();
@@ -49,22 +72,64 @@ pub struct EntryMetadata {
     preview: String,
 }
 
+impl EntryMetadata {
+    #[inline]
+    #[must_use]
+    pub const fn id(&self) -> u64 { self.id }
+
+    #[inline]
+    #[must_use]
+    pub fn mime(&self) -> &str { &self.mime }
+
+    #[inline]
+    #[must_use]
+    pub const fn kind(&self) -> ClipboardKind { self.kind }
+
+    /// Returns the timestamp in seconds since the Unix epoch.
+    #[inline]
+    #[must_use]
+    pub const fn timestamp(&self) -> i64 { self.timestamp }
+
+    #[inline]
+    #[must_use]
+    pub fn preview(&self) -> &str { &self.preview }
+}
+
 impl From<clipcat_base::ClipEntryMetadata> for EntryMetadata {
     fn from(metadata: clipcat_base::ClipEntryMetadata) -> Self {
-        let clipcat_base::ClipEntryMetadata { id, kind: clipboard_kind, timestamp, mime, preview } =
-            metadata;
+        let clipcat_base::ClipEntryMetadata {
+            id,
+            kind: clipboard_kind,
+            timestamp,
+            mime,
+            preview,
+            ..
+        } = metadata;
         let mime = mime.essence_str().to_owned();
         let timestamp = timestamp.unix_timestamp();
         Self { id, preview, kind: clipboard_kind.into(), mime, timestamp }
     }
 }
 
+/// The D-Bus interface does not provide the size, the pin and the source of
+/// clips, the converted metadata has the placeholders `size: 0`,
+/// `pinned: false` and `source: ClipSource::History`, they must not be
+/// reported as properties of the clip.
 impl From<EntryMetadata> for clipcat_base::ClipEntryMetadata {
     fn from(EntryMetadata { id, mime, kind, timestamp, preview }: EntryMetadata) -> Self {
         let timestamp = OffsetDateTime::from_unix_timestamp(timestamp)
             .unwrap_or_else(|_| OffsetDateTime::now_utc());
         let clipboard_kind = clipcat_base::ClipboardKind::from(kind);
         let mime = mime::Mime::from_str(&mime).unwrap_or(mime::APPLICATION_OCTET_STREAM);
-        Self { id, kind: clipboard_kind, timestamp, mime, preview }
+        Self {
+            id,
+            kind: clipboard_kind,
+            timestamp,
+            mime,
+            preview,
+            size: 0,
+            pinned: false,
+            source: clipcat_base::ClipSource::default(),
+        }
     }
 }
@@ -2,4 +2,8 @@ pub mod error;
 mod server;
 mod traits;
 
-pub use self::{error::Error, server::start_metrics_server, traits::Metrics};
+pub use self::{
+    error::Error,
+    server::start_metrics_server,
+    traits::{Health, Metrics},
+};
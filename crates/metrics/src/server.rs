@@ -3,8 +3,8 @@ use std::{future::Future, net::SocketAddr, str::FromStr};
 use axum::{
     body::Body,
     extract::Extension,
-    http::{header, HeaderValue},
-    response::Response,
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     routing, Router,
 };
 use bytes::{BufMut, BytesMut};
@@ -51,22 +51,50 @@ where
     Router::new().route("/metrics", routing::get(metrics::<Metrics>)).layer(Extension(m))
 }
 
+async fn healthz() -> &'static str { "ok\n" }
+
+async fn readyz<Health>(Extension(health): Extension<Health>) -> impl IntoResponse
+where
+    Health: traits::Health + 'static,
+{
+    let reasons = health.check_readiness();
+    if reasons.is_empty() {
+        (StatusCode::OK, "ready\n".to_string())
+    } else {
+        let body = reasons.into_iter().map(|reason| reason + "\n").collect::<String>();
+        (StatusCode::SERVICE_UNAVAILABLE, body)
+    }
+}
+
+fn health_index<Health>(health: Health) -> Router
+where
+    Health: traits::Health + 'static,
+{
+    Router::new()
+        .route("/healthz", routing::get(healthz))
+        .route("/readyz", routing::get(readyz::<Health>))
+        .layer(Extension(health))
+}
+
 /// # Errors
 ///
 /// * if it cannot bind server
-pub async fn start_metrics_server<Metrics, ShutdownSignal>(
+pub async fn start_metrics_server<Metrics, Health, ShutdownSignal>(
     listen_address: SocketAddr,
     metrics: Metrics,
+    health: Health,
     shutdown_signal: ShutdownSignal,
 ) -> Result<(), Error>
 where
     Metrics: Clone + traits::Metrics + Send + 'static,
+    Health: traits::Health + 'static,
     ShutdownSignal: Future<Output = ()> + Send + 'static,
 {
     let middleware_stack = tower::ServiceBuilder::new();
 
     let router = Router::new()
         .merge(metrics_index(metrics))
+        .merge(health_index(health))
         .layer(middleware_stack)
         .into_make_service_with_connect_info::<SocketAddr>();
 
@@ -1,3 +1,9 @@
 pub trait Metrics: Clone + Send + Sync {
     fn gather(&self) -> Vec<prometheus::proto::MetricFamily>;
 }
+
+pub trait Health: Clone + Send + Sync {
+    /// Returns the reasons why the service is not ready, the service is ready
+    /// if nothing is returned.
+    fn check_readiness(&self) -> Vec<String>;
+}
@@ -22,25 +22,68 @@ mod proto {
 
     tonic::include_proto!("clipcat");
 }
+mod health {
+    // SAFETY: allow: prost
+    #![allow(
+        unreachable_pub,
+        unused_qualifications,
+        unused_results,
+        clippy::default_trait_access,
+        clippy::derive_partial_eq_without_eq,
+        clippy::doc_markdown,
+        clippy::future_not_send,
+        clippy::missing_const_for_fn,
+        clippy::missing_errors_doc,
+        clippy::must_use_candidate,
+        clippy::return_self_not_must_use,
+        clippy::similar_names,
+        clippy::too_many_lines,
+        clippy::use_self,
+        clippy::wildcard_imports
+    )]
+
+    tonic::include_proto!("grpc.health.v1");
+}
 
 use std::str::FromStr;
 
 use time::OffsetDateTime;
 
-pub use self::proto::{
-    manager_client::ManagerClient,
-    manager_server::{Manager, ManagerServer},
-    system_client::SystemClient,
-    system_server::{System, SystemServer},
-    watcher_client::WatcherClient,
-    watcher_server::{Watcher, WatcherServer},
-    BatchRemoveRequest, BatchRemoveResponse, ClipEntry, ClipEntryMetadata, ClipboardKind,
-    GetCurrentClipRequest, GetCurrentClipResponse, GetRequest, GetResponse,
-    GetSystemVersionResponse, InsertRequest, InsertResponse, LengthResponse, ListRequest,
-    ListResponse, MarkRequest, MarkResponse, RemoveRequest, RemoveResponse, UpdateRequest,
-    UpdateResponse, WatcherState, WatcherStateReply,
+pub use self::{
+    health::{
+        health_check_response::ServingStatus as HealthServingStatus,
+        health_client::HealthClient,
+        health_server::{Health, HealthServer},
+        HealthCheckRequest, HealthCheckResponse,
+    },
+    proto::{
+        audit_client::AuditClient,
+        audit_server::{Audit, AuditServer},
+        clipboard_event::Event as ClipboardEventKind,
+        manager_client::ManagerClient,
+        manager_server::{Manager, ManagerServer},
+        system_client::SystemClient,
+        system_server::{System, SystemServer},
+        watcher_client::WatcherClient,
+        watcher_server::{Watcher, WatcherServer},
+        AuditOperation, AuditRecord, AuditTransport, BatchRemoveRequest, BatchRemoveResponse,
+        ClipEntry, ClipEntryMetadata, ClipMarkedEvent, ClipPinnedEvent, ClipSource, ClipboardEvent,
+        ClipboardKind, GetCurrentClipRequest, GetCurrentClipResponse, GetMetadataRequest,
+        GetMetadataResponse, GetRequest, GetResponse, GetSystemVersionResponse, InsertRequest,
+        InsertResponse, LengthResponse, ListAuditRecordsRequest, ListAuditRecordsResponse,
+        ListRequest, ListResponse, MarkRequest, MarkResponse, ReloadConfigResponse, RemoveRequest,
+        RemoveResponse, UpdateRequest, UpdateResponse, WatcherState, WatcherStateReply,
+    },
 };
 
+/// Names of the services of Clipcat.
+pub const SERVICE_NAMES: [&str; 4] = [
+    proto::audit_server::SERVICE_NAME,
+    proto::manager_server::SERVICE_NAME,
+    proto::system_server::SERVICE_NAME,
+    proto::watcher_server::SERVICE_NAME,
+];
+
 impl From<ClipboardKind> for clipcat_base::ClipboardKind {
     fn from(kind: ClipboardKind) -> Self {
         match kind {
@@ -82,24 +125,71 @@ impl From<ClipEntry> for clipcat_base::ClipEntry {
     }
 }
 
+impl From<ClipSource> for clipcat_base::ClipSource {
+    fn from(source: ClipSource) -> Self {
+        match source {
+            ClipSource::History => Self::History,
+            ClipSource::Snippet => Self::Snippet,
+        }
+    }
+}
+
+impl From<clipcat_base::ClipSource> for ClipSource {
+    fn from(source: clipcat_base::ClipSource) -> Self {
+        match source {
+            clipcat_base::ClipSource::History => Self::History,
+            clipcat_base::ClipSource::Snippet => Self::Snippet,
+        }
+    }
+}
+
 impl From<clipcat_base::ClipEntryMetadata> for ClipEntryMetadata {
     fn from(metadata: clipcat_base::ClipEntryMetadata) -> Self {
-        let clipcat_base::ClipEntryMetadata { id, kind: clipboard_kind, timestamp, mime, preview } =
-            metadata;
+        let clipcat_base::ClipEntryMetadata {
+            id,
+            kind: clipboard_kind,
+            timestamp,
+            mime,
+            preview,
+            size,
+            pinned,
+            source,
+        } = metadata;
         let mime = mime.essence_str().to_owned();
         let timestamp = utils::datetime_to_timestamp(&timestamp);
-        Self { id, preview, kind: clipboard_kind.into(), mime, timestamp: Some(timestamp) }
+        Self {
+            id,
+            preview,
+            kind: clipboard_kind.into(),
+            mime,
+            timestamp: Some(timestamp),
+            size: u64::try_from(size).unwrap_or(u64::MAX),
+            pinned,
+            source: ClipSource::from(source).into(),
+        }
     }
 }
 
 impl From<ClipEntryMetadata> for clipcat_base::ClipEntryMetadata {
-    fn from(ClipEntryMetadata { id, mime, kind, timestamp, preview }: ClipEntryMetadata) -> Self {
+    fn from(
+        ClipEntryMetadata { id, mime, kind, timestamp, preview, size, pinned, source }: ClipEntryMetadata,
+    ) -> Self {
         let timestamp = timestamp
             .and_then(|ts| utils::timestamp_to_datetime(&ts).ok())
             .unwrap_or_else(OffsetDateTime::now_utc);
         let clipboard_kind = clipcat_base::ClipboardKind::from(kind);
         let mime = mime::Mime::from_str(&mime).unwrap_or(mime::APPLICATION_OCTET_STREAM);
-        Self { id, kind: clipboard_kind, timestamp, mime, preview }
+        let source = ClipSource::try_from(source).unwrap_or_default().into();
+        Self {
+            id,
+            kind: clipboard_kind,
+            timestamp,
+            mime,
+            preview,
+            size: usize::try_from(size).unwrap_or(usize::MAX),
+            pinned,
+            source,
+        }
     }
 }
 
@@ -120,3 +210,152 @@ impl From<clipcat_base::ClipboardWatcherState> for WatcherState {
         }
     }
 }
+
+impl From<clipcat_base::ClipboardEvent> for ClipboardEvent {
+    fn from(event: clipcat_base::ClipboardEvent) -> Self {
+        let event = match event {
+            clipcat_base::ClipboardEvent::ClipAdded { metadata } => {
+                ClipboardEventKind::ClipAdded(metadata.into())
+            }
+            clipcat_base::ClipboardEvent::ClipRemoved { id } => ClipboardEventKind::ClipRemoved(id),
+            clipcat_base::ClipboardEvent::ClipMarked { id, kind } => {
+                ClipboardEventKind::ClipMarked(ClipMarkedEvent {
+                    id,
+                    kind: ClipboardKind::from(kind).into(),
+                })
+            }
+            clipcat_base::ClipboardEvent::ClipPinned { id, pinned } => {
+                ClipboardEventKind::ClipPinned(ClipPinnedEvent { id, pinned })
+            }
+            clipcat_base::ClipboardEvent::HistoryCleared => ClipboardEventKind::HistoryCleared(()),
+            clipcat_base::ClipboardEvent::WatcherStateChanged { state } => {
+                ClipboardEventKind::WatcherStateChanged(WatcherState::from(state).into())
+            }
+            clipcat_base::ClipboardEvent::Lagged { missed } => ClipboardEventKind::Lagged(missed),
+        };
+        Self { event: Some(event) }
+    }
+}
+
+impl From<ClipboardEventKind> for clipcat_base::ClipboardEvent {
+    fn from(event: ClipboardEventKind) -> Self {
+        match event {
+            ClipboardEventKind::ClipAdded(metadata) => {
+                Self::ClipAdded { metadata: metadata.into() }
+            }
+            ClipboardEventKind::ClipRemoved(id) => Self::ClipRemoved { id },
+            ClipboardEventKind::ClipMarked(ClipMarkedEvent { id, kind }) => {
+                Self::ClipMarked { id, kind: clipcat_base::ClipboardKind::from(kind) }
+            }
+            ClipboardEventKind::ClipPinned(ClipPinnedEvent { id, pinned }) => {
+                Self::ClipPinned { id, pinned }
+            }
+            ClipboardEventKind::HistoryCleared(()) => Self::HistoryCleared,
+            ClipboardEventKind::WatcherStateChanged(state) => Self::WatcherStateChanged {
+                state: clipcat_base::ClipboardWatcherState::from(state),
+            },
+            ClipboardEventKind::Lagged(missed) => Self::Lagged { missed },
+        }
+    }
+}
+
+impl From<AuditTransport> for clipcat_base::AuditTransport {
+    fn from(transport: AuditTransport) -> Self {
+        match transport {
+            AuditTransport::GrpcHttp => Self::GrpcHttp,
+            AuditTransport::GrpcLocalSocket => Self::GrpcLocalSocket,
+            AuditTransport::DBus => Self::DBus,
+            AuditTransport::HttpApi => Self::HttpApi,
+            AuditTransport::Klipper => Self::Klipper,
+            AuditTransport::SearchProvider => Self::SearchProvider,
+            AuditTransport::DesktopNotification => Self::DesktopNotification,
+        }
+    }
+}
+
+impl From<clipcat_base::AuditTransport> for AuditTransport {
+    fn from(transport: clipcat_base::AuditTransport) -> Self {
+        match transport {
+            clipcat_base::AuditTransport::GrpcHttp => Self::GrpcHttp,
+            clipcat_base::AuditTransport::GrpcLocalSocket => Self::GrpcLocalSocket,
+            clipcat_base::AuditTransport::DBus => Self::DBus,
+            clipcat_base::AuditTransport::HttpApi => Self::HttpApi,
+            clipcat_base::AuditTransport::Klipper => Self::Klipper,
+            clipcat_base::AuditTransport::SearchProvider => Self::SearchProvider,
+            clipcat_base::AuditTransport::DesktopNotification => Self::DesktopNotification,
+        }
+    }
+}
+
+impl From<AuditOperation> for clipcat_base::AuditOperation {
+    fn from(operation: AuditOperation) -> Self {
+        match operation {
+            AuditOperation::Get => Self::Get,
+            AuditOperation::GetCurrentClip => Self::GetCurrentClip,
+            AuditOperation::Mark => Self::Mark,
+            AuditOperation::Remove => Self::Remove,
+            AuditOperation::Clear => Self::Clear,
+            AuditOperation::Export => Self::Export,
+        }
+    }
+}
+
+impl From<clipcat_base::AuditOperation> for AuditOperation {
+    fn from(operation: clipcat_base::AuditOperation) -> Self {
+        match operation {
+            clipcat_base::AuditOperation::Get => Self::Get,
+            clipcat_base::AuditOperation::GetCurrentClip => Self::GetCurrentClip,
+            clipcat_base::AuditOperation::Mark => Self::Mark,
+            clipcat_base::AuditOperation::Remove => Self::Remove,
+            clipcat_base::AuditOperation::Clear => Self::Clear,
+            clipcat_base::AuditOperation::Export => Self::Export,
+        }
+    }
+}
+
+impl From<clipcat_base::AuditRecord> for AuditRecord {
+    fn from(record: clipcat_base::AuditRecord) -> Self {
+        let clipcat_base::AuditRecord {
+            timestamp,
+            transport,
+            operation,
+            token_name,
+            peer_uid,
+            peer_pid,
+            peer_address,
+            clip_ids,
+        } = record;
+        Self {
+            timestamp: Some(utils::datetime_to_timestamp(&timestamp)),
+            transport: AuditTransport::from(transport).into(),
+            operation: AuditOperation::from(operation).into(),
+            token_name,
+            peer_uid,
+            peer_pid,
+            peer_address,
+            clip_ids,
+        }
+    }
+}
+
+impl From<AuditRecord> for clipcat_base::AuditRecord {
+    fn from(record: AuditRecord) -> Self {
+        let timestamp = record
+            .timestamp
+            .and_then(|ts| utils::timestamp_to_datetime(&ts).ok())
+            .unwrap_or_else(OffsetDateTime::now_utc);
+        let transport = record.transport().into();
+        let operation = record.operation().into();
+        let AuditRecord { token_name, peer_uid, peer_pid, peer_address, clip_ids, .. } = record;
+        Self {
+            timestamp,
+            transport,
+            operation,
+            token_name,
+            peer_uid,
+            peer_pid,
+            peer_address,
+            clip_ids,
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use clipcat_base::{AuditOperation, AuditRecord, AuditTransport};
+use snafu::ResultExt;
+use time::OffsetDateTime;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::Mutex,
+};
+
+use crate::{
+    config::AuditLogConfig,
+    error::{self, Error},
+};
+
+/// Only the owner can read the audit log.
+const FILE_MODE: u32 = 0o600;
+
+/// Identity of the peer which performs an audited operation.
+#[derive(Clone, Debug)]
+pub struct Peer {
+    transport: AuditTransport,
+
+    token_name: Option<String>,
+
+    uid: Option<u32>,
+
+    pid: Option<i32>,
+
+    address: Option<String>,
+}
+
+impl Peer {
+    pub const fn new(transport: AuditTransport) -> Self {
+        Self { transport, token_name: None, uid: None, pid: None, address: None }
+    }
+
+    #[must_use]
+    pub fn with_token_name<S>(mut self, token_name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.token_name = Some(token_name.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn with_credentials(mut self, uid: Option<u32>, pid: Option<i32>) -> Self {
+        self.uid = uid;
+        self.pid = pid;
+        self
+    }
+
+    #[must_use]
+    pub fn with_address<S>(mut self, address: Option<S>) -> Self
+    where
+        S: ToString,
+    {
+        self.address = address.map(|address| address.to_string());
+        self
+    }
+
+    fn record(&self, operation: AuditOperation, clip_ids: Vec<u64>) -> AuditRecord {
+        AuditRecord {
+            timestamp: OffsetDateTime::now_utc(),
+            transport: self.transport,
+            operation,
+            token_name: self.token_name.clone(),
+            peer_uid: self.uid,
+            peer_pid: self.pid,
+            peer_address: self.address.clone(),
+            clip_ids,
+        }
+    }
+}
+
+/// Append-only log of accesses to the history, one JSON record per line.
+///
+/// Nothing is recorded if it is created with [`AuditLog::default`].
+#[derive(Clone, Debug, Default)]
+pub struct AuditLog {
+    writer: Option<Arc<Mutex<Writer>>>,
+}
+
+impl AuditLog {
+    /// Opens the audit log for appending, the parent directory is created if
+    /// it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file could not be opened.
+    pub async fn open(config: AuditLogConfig) -> Result<Self, Error> {
+        if let Some(parent) = config.file_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .context(error::OpenAuditLogSnafu { path: parent.to_path_buf() })?;
+        }
+        let file = open_file(&config.file_path).await?;
+        let size = file
+            .metadata()
+            .await
+            .context(error::OpenAuditLogSnafu { path: config.file_path.clone() })?
+            .len();
+        Ok(Self { writer: Some(Arc::new(Mutex::new(Writer { config, file, size }))) })
+    }
+
+    /// Records `operation` performed by `peer` on the clips with `clip_ids`,
+    /// failures are logged but not returned since they must not fail the
+    /// operation itself.
+    pub async fn record(&self, peer: &Peer, operation: AuditOperation, clip_ids: Vec<u64>) {
+        let Some(ref writer) = self.writer else {
+            return;
+        };
+        let mut line =
+            serde_json::to_vec(&peer.record(operation, clip_ids)).expect("record is serializable");
+        line.push(b'\n');
+        if let Err(err) = writer.lock().await.write(&line).await {
+            tracing::warn!("{err}");
+        }
+    }
+
+    /// Returns the newest `limit` records from the oldest to the newest, all
+    /// records are returned if `limit` is 0.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the files could not be read.
+    pub async fn list(&self, limit: usize) -> Result<Vec<AuditRecord>, Error> {
+        let Some(ref writer) = self.writer else {
+            return Ok(Vec::new());
+        };
+        // only opening the files blocks writing, the opened files are read after
+        // unlocking, they are not affected by rotation
+        let files = {
+            let writer = writer.lock().await;
+            let AuditLogConfig { ref file_path, max_files, .. } = writer.config;
+            let paths = (1..=max_files).rev().map(|index| rotated_path(file_path, index));
+            let mut files = Vec::new();
+            for path in paths.chain([file_path.clone()]) {
+                match File::open(&path).await {
+                    Ok(file) => files.push((path, file)),
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                    Err(source) => return Err(Error::ReadAuditLog { path, source }),
+                }
+            }
+            drop(writer);
+            files
+        };
+
+        let mut records = Vec::new();
+        for (path, mut file) in files {
+            let mut data = String::new();
+            let _size = file
+                .read_to_string(&mut data)
+                .await
+                .context(error::ReadAuditLogSnafu { path: path.clone() })?;
+            records.extend(data.lines().filter_map(|line| {
+                serde_json::from_str::<AuditRecord>(line)
+                    .map_err(|err| {
+                        tracing::warn!(
+                            "Skip malformed record in `{}`, error: {err}",
+                            path.display()
+                        );
+                    })
+                    .ok()
+            }));
+        }
+
+        if limit != 0 && records.len() > limit {
+            let _unused = records.drain(..records.len() - limit);
+        }
+        Ok(records)
+    }
+}
+
+#[derive(Debug)]
+struct Writer {
+    config: AuditLogConfig,
+
+    file: File,
+
+    size: u64,
+}
+
+impl Writer {
+    async fn write(&mut self, line: &[u8]) -> Result<(), Error> {
+        let len = line.len() as u64;
+        if self.size > 0 && self.size + len > self.config.max_file_size {
+            self.rotate().await?;
+        }
+        let path = &self.config.file_path;
+        self.file
+            .write_all(line)
+            .await
+            .context(error::WriteAuditLogSnafu { path: path.clone() })?;
+        self.file.flush().await.context(error::WriteAuditLogSnafu { path: path.clone() })?;
+        self.size += len;
+        Ok(())
+    }
+
+    // `audit.log` is renamed to `audit.log.1`, `audit.log.1` is renamed to
+    // `audit.log.2` and so on, the oldest one is overwritten
+    async fn rotate(&mut self) -> Result<(), Error> {
+        let AuditLogConfig { ref file_path, max_files, .. } = self.config;
+        let rename = |from: PathBuf, to: PathBuf| async move {
+            match tokio::fs::rename(&from, &to).await {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                    Err(Error::WriteAuditLog { path: from, source: err })
+                }
+                _ => Ok(()),
+            }
+        };
+        if max_files == 0 {
+            tokio::fs::remove_file(file_path)
+                .await
+                .context(error::WriteAuditLogSnafu { path: file_path.clone() })?;
+        } else {
+            for index in (1..max_files).rev() {
+                rename(rotated_path(file_path, index), rotated_path(file_path, index + 1)).await?;
+            }
+            rename(file_path.clone(), rotated_path(file_path, 1)).await?;
+        }
+
+        tracing::info!("Audit log `{}` is rotated", file_path.display());
+        self.file = open_file(file_path).await?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+async fn open_file(path: &Path) -> Result<File, Error> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(FILE_MODE)
+        .open(path)
+        .await
+        .context(error::OpenAuditLogSnafu { path: path.to_path_buf() })
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{index}"));
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use clipcat_base::{AuditOperation, AuditTransport};
+
+    use super::{rotated_path, AuditLog, Peer};
+    use crate::config::AuditLogConfig;
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let directory = tempfile::tempdir().unwrap();
+        let file_path = directory.path().join("audit.log");
+        let audit_log = AuditLog::open(AuditLogConfig {
+            file_path: file_path.clone(),
+            max_file_size: 512,
+            max_files: 2,
+        })
+        .await
+        .unwrap();
+
+        let peer = Peer::new(AuditTransport::GrpcLocalSocket)
+            .with_token_name("default")
+            .with_credentials(Some(1000), Some(42));
+        for id in 0..20 {
+            audit_log.record(&peer, AuditOperation::Get, vec![id]).await;
+        }
+        audit_log.record(&Peer::new(AuditTransport::DBus), AuditOperation::Clear, Vec::new()).await;
+
+        assert!(rotated_path(&file_path, 2).exists());
+        assert!(!rotated_path(&file_path, 3).exists());
+
+        let records = audit_log.list(3).await.unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].clip_ids, [18]);
+        assert_eq!(records[1].token_name.as_deref(), Some("default"));
+        assert_eq!(records[1].peer_uid, Some(1000));
+        assert_eq!(records[2].transport, AuditTransport::DBus);
+        assert_eq!(records[2].operation, AuditOperation::Clear);
+        assert!(records[2].token_name.is_none());
+
+        // the oldest records are dropped by rotation
+        let records = audit_log.list(0).await.unwrap();
+        assert!(records.len() < 21);
+        assert_eq!(records.last().unwrap().operation, AuditOperation::Clear);
+
+        assert!(AuditLog::default().list(0).await.unwrap().is_empty());
+    }
+}
@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use clipcat_base::{ClipFilter, ClipboardContent, ClipboardKind};
 use clipcat_clipboard::{Clipboard, ClipboardLoad, ClipboardStore, ClipboardSubscribe};
 use snafu::ResultExt;
-use tokio::task;
+use tokio::{sync::watch, task};
 
 use crate::backend::{error, traits, Error, Result, Subscriber};
 
@@ -13,6 +13,18 @@ pub struct Backend {
     clipboards: Vec<Arc<Clipboard>>,
 
     supported_clipboard_kinds: Vec<ClipboardKind>,
+
+    #[cfg(all(
+        unix,
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android",
+            target_os = "emscripten"
+        ))
+    ))]
+    // the receiver is updated by the pointer while it is alive
+    pointer: Option<(Arc<clipcat_clipboard::X11Pointer>, watch::Receiver<bool>)>,
 }
 
 impl Backend {
@@ -57,7 +69,48 @@ impl Backend {
             }
         }
 
-        Ok(Self { clipboards, supported_clipboard_kinds })
+        #[cfg(all(
+            unix,
+            not(any(
+                target_os = "macos",
+                target_os = "ios",
+                target_os = "android",
+                target_os = "emscripten"
+            ))
+        ))]
+        let pointer = if supported_clipboard_kinds.contains(&ClipboardKind::Primary)
+            && std::env::var_os("WAYLAND_DISPLAY").is_none()
+        {
+            let display_name = std::env::var("DISPLAY").ok();
+            let (sender, receiver) = watch::channel(false);
+            let on_change = move |is_selecting| {
+                let _unused = sender.send_replace(is_selecting);
+            };
+            match clipcat_clipboard::X11Pointer::new(display_name.as_deref(), on_change) {
+                Ok(pointer) => Some((Arc::new(pointer), receiver)),
+                Err(err) => {
+                    tracing::info!("Could not watch pointer buttons, error: {err}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        Ok(Self {
+            clipboards,
+            supported_clipboard_kinds,
+            #[cfg(all(
+                unix,
+                not(any(
+                    target_os = "macos",
+                    target_os = "ios",
+                    target_os = "android",
+                    target_os = "emscripten"
+                ))
+            ))]
+            pointer,
+        })
     }
 
     #[inline]
@@ -125,4 +178,17 @@ impl traits::Backend for Backend {
     fn supported_clipboard_kinds(&self) -> Vec<ClipboardKind> {
         self.supported_clipboard_kinds.clone()
     }
+
+    #[cfg(all(
+        unix,
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android",
+            target_os = "emscripten"
+        ))
+    ))]
+    fn watch_selecting(&self) -> Option<watch::Receiver<bool>> {
+        self.pointer.as_ref().map(|(_pointer, receiver)| receiver.clone())
+    }
 }
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clipcat_base::ClipboardKind;
 use snafu::Snafu;
 
@@ -24,6 +26,45 @@ pub enum Error {
     #[snafu(display("Unknown Content type"))]
     UnknownContentType,
 
+    #[snafu(display("Content type is not supported by backend, content type: {}", mime.essence_str()))]
+    UnsupportedContentType { mime: mime::Mime },
+
+    #[snafu(display("Could not open terminal `{}`, error: {source}", path.display()))]
+    OpenTerminal { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not write to terminal `{}`, error: {source}", path.display()))]
+    WriteTerminal { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not create directory `{}`, error: {source}", path.display()))]
+    CreateDirectory { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not read metadata of `{}`, error: {source}", path.display()))]
+    ReadFileMetadata { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("`{}` is neither a directory nor a named pipe", path.display()))]
+    UnsupportedFileType { path: PathBuf },
+
+    #[snafu(display(
+        "Named pipe `{}` is also the output file, set another output directory",
+        path.display()
+    ))]
+    OutputFileIsInput { path: PathBuf },
+
+    #[snafu(display("Could not watch directory `{}`, error: {source}", path.display()))]
+    WatchDirectory { path: PathBuf, source: notify::Error },
+
+    #[snafu(display("Could not write file `{}`, error: {source}", path.display()))]
+    WriteFile { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not spawn thread, error: {source}"))]
+    SpawnThread { source: std::io::Error },
+
+    #[snafu(display("Could not run tmux, error: {source}"))]
+    RunTmux { source: std::io::Error },
+
+    #[snafu(display("tmux exited with error: {message}"))]
+    TmuxCommand { message: String },
+
     #[snafu(display("Could not initialize clipboard, error: {source}"))]
     InitializeClipboard { source: clipcat_clipboard::Error },
 
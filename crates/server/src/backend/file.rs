@@ -0,0 +1,438 @@
+use std::{
+    io::Read,
+    os::unix::fs::{FileTypeExt, OpenOptionsExt},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use clipcat_base::{ClipEntry, ClipboardContent, ClipboardKind};
+use clipcat_clipboard::{ClipboardLoad, ClipboardStore, ClipboardSubscribe, LocalClipboard};
+use notify::{event, Event, EventKind, RecursiveMode, Watcher};
+use snafu::ResultExt;
+use tokio::task;
+
+use crate::{
+    backend::{error, traits, Error, Result, Subscriber},
+    config::FileBackendConfig,
+};
+
+/// A headless clipboard backend using a directory or a named pipe as the
+/// clipboard.
+///
+/// Every file written into the directory, or every write session on the named
+/// pipe, becomes the new contents of the configured clipboard kind. Contents
+/// stored into a clipboard kind are written to a file named after the kind in
+/// the output directory, e.g. `<output_directory>/clipboard`.
+pub struct Backend {
+    clipboards: Vec<Arc<LocalClipboard>>,
+
+    supported_clipboard_kinds: Vec<ClipboardKind>,
+
+    output_directory: PathBuf,
+
+    _input: Input,
+}
+
+enum Input {
+    Directory { _watcher: notify::RecommendedWatcher },
+    Fifo { path: PathBuf, stopped: Arc<AtomicBool> },
+}
+
+impl Drop for Input {
+    fn drop(&mut self) {
+        if let Self::Fifo { path, stopped } = self {
+            stopped.store(true, Ordering::Release);
+            // the reader thread is blocked until a writer opens the named pipe, wake it up
+            // so it can notice that it is stopped
+            drop(std::fs::OpenOptions::new().write(true).custom_flags(libc::O_NONBLOCK).open(path));
+        }
+    }
+}
+
+impl Backend {
+    /// # Errors
+    pub fn new<I>(kinds: I, config: &FileBackendConfig) -> Result<Self>
+    where
+        I: IntoIterator<Item = ClipboardKind>,
+    {
+        let FileBackendConfig { path, output_directory, clipboard_kind } = config;
+        // the clipboard kind fed by the input is always supported
+        let supported_clipboard_kinds = {
+            let mut kinds = kinds.into_iter().chain([*clipboard_kind]).collect::<Vec<_>>();
+            kinds.sort_unstable();
+            kinds.dedup();
+            kinds
+        };
+
+        if !path.exists() {
+            std::fs::create_dir_all(path)
+                .context(error::CreateDirectorySnafu { path: path.clone() })?;
+        }
+        let file_type = std::fs::metadata(path)
+            .context(error::ReadFileMetadataSnafu { path: path.clone() })?
+            .file_type();
+
+        let clipboards = ClipboardKind::all_kinds()
+            .into_iter()
+            .map(|kind| Arc::new(LocalClipboard::with_kind(kind)))
+            .collect::<Vec<_>>();
+        let clipboard = clipboards[usize::from(*clipboard_kind)].clone();
+
+        let (input, default_output_directory) = if file_type.is_dir() {
+            tracing::info!("Use directory `{}` as clipboard", path.display());
+            let output_directory = output_directory.clone().unwrap_or_else(|| path.clone());
+            let output_files = ClipboardKind::all_kinds()
+                .into_iter()
+                .map(|kind| output_file_path(&output_directory, kind))
+                .collect();
+            let mut watcher = notify::recommended_watcher(EventHandler { clipboard, output_files })
+                .context(error::WatchDirectorySnafu { path: path.clone() })?;
+            watcher
+                .watch(path, RecursiveMode::NonRecursive)
+                .context(error::WatchDirectorySnafu { path: path.clone() })?;
+            (Input::Directory { _watcher: watcher }, path.clone())
+        } else if file_type.is_fifo() {
+            let parent = path.parent().map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+            // storing into the named pipe would block and feed the contents back
+            let output_directory = output_directory.as_deref().unwrap_or(&parent);
+            if supported_clipboard_kinds
+                .iter()
+                .any(|kind| output_file_path(output_directory, *kind) == *path)
+            {
+                return Err(Error::OutputFileIsInput { path: path.clone() });
+            }
+
+            tracing::info!("Use named pipe `{}` as clipboard", path.display());
+            let stopped = Arc::new(AtomicBool::new(false));
+            let _handle = std::thread::Builder::new()
+                .name("clipcat-fifo".to_string())
+                .spawn({
+                    let path = path.clone();
+                    let stopped = stopped.clone();
+                    move || read_fifo(&path, &clipboard, &stopped)
+                })
+                .context(error::SpawnThreadSnafu)?;
+            (Input::Fifo { path: path.clone(), stopped }, parent)
+        } else {
+            return Err(Error::UnsupportedFileType { path: path.clone() });
+        };
+
+        Ok(Self {
+            clipboards,
+            supported_clipboard_kinds,
+            output_directory: output_directory.clone().unwrap_or(default_output_directory),
+            _input: input,
+        })
+    }
+
+    #[inline]
+    fn select_clipboard(&self, kind: ClipboardKind) -> Result<Arc<LocalClipboard>> {
+        if self.supported_clipboard_kinds.contains(&kind) {
+            self.clipboards
+                .get(usize::from(kind))
+                .cloned()
+                .ok_or(Error::UnsupportedClipboardKind { kind })
+        } else {
+            Err(Error::UnsupportedClipboardKind { kind })
+        }
+    }
+}
+
+#[async_trait]
+impl traits::Backend for Backend {
+    async fn load(
+        &self,
+        kind: ClipboardKind,
+        mime: Option<mime::Mime>,
+    ) -> Result<ClipboardContent> {
+        let clipboard = self.select_clipboard(kind)?;
+        task::spawn_blocking(move || match clipboard.load(mime) {
+            Ok(data) => Ok(data),
+            Err(clipcat_clipboard::Error::Empty) => Err(Error::EmptyClipboard),
+            Err(source) => Err(Error::LoadDataFromClipboard { source }),
+        })
+        .await
+        .context(error::SpawnBlockingTaskSnafu)?
+    }
+
+    async fn store(&self, kind: ClipboardKind, data: ClipboardContent) -> Result<()> {
+        let clipboard = self.select_clipboard(kind)?;
+        let path = output_file_path(&self.output_directory, kind);
+        task::spawn_blocking(move || {
+            let encoded = ClipEntry::from_clipboard_content(data.clone(), kind, None)
+                .encoded()
+                .map_err(|_| Error::UnsupportedContentType { mime: data.mime() })?;
+            std::fs::write(&path, encoded).context(error::WriteFileSnafu { path })?;
+            clipboard.store(data).context(error::StoreDataToClipboardSnafu)
+        })
+        .await
+        .context(error::SpawnBlockingTaskSnafu)?
+    }
+
+    async fn clear(&self, kind: ClipboardKind) -> Result<()> {
+        let clipboard = self.select_clipboard(kind)?;
+        let path = output_file_path(&self.output_directory, kind);
+        task::spawn_blocking(move || {
+            match std::fs::remove_file(&path) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(Error::WriteFile { path, source: err });
+                }
+                _ => {}
+            }
+            clipboard.clear().context(error::ClearClipboardSnafu)
+        })
+        .await
+        .context(error::SpawnBlockingTaskSnafu)?
+    }
+
+    fn subscribe(&self) -> Result<Subscriber> {
+        let subscribers = self
+            .supported_clipboard_kinds
+            .iter()
+            .map(|&kind| {
+                self.select_clipboard(kind)?.subscribe().context(error::SubscribeClipboardSnafu)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Subscriber::from(subscribers))
+    }
+
+    #[inline]
+    fn supported_clipboard_kinds(&self) -> Vec<ClipboardKind> {
+        self.supported_clipboard_kinds.clone()
+    }
+}
+
+fn output_file_path(output_directory: &Path, kind: ClipboardKind) -> PathBuf {
+    output_directory.join(kind.as_str().to_lowercase())
+}
+
+fn parse_contents(path: &Path, data: &[u8]) -> Option<ClipboardContent> {
+    if data.is_empty() {
+        return None;
+    }
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase);
+    let mime = match extension.as_deref() {
+        Some("png") => mime::IMAGE_PNG,
+        Some("jpg" | "jpeg") => mime::IMAGE_JPEG,
+        Some("gif") => mime::IMAGE_GIF,
+        Some("bmp") => mime::IMAGE_BMP,
+        _ if simdutf8::basic::from_utf8(data).is_ok() => mime::TEXT_PLAIN_UTF_8,
+        _ => {
+            tracing::warn!("Contents of `{}` is neither image nor UTF-8 text", path.display());
+            return None;
+        }
+    };
+
+    match ClipEntry::new(data, &mime, ClipboardKind::Clipboard, None) {
+        Ok(clip) => Some(clip.as_ref().clone()),
+        Err(err) => {
+            tracing::warn!("Could not load `{}`, error: {err}", path.display());
+            None
+        }
+    }
+}
+
+fn store_contents(clipboard: &LocalClipboard, path: &Path, data: &[u8]) {
+    if let Some(contents) = parse_contents(path, data) {
+        tracing::debug!("Load clip from `{}`", path.display());
+        if let Err(err) = clipboard.store(contents) {
+            tracing::warn!("Could not store contents of `{}`, error: {err}", path.display());
+        }
+    }
+}
+
+// every time all writers close the named pipe, the collected data becomes a new
+// clip
+fn read_fifo(path: &Path, clipboard: &LocalClipboard, stopped: &AtomicBool) {
+    loop {
+        let mut data = Vec::new();
+        let result = std::fs::File::open(path).and_then(|mut fifo| fifo.read_to_end(&mut data));
+        if stopped.load(Ordering::Acquire) {
+            return;
+        }
+        match result {
+            Ok(_) => store_contents(clipboard, path, &data),
+            Err(err) => {
+                tracing::error!("Could not read named pipe `{}`, error: {err}", path.display());
+                return;
+            }
+        }
+    }
+}
+
+struct EventHandler {
+    clipboard: Arc<LocalClipboard>,
+
+    // files written by `clipcatd` itself, they are not clipboard inputs
+    output_files: Vec<PathBuf>,
+}
+
+impl EventHandler {
+    fn on_file_written(&self, paths: Vec<PathBuf>) {
+        for path in paths {
+            let is_hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map_or(true, |name| name.starts_with('.'));
+            if is_hidden || self.output_files.contains(&path) || !path.is_file() {
+                continue;
+            }
+
+            match std::fs::read(&path) {
+                Ok(data) => store_contents(&self.clipboard, &path, &data),
+                Err(err) => tracing::warn!("Could not read `{}`, error: {err}", path.display()),
+            }
+        }
+    }
+}
+
+impl notify::EventHandler for EventHandler {
+    fn handle_event(&mut self, event: notify::Result<Event>) {
+        match event {
+            Ok(event) => match event.kind {
+                EventKind::Access(event::AccessKind::Close(event::AccessMode::Write))
+                | EventKind::Modify(event::ModifyKind::Name(event::RenameMode::To)) => {
+                    self.on_file_written(event.paths);
+                }
+                _ => {}
+            },
+            Err(err) => tracing::warn!("Error occurs while watching file system, error: {err:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use clipcat_base::{ClipboardContent, ClipboardKind};
+
+    use super::Backend;
+    use crate::{
+        backend::{ClipboardBackend, Error, Subscriber},
+        config::FileBackendConfig,
+    };
+
+    // the subscriber misses changes made before it starts waiting, so the input
+    // is written again until the change is reported
+    async fn write_until_reported<F>(subscriber: &mut Subscriber, write: F) -> ClipboardKind
+    where
+        F: Fn() -> std::io::Result<()> + Clone + Send + 'static,
+    {
+        for _ in 0..50 {
+            tokio::task::spawn_blocking(write.clone()).await.unwrap().unwrap();
+            if let Ok(Some((kind, _mime))) =
+                tokio::time::timeout(Duration::from_millis(100), subscriber.next()).await
+            {
+                return kind;
+            }
+        }
+        panic!("change is not reported");
+    }
+
+    #[tokio::test]
+    async fn test_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().to_path_buf();
+        let config = FileBackendConfig {
+            path: path.clone(),
+            output_directory: None,
+            clipboard_kind: ClipboardKind::Clipboard,
+        };
+        let backend = Backend::new([ClipboardKind::Primary], &config).unwrap();
+        assert_eq!(
+            backend.supported_clipboard_kinds(),
+            vec![ClipboardKind::Clipboard, ClipboardKind::Primary]
+        );
+        let mut subscriber = backend.subscribe().unwrap();
+
+        let note_path = path.join("note.txt");
+        let kind = write_until_reported(&mut subscriber, move || {
+            std::fs::write(&note_path, "written by a tool")
+        })
+        .await;
+        assert_eq!(kind, ClipboardKind::Clipboard);
+        assert_eq!(
+            backend.load(kind, None).await.unwrap(),
+            ClipboardContent::Plaintext("written by a tool".to_string())
+        );
+
+        backend
+            .store(ClipboardKind::Primary, ClipboardContent::Plaintext("marked".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(path.join("primary")).unwrap(), "marked");
+
+        assert!(matches!(
+            backend
+                .store(ClipboardKind::Secondary, ClipboardContent::Plaintext("marked".to_string()))
+                .await,
+            Err(Error::UnsupportedClipboardKind { kind: ClipboardKind::Secondary })
+        ));
+        assert!(!path.join("secondary").exists());
+    }
+
+    #[tokio::test]
+    async fn test_fifo() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("clipboard.fifo");
+        let status = std::process::Command::new("mkfifo").arg(&path).status().unwrap();
+        assert!(status.success());
+
+        let config = FileBackendConfig {
+            path: path.clone(),
+            output_directory: None,
+            clipboard_kind: ClipboardKind::Clipboard,
+        };
+        let backend = Backend::new([ClipboardKind::Clipboard], &config).unwrap();
+        let mut subscriber = backend.subscribe().unwrap();
+
+        // opening the named pipe for writing blocks until the backend reads it
+        let fifo_path = path.clone();
+        let kind = write_until_reported(&mut subscriber, move || {
+            std::fs::write(&fifo_path, "piped from a tool")
+        })
+        .await;
+        assert_eq!(
+            backend.load(kind, None).await.unwrap(),
+            ClipboardContent::Plaintext("piped from a tool".to_string())
+        );
+
+        backend
+            .store(ClipboardKind::Clipboard, ClipboardContent::Plaintext("marked".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(directory.path().join("clipboard")).unwrap(), "marked");
+    }
+
+    #[test]
+    fn test_fifo_as_output_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("clipboard");
+        let status = std::process::Command::new("mkfifo").arg(&path).status().unwrap();
+        assert!(status.success());
+
+        let config = FileBackendConfig {
+            path: path.clone(),
+            output_directory: None,
+            clipboard_kind: ClipboardKind::Clipboard,
+        };
+        assert!(matches!(
+            Backend::new([ClipboardKind::Clipboard], &config),
+            Err(Error::OutputFileIsInput { path: output_path }) if output_path == path
+        ));
+
+        // contents are stored into another directory
+        let output_directory = tempfile::tempdir().unwrap();
+        let config = FileBackendConfig {
+            output_directory: Some(output_directory.path().to_path_buf()),
+            ..config
+        };
+        assert!(Backend::new([ClipboardKind::Clipboard], &config).is_ok());
+    }
+}
@@ -1,7 +1,10 @@
 mod default;
 mod error;
+mod file;
 mod local;
+mod osc52;
 mod subscriber;
+mod tmux;
 mod traits;
 
 use std::sync::Arc;
@@ -12,9 +15,11 @@ use clipcat_clipboard::EventObserver;
 use self::error::Result;
 pub use self::{
     default::Backend as DefaultClipboardBackend, error::Error,
-    local::Backend as LocalClipboardBackend, subscriber::Subscriber,
-    traits::Backend as ClipboardBackend,
+    file::Backend as FileClipboardBackend, local::Backend as LocalClipboardBackend,
+    osc52::Backend as Osc52ClipboardBackend, subscriber::Subscriber,
+    tmux::Backend as TmuxClipboardBackend, traits::Backend as ClipboardBackend,
 };
+use crate::config::{BackendConfig, TmuxConfig};
 
 /// # Errors
 pub fn new<I>(
@@ -38,6 +43,8 @@ where
 
 /// # Errors
 pub fn new_shared<I>(
+    config: &BackendConfig,
+    tmux_config: &TmuxConfig,
     kinds: I,
     clip_filter: &Arc<ClipFilter>,
     event_observers: &[Arc<dyn EventObserver>],
@@ -45,13 +52,22 @@ pub fn new_shared<I>(
 where
     I: IntoIterator<Item = ClipboardKind>,
 {
-    DefaultClipboardBackend::new(kinds, clip_filter, event_observers).map_or_else::<Result<
-        Arc<dyn traits::Backend>,
-    >, _, _>(
-        |err| {
-            tracing::warn!("{err}");
-            Ok(Arc::new(LocalClipboardBackend::new()))
-        },
-        |backend| Ok(Arc::new(backend)),
-    )
+    let backend: Arc<dyn traits::Backend> = match config {
+        BackendConfig::Default => DefaultClipboardBackend::new(kinds, clip_filter, event_observers)
+            .map_or_else::<Arc<dyn traits::Backend>, _, _>(
+                |err| {
+                    tracing::warn!("{err}");
+                    Arc::new(LocalClipboardBackend::new())
+                },
+                |backend| Arc::new(backend),
+            ),
+        BackendConfig::Osc52(config) => Arc::new(Osc52ClipboardBackend::new(kinds, config)),
+        BackendConfig::File(config) => Arc::new(FileClipboardBackend::new(kinds, config)?),
+    };
+
+    if tmux_config.enable {
+        Ok(Arc::new(TmuxClipboardBackend::new(backend, tmux_config)))
+    } else {
+        Ok(backend)
+    }
 }
@@ -0,0 +1,356 @@
+use std::{
+    fs::OpenOptions,
+    io::{Read, Write},
+    os::fd::AsRawFd,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use base64::Engine;
+use clipcat_base::{ClipboardContent, ClipboardKind};
+use clipcat_clipboard::{ClipboardLoad, ClipboardStore, ClipboardSubscribe, LocalClipboard};
+use snafu::ResultExt;
+use tokio::task;
+
+use crate::{
+    backend::{error, traits, Error, Result, Subscriber},
+    config::Osc52Config,
+};
+
+const ESC: &str = "\x1b";
+const BEL: &str = "\x07";
+const OSC52_PREFIX: &[u8] = b"\x1b]52;";
+
+/// A clipboard backend for terminals, it stores clips by writing OSC 52 escape
+/// sequences to a TTY and optionally reads them back with OSC 52 queries.
+///
+/// Terminals do not report clipboard changes, so the contents stored by
+/// `clipcatd` are also kept in memory and served from there when querying is
+/// disabled or the terminal does not answer.
+#[derive(Clone)]
+pub struct Backend {
+    tty: PathBuf,
+
+    tmux_passthrough: bool,
+
+    enable_query: bool,
+
+    query_timeout: Duration,
+
+    clipboards: Vec<Arc<LocalClipboard>>,
+
+    supported_clipboard_kinds: Vec<ClipboardKind>,
+}
+
+impl Backend {
+    pub fn new<I>(kinds: I, config: &Osc52Config) -> Self
+    where
+        I: IntoIterator<Item = ClipboardKind>,
+    {
+        let supported_clipboard_kinds = {
+            let mut kinds = kinds.into_iter().collect::<Vec<_>>();
+            kinds.sort_unstable();
+            kinds.dedup();
+            kinds
+        };
+        let clipboards = ClipboardKind::all_kinds()
+            .into_iter()
+            .map(|kind| Arc::new(LocalClipboard::with_kind(kind)))
+            .collect();
+
+        tracing::info!("Use OSC 52 clipboard backend with terminal `{}`", config.tty.display());
+
+        Self {
+            tty: config.tty.clone(),
+            tmux_passthrough: config.tmux_passthrough,
+            enable_query: config.enable_query,
+            query_timeout: config.query_timeout,
+            clipboards,
+            supported_clipboard_kinds,
+        }
+    }
+
+    #[inline]
+    fn select_clipboard(&self, kind: ClipboardKind) -> Result<Arc<LocalClipboard>> {
+        if self.supported_clipboard_kinds.contains(&kind) {
+            self.clipboards
+                .get(usize::from(kind))
+                .cloned()
+                .ok_or(Error::UnsupportedClipboardKind { kind })
+        } else {
+            Err(Error::UnsupportedClipboardKind { kind })
+        }
+    }
+}
+
+#[async_trait]
+impl traits::Backend for Backend {
+    async fn load(
+        &self,
+        kind: ClipboardKind,
+        mime: Option<mime::Mime>,
+    ) -> Result<ClipboardContent> {
+        let clipboard = self.select_clipboard(kind)?;
+
+        if self.enable_query && mime.as_ref().map_or(true, |mime| mime.type_() == mime::TEXT) {
+            let tty = self.tty.clone();
+            let tmux_passthrough = self.tmux_passthrough;
+            let timeout = self.query_timeout;
+            match task::spawn_blocking(move || query(&tty, kind, tmux_passthrough, timeout))
+                .await
+                .context(error::SpawnBlockingTaskSnafu)?
+            {
+                Ok(Some(data)) if !data.is_empty() => {
+                    return Ok(ClipboardContent::Plaintext(
+                        String::from_utf8_lossy(&data).into_owned(),
+                    ));
+                }
+                Ok(_) => {
+                    tracing::debug!("Terminal did not answer OSC 52 query, use cached contents");
+                }
+                Err(err) => {
+                    tracing::debug!("Could not query terminal with OSC 52, error: {err}");
+                }
+            }
+        }
+
+        task::spawn_blocking(move || match clipboard.load(mime) {
+            Ok(data) => Ok(data),
+            Err(clipcat_clipboard::Error::Empty) => Err(Error::EmptyClipboard),
+            Err(source) => Err(Error::LoadDataFromClipboard { source }),
+        })
+        .await
+        .context(error::SpawnBlockingTaskSnafu)?
+    }
+
+    async fn store(&self, kind: ClipboardKind, data: ClipboardContent) -> Result<()> {
+        let clipboard = self.select_clipboard(kind)?;
+        let ClipboardContent::Plaintext(text) = &data else {
+            return Err(Error::UnsupportedContentType { mime: data.mime() });
+        };
+
+        let sequence = encode_sequence(kind, text.as_bytes(), self.tmux_passthrough);
+        let tty = self.tty.clone();
+        task::spawn_blocking(move || {
+            write_terminal(&tty, sequence.as_bytes())?;
+            clipboard.store(data).context(error::StoreDataToClipboardSnafu)
+        })
+        .await
+        .context(error::SpawnBlockingTaskSnafu)?
+    }
+
+    async fn clear(&self, kind: ClipboardKind) -> Result<()> {
+        let Ok(clipboard) = self.select_clipboard(kind) else {
+            return Ok(());
+        };
+
+        // an empty payload asks the terminal to clear the selection
+        let sequence = encode_sequence(kind, &[], self.tmux_passthrough);
+        let tty = self.tty.clone();
+        task::spawn_blocking(move || {
+            write_terminal(&tty, sequence.as_bytes())?;
+            clipboard.clear().context(error::ClearClipboardSnafu)
+        })
+        .await
+        .context(error::SpawnBlockingTaskSnafu)?
+    }
+
+    fn subscribe(&self) -> Result<Subscriber> {
+        let subscribers = self
+            .supported_clipboard_kinds
+            .iter()
+            .filter_map(|&kind| self.clipboards.get(usize::from(kind)))
+            .map(|clipboard| clipboard.subscribe().context(error::SubscribeClipboardSnafu))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Subscriber::from(subscribers))
+    }
+
+    #[inline]
+    fn supported_clipboard_kinds(&self) -> Vec<ClipboardKind> {
+        self.supported_clipboard_kinds.clone()
+    }
+}
+
+const fn selection_parameter(kind: ClipboardKind) -> char {
+    match kind {
+        ClipboardKind::Clipboard => 'c',
+        ClipboardKind::Primary => 'p',
+        ClipboardKind::Secondary => 'q',
+    }
+}
+
+fn wrap_sequence(sequence: &str, tmux_passthrough: bool) -> String {
+    if tmux_passthrough {
+        // tmux forwards the sequence to the outer terminal only if it is wrapped in a
+        // DCS sequence and every ESC inside is doubled
+        format!("{ESC}Ptmux;{}{ESC}\\", sequence.replace(ESC, &format!("{ESC}{ESC}")))
+    } else {
+        sequence.to_string()
+    }
+}
+
+fn encode_sequence(kind: ClipboardKind, data: &[u8], tmux_passthrough: bool) -> String {
+    let payload = base64::engine::general_purpose::STANDARD.encode(data);
+    let sequence = format!("{ESC}]52;{};{payload}{BEL}", selection_parameter(kind));
+    wrap_sequence(&sequence, tmux_passthrough)
+}
+
+fn encode_query(kind: ClipboardKind, tmux_passthrough: bool) -> String {
+    let sequence = format!("{ESC}]52;{};?{BEL}", selection_parameter(kind));
+    wrap_sequence(&sequence, tmux_passthrough)
+}
+
+/// Extracts the decoded payload from an OSC 52 response, returns `None` if the
+/// response is not complete yet.
+fn parse_response(response: &[u8]) -> Option<Vec<u8>> {
+    let start = response.windows(OSC52_PREFIX.len()).position(|w| w == OSC52_PREFIX)?;
+    let rest = &response[start + OSC52_PREFIX.len()..];
+    let payload_start = rest.iter().position(|&b| b == b';')? + 1;
+    let rest = &rest[payload_start..];
+    let end = rest.iter().position(|&b| b == 0x07 || b == 0x1b)?;
+    if rest[end] == 0x1b && rest.get(end + 1) != Some(&b'\\') {
+        return None;
+    }
+    base64::engine::general_purpose::STANDARD.decode(&rest[..end]).ok()
+}
+
+fn write_terminal(path: &Path, data: &[u8]) -> Result<()> {
+    let mut tty = OpenOptions::new()
+        .write(true)
+        .open(path)
+        .context(error::OpenTerminalSnafu { path: path.to_path_buf() })?;
+    tty.write_all(data)
+        .and_then(|()| tty.flush())
+        .context(error::WriteTerminalSnafu { path: path.to_path_buf() })
+}
+
+/// Asks the terminal at `path` for the contents of the selection `kind`.
+///
+/// The terminal is switched into raw mode while waiting for the answer, so the
+/// answer is neither echoed nor line buffered. Its original attributes are
+/// restored by [`RawModeGuard`] when this function returns, including early
+/// returns on errors and unwinding, keystrokes typed into the terminal in the
+/// meantime are consumed.
+fn query(
+    path: &Path,
+    kind: ClipboardKind,
+    tmux_passthrough: bool,
+    timeout: Duration,
+) -> std::io::Result<Option<Vec<u8>>> {
+    let mut tty = OpenOptions::new().read(true).write(true).open(path)?;
+    let _raw_mode = RawModeGuard::new(&tty)?;
+
+    tty.write_all(encode_query(kind, tmux_passthrough).as_bytes())?;
+    tty.flush()?;
+
+    let deadline = Instant::now() + timeout;
+    let mut response = Vec::new();
+    let mut buf = [0_u8; 4096];
+    while Instant::now() < deadline {
+        // the terminal is in non-canonical mode with `VTIME` set, `read` returns 0
+        // after 100ms without input
+        let n = tty.read(&mut buf)?;
+        if n > 0 {
+            response.extend_from_slice(&buf[..n]);
+            if let Some(data) = parse_response(&response) {
+                return Ok(Some(data));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Puts the terminal into raw mode and restores the original attributes on
+/// drop, so the answer of the terminal is neither echoed nor line buffered.
+struct RawModeGuard {
+    fd: libc::c_int,
+    original: libc::termios,
+}
+
+impl RawModeGuard {
+    #[allow(unsafe_code)]
+    fn new(tty: &std::fs::File) -> std::io::Result<Self> {
+        let fd = tty.as_raw_fd();
+        // SAFETY: `termios` is a plain C struct, it is fully initialized by `tcgetattr`
+        let mut original = unsafe { std::mem::zeroed::<libc::termios>() };
+        // SAFETY: `fd` is a valid file descriptor owned by `tty`
+        if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let mut raw = original;
+        // SAFETY: `raw` is a valid `termios` obtained from `tcgetattr`
+        unsafe { libc::cfmakeraw(&mut raw) };
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 1;
+        // SAFETY: `fd` is a valid file descriptor owned by `tty`
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(Self { fd, original })
+    }
+}
+
+impl Drop for RawModeGuard {
+    #[allow(unsafe_code)]
+    fn drop(&mut self) {
+        // SAFETY: `fd` is still open, `RawModeGuard` never outlives the terminal file
+        let _ = unsafe { libc::tcsetattr(self.fd, libc::TCSANOW, &self.original) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use clipcat_base::{ClipboardContent, ClipboardKind};
+
+    use super::{encode_query, encode_sequence, parse_response, Backend};
+    use crate::{
+        backend::{traits::Backend as _, Error},
+        config::Osc52Config,
+    };
+
+    #[test]
+    fn test_encode_sequence() {
+        assert_eq!(
+            encode_sequence(ClipboardKind::Clipboard, b"clipcat", false),
+            "\x1b]52;c;Y2xpcGNhdA==\x07"
+        );
+        assert_eq!(encode_sequence(ClipboardKind::Primary, b"", false), "\x1b]52;p;\x07");
+        assert_eq!(
+            encode_sequence(ClipboardKind::Clipboard, b"clipcat", true),
+            "\x1bPtmux;\x1b\x1b]52;c;Y2xpcGNhdA==\x07\x1b\\"
+        );
+        assert_eq!(encode_query(ClipboardKind::Secondary, false), "\x1b]52;q;?\x07");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(parse_response(b"\x1b]52;c;Y2xpcGNhdA==\x07"), Some(b"clipcat".to_vec()));
+        assert_eq!(parse_response(b"junk\x1b]52;c;Y2xpcGNhdA==\x1b\\"), Some(b"clipcat".to_vec()));
+        assert_eq!(parse_response(b"\x1b]52;c;Y2xpcGNh"), None);
+        assert_eq!(parse_response(b"\x1b]52;c;Y2xpcGNhdA==\x1b"), None);
+    }
+
+    #[tokio::test]
+    async fn test_store_unsupported_kind() {
+        let config = Osc52Config {
+            tty: std::env::temp_dir().join("clipcat-osc52-test-unused-tty"),
+            tmux_passthrough: false,
+            enable_query: false,
+            query_timeout: Duration::from_millis(100),
+        };
+        let backend = Backend::new([ClipboardKind::Clipboard], &config);
+        let result = backend
+            .store(ClipboardKind::Primary, ClipboardContent::Plaintext("clipcat".to_string()))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedClipboardKind { kind: ClipboardKind::Primary })
+        ));
+    }
+}
@@ -11,6 +11,13 @@ pub struct Subscriber {
 }
 
 impl Subscriber {
+    pub(crate) const fn new(
+        receiver: mpsc::UnboundedReceiver<(ClipboardKind, mime::Mime)>,
+        join_handles: task::JoinSet<()>,
+    ) -> Self {
+        Self { receiver, join_handles }
+    }
+
     pub async fn next(&mut self) -> Option<(ClipboardKind, mime::Mime)> {
         self.receiver.recv().await
     }
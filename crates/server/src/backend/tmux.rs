@@ -0,0 +1,359 @@
+use std::{path::PathBuf, process::Stdio, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use clipcat_base::{ClipboardContent, ClipboardKind};
+use parking_lot::Mutex;
+use snafu::ResultExt;
+use tokio::{
+    io::AsyncWriteExt,
+    process::Command,
+    sync::{mpsc, watch},
+    task,
+};
+
+use crate::{
+    backend::{error, traits, Error, Result, Subscriber},
+    config::TmuxConfig,
+};
+
+/// Name of the paste buffer written by `clipcatd`.
+const BUFFER_NAME: &str = "clipcat";
+
+/// Watches tmux paste buffers on top of another backend.
+///
+/// New tmux buffers are reported as changes of the configured clipboard kind,
+/// so tmux copies and the copies of the inner backend share one history.
+/// Contents stored into that clipboard kind are also written into a tmux
+/// paste buffer.
+#[derive(Clone)]
+pub struct Backend {
+    inner: Arc<dyn traits::Backend>,
+
+    tmux: Tmux,
+
+    clipboard_kind: ClipboardKind,
+
+    polling_interval: Duration,
+
+    set_buffer_on_store: bool,
+
+    // contents of the newest tmux buffer if it is newer than the contents of the inner backend
+    latest_buffer: Arc<Mutex<Option<String>>>,
+}
+
+impl Backend {
+    pub fn new(inner: Arc<dyn traits::Backend>, config: &TmuxConfig) -> Self {
+        tracing::info!("Watch tmux paste buffers as {kind}", kind = config.clipboard_kind);
+        Self {
+            inner,
+            tmux: Tmux { socket_path: config.socket_path.clone() },
+            clipboard_kind: config.clipboard_kind,
+            polling_interval: config.polling_interval,
+            set_buffer_on_store: config.set_buffer_on_store,
+            latest_buffer: Arc::default(),
+        }
+    }
+
+    // returns the contents of a new buffer, `None` if it is not reported
+    async fn read_buffer(&self, buffer: Option<&BufferInfo>) -> Result<Option<String>> {
+        // the buffer written by `store` has its contents in the history already
+        let Some(info) = buffer.filter(|info| info.name != BUFFER_NAME) else {
+            return Ok(None);
+        };
+        let contents = self.tmux.show_buffer(&info.name).await?;
+        Ok(Some(contents).filter(|contents| !contents.is_empty()))
+    }
+
+    // the newest buffer when watching starts is not reported
+    async fn watch_buffers(self, sender: mpsc::UnboundedSender<(ClipboardKind, mime::Mime)>) {
+        let mut last_buffer = self.tmux.newest_buffer().await.unwrap_or_else(|err| {
+            tracing::debug!("{err}");
+            None
+        });
+        let mut interval = tokio::time::interval(self.polling_interval);
+        loop {
+            let _ = interval.tick().await;
+            if sender.is_closed() {
+                break;
+            }
+
+            let buffer = match self.tmux.newest_buffer().await {
+                Ok(buffer) => buffer,
+                Err(err) => {
+                    tracing::debug!("{err}");
+                    continue;
+                }
+            };
+            if buffer == last_buffer {
+                continue;
+            }
+
+            let contents = match self.read_buffer(buffer.as_ref()).await {
+                Ok(contents) => contents,
+                // the buffer is read again on the next tick
+                Err(err) => {
+                    tracing::warn!("{err}");
+                    continue;
+                }
+            };
+            last_buffer = buffer;
+
+            if let Some(contents) = contents {
+                *self.latest_buffer.lock() = Some(contents);
+                if sender.send((self.clipboard_kind, mime::TEXT_PLAIN_UTF_8)).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl traits::Backend for Backend {
+    async fn load(
+        &self,
+        kind: ClipboardKind,
+        mime: Option<mime::Mime>,
+    ) -> Result<ClipboardContent> {
+        if kind == self.clipboard_kind {
+            let latest_buffer = self.latest_buffer.lock().clone();
+            if let Some(contents) = latest_buffer {
+                return match mime {
+                    Some(mime) if mime.type_() != mime::TEXT => {
+                        Err(Error::MatchMime { expected_mime: mime })
+                    }
+                    _ => Ok(ClipboardContent::Plaintext(contents)),
+                };
+            }
+        }
+        self.inner.load(kind, mime).await
+    }
+
+    async fn store(&self, kind: ClipboardKind, data: ClipboardContent) -> Result<()> {
+        if kind == self.clipboard_kind {
+            if self.set_buffer_on_store {
+                if let ClipboardContent::Plaintext(text) = &data {
+                    self.tmux.set_buffer(text).await?;
+                }
+            }
+            // the inner backend is the source of truth again after storing
+            *self.latest_buffer.lock() = None;
+        }
+        self.inner.store(kind, data).await
+    }
+
+    async fn clear(&self, kind: ClipboardKind) -> Result<()> {
+        if kind == self.clipboard_kind {
+            *self.latest_buffer.lock() = None;
+        }
+        self.inner.clear(kind).await
+    }
+
+    fn subscribe(&self) -> Result<Subscriber> {
+        let mut inner = self.inner.subscribe()?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut join_handles = task::JoinSet::new();
+
+        let _unused = join_handles.spawn({
+            let sender = sender.clone();
+            let clipboard_kind = self.clipboard_kind;
+            let latest_buffer = self.latest_buffer.clone();
+            async move {
+                while let Some((kind, mime)) = inner.next().await {
+                    if kind == clipboard_kind {
+                        *latest_buffer.lock() = None;
+                    }
+                    if sender.send((kind, mime)).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        let _unused = join_handles.spawn(self.clone().watch_buffers(sender));
+
+        Ok(Subscriber::new(receiver, join_handles))
+    }
+
+    fn supported_clipboard_kinds(&self) -> Vec<ClipboardKind> {
+        let mut kinds = self.inner.supported_clipboard_kinds();
+        if !kinds.contains(&self.clipboard_kind) {
+            kinds.push(self.clipboard_kind);
+            kinds.sort_unstable();
+        }
+        kinds
+    }
+
+    fn watch_selecting(&self) -> Option<watch::Receiver<bool>> { self.inner.watch_selecting() }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct BufferInfo {
+    name: String,
+    created: String,
+    size: String,
+}
+
+impl BufferInfo {
+    const FORMAT: &'static str = "#{buffer_name}\t#{buffer_created}\t#{buffer_size}";
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(3, '\t');
+        let name = fields.next()?.to_string();
+        let created = fields.next()?.to_string();
+        let size = fields.next()?.to_string();
+        Some(Self { name, created, size })
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Tmux {
+    socket_path: Option<PathBuf>,
+}
+
+impl Tmux {
+    fn std_command(&self) -> std::process::Command {
+        let mut cmd = std::process::Command::new("tmux");
+        if let Some(socket_path) = &self.socket_path {
+            let _ = cmd.arg("-S").arg(socket_path);
+        }
+        cmd
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::from(self.std_command());
+        let _ = cmd.kill_on_drop(true);
+        cmd
+    }
+
+    fn parse_output(output: &std::process::Output) -> Result<String> {
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            Err(Error::TmuxCommand {
+                message: String::from_utf8_lossy(&output.stderr).trim_end().to_string(),
+            })
+        }
+    }
+
+    async fn run(&self, args: &[&str]) -> Result<String> {
+        let output = self.command().args(args).output().await.context(error::RunTmuxSnafu)?;
+        Self::parse_output(&output)
+    }
+
+    // tmux lists the most recently created buffer first
+    async fn newest_buffer(&self) -> Result<Option<BufferInfo>> {
+        let output = self.run(&["list-buffers", "-F", BufferInfo::FORMAT]).await?;
+        Ok(output.lines().next().and_then(BufferInfo::parse))
+    }
+
+    async fn show_buffer(&self, name: &str) -> Result<String> {
+        self.run(&["show-buffer", "-b", name]).await
+    }
+
+    // `load-buffer` reads the contents from stdin, so it is not limited by the
+    // maximum length of command arguments like `set-buffer`
+    async fn set_buffer(&self, data: &str) -> Result<()> {
+        let mut child = self
+            .command()
+            .args(["load-buffer", "-b", BUFFER_NAME, "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context(error::RunTmuxSnafu)?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(data.as_bytes()).await.context(error::RunTmuxSnafu)?;
+        }
+        let output = child.wait_with_output().await.context(error::RunTmuxSnafu)?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(Error::TmuxCommand {
+                message: String::from_utf8_lossy(&output.stderr).trim_end().to_string(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use clipcat_base::{ClipboardContent, ClipboardKind};
+
+    use super::{Backend, BufferInfo, Tmux};
+    use crate::{
+        backend::{ClipboardBackend, LocalClipboardBackend},
+        config::TmuxConfig,
+    };
+
+    #[test]
+    fn test_parse_buffer_info() {
+        assert_eq!(
+            BufferInfo::parse("buffer0\t1700000000\t7"),
+            Some(BufferInfo {
+                name: "buffer0".to_string(),
+                created: "1700000000".to_string(),
+                size: "7".to_string()
+            })
+        );
+        assert_eq!(BufferInfo::parse("buffer0"), None);
+    }
+
+    #[tokio::test]
+    async fn test_tmux_buffers() {
+        // tmux is optional in the build environment
+        if std::process::Command::new("tmux").arg("-V").output().is_err() {
+            return;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("tmux.sock");
+        let tmux = Tmux { socket_path: Some(socket_path.clone()) };
+        let _unused = tmux.run(&["-f", "/dev/null", "new-session", "-d"]).await.unwrap();
+        // the server is killed even if an assertion fails
+        let _server = TmuxServer(tmux.clone());
+
+        let config = TmuxConfig {
+            enable: true,
+            socket_path: Some(socket_path.clone()),
+            clipboard_kind: ClipboardKind::Clipboard,
+            polling_interval: Duration::from_millis(50),
+            set_buffer_on_store: true,
+        };
+        let backend = Backend::new(Arc::new(LocalClipboardBackend::new()), &config);
+        let _unused = tmux.run(&["set-buffer", "set before subscribing"]).await.unwrap();
+        let mut subscriber = backend.subscribe().unwrap();
+        // the snapshot of buffers is taken by the watching task, buffers are set
+        // until one of them is newer than the snapshot and reported
+        let (kind, _mime) = loop {
+            let _unused = tmux.run(&["set-buffer", "copied in tmux"]).await.unwrap();
+            if let Ok(event) = tokio::time::timeout(Duration::from_secs(2), subscriber.next()).await
+            {
+                break event.unwrap();
+            }
+        };
+        assert_eq!(kind, ClipboardKind::Clipboard);
+        assert_eq!(
+            backend.load(kind, None).await.unwrap(),
+            ClipboardContent::Plaintext("copied in tmux".to_string())
+        );
+
+        backend
+            .store(ClipboardKind::Clipboard, ClipboardContent::Plaintext("marked".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(tmux.show_buffer(super::BUFFER_NAME).await.unwrap(), "marked");
+        // the inner backend reports the stored contents, the buffer written by
+        // `store` is not reported again
+        let (kind, _mime) = subscriber.next().await.unwrap();
+        assert_eq!(kind, ClipboardKind::Clipboard);
+        assert!(tokio::time::timeout(Duration::from_millis(300), subscriber.next()).await.is_err());
+    }
+
+    struct TmuxServer(Tmux);
+
+    impl Drop for TmuxServer {
+        fn drop(&mut self) { let _unused = self.0.std_command().arg("kill-server").output(); }
+    }
+}
@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use clipcat_base::{ClipboardContent, ClipboardKind};
+use tokio::sync::watch;
 
 use crate::backend::{error::Result, Subscriber};
 
@@ -16,4 +17,11 @@ pub trait Backend: Sync + Send {
     fn subscribe(&self) -> Result<Subscriber>;
 
     fn supported_clipboard_kinds(&self) -> Vec<ClipboardKind>;
+
+    /// Returns a receiver holding `true` while the user is still selecting,
+    /// e.g. a mouse button is held down while dragging over text. The primary
+    /// selection changes on every movement until the selection is complete.
+    ///
+    /// Returns `None` if the backend cannot tell.
+    fn watch_selecting(&self) -> Option<watch::Receiver<bool>> { None }
 }
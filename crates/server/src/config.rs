@@ -1,20 +1,33 @@
-use std::{net::SocketAddr, path::PathBuf, time::Duration};
+use std::{collections::HashSet, net::SocketAddr, path::PathBuf, time::Duration};
+
+use clipcat_base::{AccessScope, ClipboardKind};
 
 use crate::ClipboardWatcherOptions;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Config {
     pub grpc_listen_address: Option<SocketAddr>,
 
     pub grpc_local_socket: Option<PathBuf>,
 
+    pub grpc_local_socket_access: LocalSocketAccessConfig,
+
     pub grpc_access_token: Option<String>,
 
-    pub primary_threshold: time::Duration,
+    /// Access tokens granted only their scopes, `grpc_access_token` is granted
+    /// all scopes.
+    pub grpc_scoped_access_tokens: Vec<ScopedAccessToken>,
+
+    /// Serves gRPC over HTTPS on `grpc_listen_address` if it is set.
+    pub grpc_tls: Option<GrpcTlsConfig>,
 
     pub max_history: usize,
 
-    pub synchronize_selection_with_clipboard: bool,
+    pub backend: BackendConfig,
+
+    pub tmux: TmuxConfig,
+
+    pub synchronization: SynchronizationConfig,
 
     pub history_file_path: PathBuf,
 
@@ -24,19 +37,159 @@ pub struct Config {
 
     pub desktop_notification: DesktopNotificationConfig,
 
+    pub notifications: NotificationSinksConfig,
+
     pub metrics: MetricsConfig,
 
+    pub web_ui: WebUiConfig,
+
+    pub http_api: HttpApiConfig,
+
+    /// Records accesses to the history if it is set.
+    pub audit_log: Option<AuditLogConfig>,
+
     pub snippets: Vec<SnippetConfig>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditLogConfig {
+    pub file_path: PathBuf,
+
+    /// The log is rotated when its size would exceed `max_file_size` bytes.
+    pub max_file_size: u64,
+
+    /// Number of rotated files to keep, the oldest one is removed.
+    pub max_files: usize,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LocalSocketAccessConfig {
+    /// UIDs of peers allowed to connect, only the UID of `clipcatd` is
+    /// allowed if it is empty.
+    pub allowed_uids: HashSet<u32>,
+
+    /// Executables of peers allowed to connect, any executable is allowed if
+    /// it is empty.
+    pub allowed_executables: HashSet<PathBuf>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScopedAccessToken {
+    pub name: String,
+
+    pub token: String,
+
+    pub scopes: HashSet<AccessScope>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GrpcTlsConfig {
+    pub certificate_file_path: PathBuf,
+
+    pub key_file_path: PathBuf,
+
+    /// Requires clients to present a certificate signed by this CA.
+    pub client_ca_file_path: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum BackendConfig {
+    /// X11, Wayland or the system clipboard, depending on the platform.
+    #[default]
+    Default,
+
+    /// OSC 52 escape sequences written to a terminal.
+    Osc52(Osc52Config),
+
+    /// A directory or a named pipe.
+    File(FileBackendConfig),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Osc52Config {
+    pub tty: PathBuf,
+
+    pub tmux_passthrough: bool,
+
+    pub enable_query: bool,
+
+    pub query_timeout: Duration,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileBackendConfig {
+    pub path: PathBuf,
+
+    pub output_directory: Option<PathBuf>,
+
+    pub clipboard_kind: ClipboardKind,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TmuxConfig {
+    pub enable: bool,
+
+    pub socket_path: Option<PathBuf>,
+
+    pub clipboard_kind: ClipboardKind,
+
+    pub polling_interval: Duration,
+
+    pub set_buffer_on_store: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SynchronizationConfig {
+    pub clipboard_to_primary: SynchronizationRule,
+
+    pub primary_to_clipboard: SynchronizationRule,
+
+    pub clipboard_to_secondary: SynchronizationRule,
+
+    pub secondary_to_clipboard: SynchronizationRule,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SynchronizationRule {
+    pub enable: bool,
+
+    /// Waits until the source selection has not changed for this duration
+    /// before synchronizing it.
+    pub debounce: Duration,
+
+    pub synchronize_image: bool,
+
+    pub filter_text_min_length: usize,
+
+    pub filter_text_max_length: usize,
+
+    pub denied_text_regex_patterns: HashSet<String>,
+}
+
+impl Default for SynchronizationRule {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            debounce: Duration::ZERO,
+            synchronize_image: true,
+            filter_text_min_length: 0,
+            filter_text_max_length: 20_000_000,
+            denied_text_regex_patterns: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DBusConfig {
     pub enable: bool,
 
     pub identifier: Option<String>,
+
+    /// Provides the D-Bus interface of Klipper at `org.kde.klipper`.
+    pub klipper_compatible: bool,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DesktopNotificationConfig {
     pub enable: bool,
 
@@ -45,16 +198,108 @@ pub struct DesktopNotificationConfig {
     pub timeout: Duration,
 
     pub long_plaintext_length: usize,
+
+    /// Maximum number of characters of the preview of new plaintext, the
+    /// preview is omitted if it is 0.
+    pub preview_length: usize,
+
+    /// Maximum width and height of the thumbnail of new images, the thumbnail
+    /// is omitted if it is 0.
+    pub thumbnail_size: u32,
+
+    /// Shows action buttons on notifications of new clips.
+    pub enable_actions: bool,
+
+    /// Clips fetched within this interval are notified in one notification.
+    pub coalesce_interval: Duration,
+
+    pub events: NotificationEvents,
+}
+
+/// Kinds of events notified with desktop notifications and notification sinks.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NotificationEvents {
+    /// The daemon is started or shutting down.
+    pub daemon_status: bool,
+
+    pub history_cleared: bool,
+
+    /// The watcher is enabled or disabled.
+    pub watcher_toggled: bool,
+
+    /// Connected to X11 or Wayland server.
+    pub backend_connected: bool,
+
+    pub image_fetched: bool,
+
+    pub plaintext_fetched: bool,
+}
+
+impl Default for NotificationEvents {
+    fn default() -> Self {
+        Self {
+            daemon_status: true,
+            history_cleared: true,
+            watcher_toggled: true,
+            backend_connected: true,
+            image_fetched: true,
+            plaintext_fetched: true,
+        }
+    }
+}
+
+/// Sinks which receive the same events as desktop notifications.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NotificationSinksConfig {
+    pub events: NotificationEvents,
+
+    /// Maximum number of characters of the preview of new plaintext, the
+    /// preview is omitted if it is 0.
+    pub preview_length: usize,
+
+    /// Appends events to this file as JSON lines if it is set.
+    pub event_log: Option<PathBuf>,
+
+    /// Runs this command for each event if it is set.
+    pub command: Option<NotificationCommandConfig>,
+
+    /// Emits a D-Bus signal for each event.
+    pub dbus: bool,
+
+    /// Rings the bell of this terminal for each event if it is set.
+    pub bell: Option<PathBuf>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotificationCommandConfig {
+    pub program: PathBuf,
+
+    pub args: Vec<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MetricsConfig {
     pub enable: bool,
 
     pub listen_address: SocketAddr,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WebUiConfig {
+    pub enable: bool,
+
+    pub listen_address: SocketAddr,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HttpApiConfig {
+    pub listen_address: Option<SocketAddr>,
+
+    pub local_socket: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum SnippetConfig {
     Inline { name: String, content: String },
     File { name: String, path: PathBuf },
@@ -0,0 +1,296 @@
+#![allow(clippy::ignored_unit_patterns)]
+
+use std::{cmp::Reverse, sync::Arc};
+
+use clipcat_base::{AuditOperation, AuditTransport, ClipEntry, ClipboardKind};
+use tokio::sync::Mutex;
+use zbus::{interface, message::Header, object_server::SignalEmitter, Connection};
+
+use crate::{
+    audit::{self, AuditLog},
+    dbus, metrics, notification,
+    watcher::ClipboardWatcherCurrentContents,
+    ClipboardManager,
+};
+
+/// Implements the D-Bus interface of Klipper, the clipboard manager of KDE
+/// Plasma, so applications talking to Klipper work with `clipcatd`.
+pub struct KlipperService<Notification> {
+    manager: Arc<Mutex<ClipboardManager<Notification>>>,
+
+    current_contents: ClipboardWatcherCurrentContents,
+
+    audit_log: AuditLog,
+}
+
+impl<Notification> KlipperService<Notification> {
+    pub const fn new(
+        manager: Arc<Mutex<ClipboardManager<Notification>>>,
+        current_contents: ClipboardWatcherCurrentContents,
+        audit_log: AuditLog,
+    ) -> Self {
+        Self { manager, current_contents, audit_log }
+    }
+}
+
+// the methods of the interface identify the peer from the message, the
+// operations are implemented here
+impl<Notification> KlipperService<Notification>
+where
+    Notification: notification::Notification,
+{
+    // Klipper lists the history from the newest clip to the oldest one
+    async fn history(&self) -> Vec<(u64, String)> {
+        let mut clips = {
+            let manager = self.manager.lock().await;
+            manager
+                .iter()
+                .map(|clip| (clip.timestamp(), clip.id(), clip.as_utf8_string()))
+                .collect::<Vec<_>>()
+        };
+        clips.sort_unstable_by_key(|(timestamp, ..)| Reverse(*timestamp));
+        clips.into_iter().map(|(_, id, text)| (id, text)).collect()
+    }
+
+    async fn contents(&self, peer: &audit::Peer) -> String {
+        let (id, text) = {
+            let manager = self.manager.lock().await;
+            manager
+                .get_current_clip(ClipboardKind::Clipboard)
+                .map(|clip| (Some(clip.id()), clip.as_utf8_string()))
+                .unwrap_or_default()
+        };
+        self.audit_log.record(peer, AuditOperation::GetCurrentClip, id.into_iter().collect()).await;
+        text
+    }
+
+    async fn set_contents(&self, peer: &audit::Peer, contents: &str) {
+        let kind = ClipboardKind::Clipboard;
+        let clip = ClipEntry::new(contents.as_bytes(), &mime::TEXT_PLAIN_UTF_8, kind, None)
+            .unwrap_or_default();
+        let mut manager = self.manager.lock().await;
+        // the watcher ignores the change caused by the following store, so the clip is
+        // not captured again
+        self.current_contents.set(kind, clip.as_ref().clone());
+        let id = manager.insert(clip);
+        if let Err(err) = manager.mark(id, kind).await {
+            tracing::warn!("{err}");
+        }
+        drop(manager);
+        self.audit_log.record(peer, AuditOperation::Mark, vec![id]).await;
+    }
+
+    async fn clear_contents(&self, peer: &audit::Peer) {
+        let removed = {
+            let mut manager = self.manager.lock().await;
+            manager
+                .get_current_clip(ClipboardKind::Clipboard)
+                .map(ClipEntry::id)
+                .filter(|&id| manager.remove(id))
+        };
+        if let Some(id) = removed {
+            self.audit_log.record(peer, AuditOperation::Remove, vec![id]).await;
+        }
+    }
+
+    async fn clear_history(&self, peer: &audit::Peer) {
+        self.manager.lock().await.clear();
+        self.audit_log.record(peer, AuditOperation::Clear, Vec::new()).await;
+    }
+
+    async fn history_menu(&self, peer: &audit::Peer) -> Vec<String> {
+        let (ids, texts) = self.history().await.into_iter().unzip();
+        self.audit_log.record(peer, AuditOperation::Export, ids).await;
+        texts
+    }
+
+    async fn history_item(&self, peer: &audit::Peer, index: i32) -> String {
+        let Ok(index) = usize::try_from(index) else {
+            return String::new();
+        };
+        let Some((id, text)) = self.history().await.into_iter().nth(index) else {
+            return String::new();
+        };
+        self.audit_log.record(peer, AuditOperation::Get, vec![id]).await;
+        text
+    }
+}
+
+async fn audit_peer(connection: &Connection, header: &Header<'_>) -> audit::Peer {
+    dbus::audit_peer_with_transport(AuditTransport::Klipper, connection, Some(header)).await
+}
+
+#[interface(name = "org.kde.klipper.klipper")]
+impl<Notification> KlipperService<Notification>
+where
+    Notification: notification::Notification + 'static,
+{
+    #[zbus(name = "getClipboardContents")]
+    async fn get_clipboard_contents(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+    ) -> String {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.contents(&audit_peer(connection, &header).await).await
+    }
+
+    #[zbus(name = "setClipboardContents")]
+    async fn set_clipboard_contents(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        contents: &str,
+    ) {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.set_contents(&audit_peer(connection, &header).await, contents).await;
+    }
+
+    #[zbus(name = "clearClipboardContents")]
+    async fn clear_clipboard_contents(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+    ) {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.clear_contents(&audit_peer(connection, &header).await).await;
+    }
+
+    #[zbus(name = "clearClipboardHistory")]
+    async fn clear_clipboard_history(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+    ) {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.clear_history(&audit_peer(connection, &header).await).await;
+    }
+
+    // `clipcatd` saves the history when it is shut down
+    #[allow(clippy::unused_self)]
+    #[zbus(name = "saveClipboardHistory")]
+    fn save_clipboard_history(&self) { metrics::dbus::REQUESTS_TOTAL.inc(); }
+
+    #[zbus(name = "getClipboardHistoryMenu")]
+    async fn get_clipboard_history_menu(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+    ) -> Vec<String> {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.history_menu(&audit_peer(connection, &header).await).await
+    }
+
+    #[zbus(name = "getClipboardHistoryItem")]
+    async fn get_clipboard_history_item(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        index: i32,
+    ) -> String {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.history_item(&audit_peer(connection, &header).await, index).await
+    }
+
+    // `clipcatd` has no menus of its own, menus are provided by `clipcat-menu`
+    #[allow(clippy::unused_self)]
+    #[zbus(name = "showKlipperPopupMenu")]
+    fn show_klipper_popup_menu(&self) { metrics::dbus::REQUESTS_TOTAL.inc(); }
+
+    #[allow(clippy::unused_self)]
+    #[zbus(name = "showKlipperManuallyInvokeActionMenu")]
+    fn show_klipper_manually_invoke_action_menu(&self) { metrics::dbus::REQUESTS_TOTAL.inc(); }
+
+    #[zbus(signal, name = "clipboardHistoryUpdated")]
+    pub async fn clipboard_history_updated(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use clipcat_base::{AuditOperation, AuditTransport, ClipboardContent, ClipboardKind};
+    use tokio::sync::Mutex;
+
+    use super::KlipperService;
+    use crate::{
+        audit::{AuditLog, Peer},
+        backend::LocalClipboardBackend,
+        config::AuditLogConfig,
+        manager::ClipboardManager,
+        notification::DummyNotification,
+        watcher::ClipboardWatcherCurrentContents,
+    };
+
+    #[tokio::test]
+    async fn test_history() {
+        let directory = tempfile::tempdir().unwrap();
+        let audit_log = AuditLog::open(AuditLogConfig {
+            file_path: directory.path().join("audit.log"),
+            max_file_size: 1 << 20,
+            max_files: 1,
+        })
+        .await
+        .unwrap();
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let manager = ClipboardManager::new(backend, DummyNotification::default());
+        let current_contents = ClipboardWatcherCurrentContents::default();
+        let service = KlipperService::new(
+            Arc::new(Mutex::new(manager)),
+            current_contents.clone(),
+            audit_log.clone(),
+        );
+        let peer = Peer::new(AuditTransport::Klipper);
+
+        service.set_contents(&peer, "first").await;
+        service.set_contents(&peer, "second").await;
+        // the watcher does not capture the contents set by Klipper clients again
+        assert!(!current_contents.replace_if_changed(
+            ClipboardKind::Clipboard,
+            &ClipboardContent::Plaintext("second".to_string())
+        ));
+        assert_eq!(service.contents(&peer).await, "second");
+        assert_eq!(service.history_menu(&peer).await, ["second", "first"]);
+        assert_eq!(service.history_item(&peer, 1).await, "first");
+        assert_eq!(service.history_item(&peer, 2).await, "");
+        assert_eq!(service.history_item(&peer, -1).await, "");
+
+        service.clear_contents(&peer).await;
+        assert_eq!(service.history_menu(&peer).await, ["first"]);
+
+        service.clear_history(&peer).await;
+        assert!(service.history_menu(&peer).await.is_empty());
+
+        let records = audit_log.list(0).await.unwrap();
+        assert!(records.iter().all(|record| record.transport == AuditTransport::Klipper));
+        assert_eq!(
+            records.iter().map(|record| record.operation).collect::<Vec<_>>(),
+            [
+                AuditOperation::Mark,
+                AuditOperation::Mark,
+                AuditOperation::GetCurrentClip,
+                AuditOperation::Export,
+                AuditOperation::Get,
+                AuditOperation::Remove,
+                AuditOperation::Export,
+                AuditOperation::Clear,
+                AuditOperation::Export,
+            ]
+        );
+        assert_eq!(records[3].clip_ids, [records[1].clip_ids[0], records[0].clip_ids[0]]);
+        assert_eq!(records[4].clip_ids, records[0].clip_ids);
+        assert_eq!(records[5].clip_ids, records[1].clip_ids);
+    }
+}
@@ -2,19 +2,27 @@
 
 use std::{str::FromStr, sync::Arc};
 
+use clipcat_base::AuditOperation;
 use clipcat_dbus_variant as dbus_variant;
 use tokio::sync::Mutex;
-use zbus::interface;
+use zbus::{interface, message::Header, object_server::SignalEmitter, Connection};
 
-use crate::{metrics, notification, ClipboardManager};
+use crate::{audit::AuditLog, dbus, metrics, notification, ClipboardManager};
 
+/// Provides the clipboard history over D-Bus, methods are the same as the ones
+/// of the `Manager` service of gRPC.
 pub struct ManagerService<Notification> {
     manager: Arc<Mutex<ClipboardManager<Notification>>>,
+
+    audit_log: AuditLog,
 }
 
 impl<Notification> ManagerService<Notification> {
-    pub const fn new(manager: Arc<Mutex<ClipboardManager<Notification>>>) -> Self {
-        Self { manager }
+    pub const fn new(
+        manager: Arc<Mutex<ClipboardManager<Notification>>>,
+        audit_log: AuditLog,
+    ) -> Self {
+        Self { manager, audit_log }
     }
 }
 
@@ -32,7 +40,9 @@ where
         let id = manager.insert(
             clipcat_base::ClipEntry::new(data, &mime, kind.into(), None).unwrap_or_default(),
         );
-        let _unused = manager.mark(id, kind.into()).await;
+        if let Err(err) = manager.mark(id, kind.into()).await {
+            tracing::warn!("{err}");
+        }
         drop(manager);
         id
     }
@@ -48,80 +58,142 @@ where
             clipcat_base::ClipEntry::new(data.as_bytes(), &mime::TEXT_PLAIN_UTF_8, kind, None)
                 .unwrap_or_default(),
         );
-        let _unused = manager.mark(id, kind).await;
+        if let Err(err) = manager.mark(id, kind).await {
+            tracing::warn!("{err}");
+        }
         drop(manager);
     }
 
     #[zbus(property)]
-    async fn clipboard_text_contents(&self) -> String {
+    async fn clipboard_text_contents(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Option<Header<'_>>,
+    ) -> String {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
-        let manager = self.manager.lock().await;
-        manager
-            .get_current_clip(clipcat_base::ClipboardKind::Clipboard)
-            .map(clipcat_base::ClipEntry::as_utf8_string)
-            .unwrap_or_default()
+        let (id, text) = {
+            let manager = self.manager.lock().await;
+            manager
+                .get_current_clip(clipcat_base::ClipboardKind::Clipboard)
+                .map(|clip| (Some(clip.id()), clip.as_utf8_string()))
+                .unwrap_or_default()
+        };
+        let peer = dbus::audit_peer(connection, header.as_ref()).await;
+        self.audit_log
+            .record(&peer, AuditOperation::GetCurrentClip, id.into_iter().collect())
+            .await;
+        text
     }
 
-    async fn remove(&self, id: u64) -> bool {
+    async fn remove(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        id: u64,
+    ) -> bool {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
-        let mut manager = self.manager.lock().await;
-        manager.remove(id)
+        let ok = self.manager.lock().await.remove(id);
+        let peer = dbus::audit_peer(connection, Some(&header)).await;
+        self.audit_log.record(&peer, AuditOperation::Remove, vec![id]).await;
+        ok
     }
 
-    async fn batch_remove(&self, ids: Vec<u64>) -> Vec<u64> {
+    async fn batch_remove(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        ids: Vec<u64>,
+    ) -> Vec<u64> {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
-        let mut manager = self.manager.lock().await;
-        ids.into_iter().filter(|&id| manager.remove(id)).collect()
+        let ids: Vec<_> = {
+            let mut manager = self.manager.lock().await;
+            ids.into_iter().filter(|&id| manager.remove(id)).collect()
+        };
+        let peer = dbus::audit_peer(connection, Some(&header)).await;
+        self.audit_log.record(&peer, AuditOperation::Remove, ids.clone()).await;
+        ids
     }
 
-    async fn clear(&self) {
+    async fn clear(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+    ) {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
-        let mut manager = self.manager.lock().await;
-        manager.clear();
+        self.manager.lock().await.clear();
+        let peer = dbus::audit_peer(connection, Some(&header)).await;
+        self.audit_log.record(&peer, AuditOperation::Clear, Vec::new()).await;
     }
 
-    async fn get(&self, id: u64) -> zvariant::Optional<dbus_variant::ClipEntry> {
+    async fn get(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        id: u64,
+    ) -> zvariant::Optional<dbus_variant::ClipEntry> {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
-        let manager = self.manager.lock().await;
-        zvariant::Optional::from(manager.get(id).map(Into::into))
+        let clip = self.manager.lock().await.get(id).map(Into::into);
+        let peer = dbus::audit_peer(connection, Some(&header)).await;
+        self.audit_log.record(&peer, AuditOperation::Get, vec![id]).await;
+        zvariant::Optional::from(clip)
     }
 
     async fn get_current_clip(
         &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
         kind: dbus_variant::ClipboardKind,
     ) -> zvariant::Optional<dbus_variant::ClipEntry> {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
-        let manager = self.manager.lock().await;
-        zvariant::Optional::from(
-            manager.get_current_clip(kind.into()).map(|clip| clip.clone().into()),
-        )
+        let clip = self.manager.lock().await.get_current_clip(kind.into()).cloned();
+        let peer = dbus::audit_peer(connection, Some(&header)).await;
+        let clip_ids = clip.iter().map(clipcat_base::ClipEntry::id).collect();
+        self.audit_log.record(&peer, AuditOperation::GetCurrentClip, clip_ids).await;
+        zvariant::Optional::from(clip.map(Into::into))
     }
 
-    async fn list(&self, preview_length: u64) -> Vec<dbus_variant::ClipEntryMetadata> {
+    async fn list(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        preview_length: u64,
+    ) -> Vec<dbus_variant::ClipEntryMetadata> {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
-        let manager = self.manager.lock().await;
-        manager
-            .list(usize::try_from(preview_length).unwrap_or(30))
-            .into_iter()
-            .map(dbus_variant::ClipEntryMetadata::from)
-            .collect()
+        let metadata = {
+            let manager = self.manager.lock().await;
+            manager.list(usize::try_from(preview_length).unwrap_or(30))
+        };
+        // previews are not limited, they contain the whole contents
+        if preview_length == 0 {
+            let peer = dbus::audit_peer(connection, Some(&header)).await;
+            let clip_ids = metadata.iter().map(|metadata| metadata.id).collect();
+            self.audit_log.record(&peer, AuditOperation::Export, clip_ids).await;
+        }
+        metadata.into_iter().map(dbus_variant::ClipEntryMetadata::from).collect()
     }
 
-    async fn update(&self, id: u64, data: &[u8], mime: &str) -> (bool, u64) {
+    async fn update(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        id: u64,
+        data: &[u8],
+        mime: &str,
+    ) -> (bool, u64) {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
@@ -130,15 +202,31 @@ where
             let mut manager = self.manager.lock().await;
             manager.replace(id, data, &mime)
         };
+        // the original clip is removed
+        if ok {
+            let peer = dbus::audit_peer(connection, Some(&header)).await;
+            self.audit_log.record(&peer, AuditOperation::Remove, vec![id]).await;
+        }
         (ok, new_id)
     }
 
-    async fn mark(&self, id: u64, kind: dbus_variant::ClipboardKind) -> bool {
+    async fn mark(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        id: u64,
+        kind: dbus_variant::ClipboardKind,
+    ) -> bool {
         metrics::dbus::REQUESTS_TOTAL.inc();
         let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
 
-        let mut manager = self.manager.lock().await;
-        manager.mark(id, kind.into()).await.is_ok()
+        let ok = {
+            let mut manager = self.manager.lock().await;
+            manager.mark(id, kind.into()).await.is_ok()
+        };
+        let peer = dbus::audit_peer(connection, Some(&header)).await;
+        self.audit_log.record(&peer, AuditOperation::Mark, vec![id]).await;
+        ok
     }
 
     #[zbus(property)]
@@ -149,4 +237,127 @@ where
         let manager = self.manager.lock().await;
         manager.len() as u64
     }
+
+    #[zbus(signal)]
+    pub async fn clip_added(
+        emitter: &SignalEmitter<'_>,
+        id: u64,
+        kind: dbus_variant::ClipboardKind,
+        mime: &str,
+        preview: &str,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    pub async fn clip_removed(emitter: &SignalEmitter<'_>, id: u64) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    pub async fn history_cleared(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{process::Stdio, sync::Arc};
+
+    use clipcat_base::{DBUS_MANAGER_OBJECT_PATH, DBUS_SERVICE_NAME};
+    use clipcat_dbus_variant as dbus_variant;
+    use tokio::{
+        io::{AsyncBufReadExt, BufReader},
+        process::{Child, Command},
+        sync::Mutex,
+    };
+
+    use super::ManagerService;
+    use crate::{
+        audit::AuditLog, backend::LocalClipboardBackend, manager::ClipboardManager,
+        notification::DummyNotification,
+    };
+
+    // starts a private session bus, returns the daemon and the address of the bus
+    async fn start_session_bus() -> Option<(Child, String)> {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .ok()?;
+        let mut address = String::new();
+        let _unused = BufReader::new(daemon.stdout.take()?).read_line(&mut address).await.ok()?;
+        Some((daemon, address.trim_end().to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_manager_over_session_bus() {
+        let Some((_daemon, address)) = start_session_bus().await else {
+            eprintln!("dbus-daemon is not installed, skip");
+            return;
+        };
+
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let manager = ClipboardManager::new(backend, DummyNotification::default());
+        let _server = zbus::connection::Builder::address(address.as_str())
+            .unwrap()
+            .name(DBUS_SERVICE_NAME)
+            .unwrap()
+            .serve_at(
+                DBUS_MANAGER_OBJECT_PATH,
+                ManagerService::new(Arc::new(Mutex::new(manager)), AuditLog::default()),
+            )
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let client =
+            zbus::connection::Builder::address(address.as_str()).unwrap().build().await.unwrap();
+        let proxy = zbus::proxy::Builder::<zbus::Proxy<'_>>::new(&client)
+            .destination(DBUS_SERVICE_NAME)
+            .unwrap()
+            .path(DBUS_MANAGER_OBJECT_PATH)
+            .unwrap()
+            .interface("org.clipcat.clipcat.Manager")
+            .unwrap()
+            .cache_properties(zbus::proxy::CacheProperties::No)
+            .build()
+            .await
+            .unwrap();
+
+        let kind = dbus_variant::ClipboardKind::Clipboard;
+        let id: u64 = proxy.call("Insert", &(kind, &b"clipcat"[..], "text/plain")).await.unwrap();
+        let _: u64 = proxy.call("Insert", &(kind, &b"clipcatd"[..], "text/plain")).await.unwrap();
+        assert_eq!(proxy.get_property::<u64>("Length").await.unwrap(), 2);
+
+        let entry: dbus_variant::ClipEntry = proxy.call("Get", &(id,)).await.unwrap();
+        assert_eq!(entry.id(), id);
+        assert_eq!(entry.data(), b"clipcat");
+        assert_eq!(entry.mime(), "text/plain");
+        assert_eq!(entry.kind(), kind);
+
+        let metadata: Vec<dbus_variant::ClipEntryMetadata> =
+            proxy.call("List", &(30_u64,)).await.unwrap();
+        assert_eq!(metadata.len(), 2);
+        assert!(metadata
+            .iter()
+            .any(|metadata| metadata.id() == id && metadata.preview() == "clipcat"));
+
+        let (ok, new_id): (bool, u64) =
+            proxy.call("Update", &(id, &b"clipcat-menu"[..], "text/plain")).await.unwrap();
+        assert!(ok);
+        assert!(proxy.call::<_, _, bool>("Mark", &(new_id, kind)).await.unwrap());
+        let current: dbus_variant::ClipEntry =
+            proxy.call("GetCurrentClip", &(kind,)).await.unwrap();
+        assert_eq!(current.id(), new_id);
+        assert_eq!(current.data(), b"clipcat-menu");
+        assert_eq!(
+            proxy.get_property::<String>("ClipboardTextContents").await.unwrap(),
+            "clipcat-menu"
+        );
+
+        let removed: Vec<u64> = proxy.call("BatchRemove", &(vec![new_id, id],)).await.unwrap();
+        assert_eq!(removed, [new_id]);
+        assert_eq!(proxy.get_property::<u64>("Length").await.unwrap(), 1);
+
+        proxy.call::<_, _, ()>("Clear", &()).await.unwrap();
+        assert_eq!(proxy.get_property::<u64>("Length").await.unwrap(), 0);
+    }
 }
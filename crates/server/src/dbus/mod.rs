@@ -1,5 +1,45 @@
+mod klipper;
 mod manager;
+mod search_provider;
+mod signal;
 mod system;
 mod watcher;
 
-pub use self::{manager::ManagerService, system::SystemService, watcher::WatcherService};
+use clipcat_base::AuditTransport;
+use zbus::message::Header;
+
+pub use self::{
+    klipper::KlipperService, manager::ManagerService, search_provider::SearchProviderService,
+    signal::emit_signals, system::SystemService, watcher::WatcherService,
+};
+use crate::audit;
+
+/// Identifies the sender of a method call for the audit log, the credentials
+/// of the sender are provided by the message bus.
+async fn audit_peer(connection: &zbus::Connection, header: Option<&Header<'_>>) -> audit::Peer {
+    audit_peer_with_transport(AuditTransport::DBus, connection, header).await
+}
+
+/// Like [`audit_peer`], for the interfaces which are recorded as their own
+/// transport, e.g. the Klipper interface.
+async fn audit_peer_with_transport(
+    transport: AuditTransport,
+    connection: &zbus::Connection,
+    header: Option<&Header<'_>>,
+) -> audit::Peer {
+    let peer = audit::Peer::new(transport);
+    let Some(sender) = header.and_then(Header::sender) else {
+        return peer;
+    };
+    let credentials = match zbus::fdo::DBusProxy::new(connection).await {
+        Ok(proxy) => proxy.get_connection_credentials(sender.clone().into()).await.ok(),
+        Err(_) => None,
+    };
+    peer.with_address(Some(sender)).with_credentials(
+        credentials.as_ref().and_then(zbus::fdo::ConnectionCredentials::unix_user_id),
+        credentials
+            .as_ref()
+            .and_then(zbus::fdo::ConnectionCredentials::process_id)
+            .and_then(|pid| i32::try_from(pid).ok()),
+    )
+}
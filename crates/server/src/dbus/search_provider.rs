@@ -0,0 +1,269 @@
+#![allow(clippy::ignored_unit_patterns, clippy::used_underscore_binding)]
+
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+
+use clipcat_base::{AuditOperation, AuditTransport, ClipEntry, ClipboardContent, ClipboardKind};
+use tokio::sync::Mutex;
+use zbus::{interface, message::Header, Connection};
+use zvariant::Value;
+
+use crate::{
+    audit::{self, AuditLog},
+    dbus, metrics, notification, ClipboardManager,
+};
+
+const PREVIEW_LENGTH: usize = 80;
+
+/// GNOME Shell shows a few results of every provider, matching the whole
+/// history on every keystroke is not useful.
+const MAX_RESULTS: usize = 20;
+
+/// Implements the search provider interface of GNOME Shell, clips matching
+/// the search terms are shown in the overview and the activated one is
+/// marked as the clipboard contents.
+pub struct SearchProviderService<Notification> {
+    manager: Arc<Mutex<ClipboardManager<Notification>>>,
+
+    audit_log: AuditLog,
+}
+
+impl<Notification> SearchProviderService<Notification> {
+    pub const fn new(
+        manager: Arc<Mutex<ClipboardManager<Notification>>>,
+        audit_log: AuditLog,
+    ) -> Self {
+        Self { manager, audit_log }
+    }
+}
+
+impl<Notification> SearchProviderService<Notification>
+where
+    Notification: notification::Notification,
+{
+    // returns the identifiers of at most `MAX_RESULTS` matched clips, the newest
+    // clip comes first
+    async fn search<F>(&self, terms: &[String], candidate: F) -> Vec<String>
+    where
+        F: Fn(u64) -> bool,
+    {
+        let terms = terms.iter().map(|term| term.to_lowercase()).collect::<Vec<_>>();
+        let mut clips = {
+            let manager = self.manager.lock().await;
+            manager
+                .iter()
+                .filter(|clip| candidate(clip.id()) && matches_terms(clip, &terms))
+                .map(|clip| (clip.timestamp(), clip.id()))
+                .collect::<Vec<_>>()
+        };
+        clips.sort_unstable_by_key(|&(timestamp, _)| Reverse(timestamp));
+        clips.into_iter().take(MAX_RESULTS).map(|(_, id)| id.to_string()).collect()
+    }
+
+    // the text of clips is provided for copying the result, reading it is audited
+    async fn result_metas(
+        &self,
+        peer: &audit::Peer,
+        identifiers: Vec<String>,
+    ) -> Vec<HashMap<String, Value<'static>>> {
+        let mut clip_ids = Vec::new();
+        let metas = {
+            let manager = self.manager.lock().await;
+            identifiers
+                .into_iter()
+                .filter_map(|identifier| {
+                    let clip = manager.get(identifier.parse().ok()?)?;
+                    let mut meta = HashMap::from([
+                        ("id".to_string(), Value::from(identifier)),
+                        (
+                            "name".to_string(),
+                            Value::from(clip.preview_information(Some(PREVIEW_LENGTH))),
+                        ),
+                        ("description".to_string(), Value::from(clip.basic_information())),
+                    ]);
+                    if let ClipboardContent::Plaintext(text) = clip.as_ref() {
+                        // GNOME Shell copies the text when the result is copied
+                        let _unused =
+                            meta.insert("clipboardText".to_string(), Value::from(text.clone()));
+                        clip_ids.push(clip.id());
+                    }
+                    Some(meta)
+                })
+                .collect()
+        };
+        if !clip_ids.is_empty() {
+            self.audit_log.record(peer, AuditOperation::Get, clip_ids).await;
+        }
+        metas
+    }
+
+    async fn activate(&self, peer: &audit::Peer, identifier: &str) {
+        let Ok(id) = identifier.parse() else {
+            return;
+        };
+        let mut manager = self.manager.lock().await;
+        if let Err(err) = manager.mark(id, ClipboardKind::Clipboard).await {
+            tracing::warn!("{err}");
+            return;
+        }
+        drop(manager);
+        self.audit_log.record(peer, AuditOperation::Mark, vec![id]).await;
+    }
+}
+
+async fn audit_peer(connection: &Connection, header: &Header<'_>) -> audit::Peer {
+    dbus::audit_peer_with_transport(AuditTransport::SearchProvider, connection, Some(header)).await
+}
+
+#[interface(name = "org.gnome.Shell.SearchProvider2")]
+impl<Notification> SearchProviderService<Notification>
+where
+    Notification: notification::Notification + 'static,
+{
+    async fn get_initial_result_set(&self, terms: Vec<String>) -> Vec<String> {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.search(&terms, |_| true).await
+    }
+
+    async fn get_subsearch_result_set(
+        &self,
+        previous_results: Vec<String>,
+        terms: Vec<String>,
+    ) -> Vec<String> {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        let previous_results =
+            previous_results.iter().filter_map(|id| id.parse::<u64>().ok()).collect::<Vec<_>>();
+        self.search(&terms, |id| previous_results.contains(&id)).await
+    }
+
+    async fn get_result_metas(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        identifiers: Vec<String>,
+    ) -> Vec<HashMap<String, Value<'static>>> {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.result_metas(&audit_peer(connection, &header).await, identifiers).await
+    }
+
+    async fn activate_result(
+        &self,
+        #[zbus(connection)] connection: &Connection,
+        #[zbus(header)] header: Header<'_>,
+        identifier: &str,
+        _terms: Vec<String>,
+        _timestamp: u32,
+    ) {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+        let _histogram_timer = metrics::dbus::REQUEST_DURATION_SECONDS.start_timer();
+
+        self.activate(&audit_peer(connection, &header).await, identifier).await;
+    }
+
+    // `clipcatd` has no window showing all results
+    #[allow(clippy::unused_self)]
+    fn launch_search(&self, _terms: Vec<String>, _timestamp: u32) {
+        metrics::dbus::REQUESTS_TOTAL.inc();
+    }
+}
+
+fn matches_terms(clip: &ClipEntry, terms: &[String]) -> bool {
+    let ClipboardContent::Plaintext(text) = clip.as_ref() else {
+        return false;
+    };
+    let text = text.to_lowercase();
+    terms.iter().all(|term| text.contains(term))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use clipcat_base::{
+        AuditOperation, AuditTransport, ClipEntry, ClipboardContent, ClipboardKind,
+    };
+    use tokio::sync::Mutex;
+    use zvariant::Value;
+
+    use super::{SearchProviderService, MAX_RESULTS};
+    use crate::{
+        audit::{AuditLog, Peer},
+        backend::{ClipboardBackend, LocalClipboardBackend},
+        config::AuditLogConfig,
+        manager::ClipboardManager,
+        notification::DummyNotification,
+    };
+
+    #[tokio::test]
+    async fn test_search() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let mut manager = ClipboardManager::new(backend.clone(), DummyNotification::default());
+        let ids = ["Clipcat daemon", "clipcat menu", "clipboard"]
+            .into_iter()
+            .map(|text| {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+                manager.insert(ClipEntry::from_string(text, ClipboardKind::Primary)).to_string()
+            })
+            .collect::<Vec<_>>();
+        let manager = Arc::new(Mutex::new(manager));
+        let directory = tempfile::tempdir().unwrap();
+        let audit_log = AuditLog::open(AuditLogConfig {
+            file_path: directory.path().join("audit.log"),
+            max_file_size: 1 << 20,
+            max_files: 1,
+        })
+        .await
+        .unwrap();
+        let service = SearchProviderService::new(manager, audit_log.clone());
+        let peer = Peer::new(AuditTransport::SearchProvider);
+
+        let results = service.get_initial_result_set(vec!["CLIPCAT".to_string()]).await;
+        assert_eq!(results, [ids[1].clone(), ids[0].clone()]);
+        let results = service
+            .get_subsearch_result_set(results, vec!["clipcat".to_string(), "menu".to_string()])
+            .await;
+        assert_eq!(results, [ids[1].clone()]);
+
+        let metas = service.result_metas(&peer, results).await;
+        assert_eq!(metas.len(), 1);
+        assert_eq!(metas[0]["id"], Value::from(ids[1].clone()));
+        assert_eq!(metas[0]["clipboardText"], Value::from("clipcat menu"));
+
+        service.activate(&peer, &ids[1]).await;
+        assert_eq!(
+            backend.load(ClipboardKind::Clipboard, None).await.unwrap(),
+            ClipboardContent::Plaintext("clipcat menu".to_string())
+        );
+
+        let records = audit_log.list(0).await.unwrap();
+        assert_eq!(
+            records.iter().map(|record| record.operation).collect::<Vec<_>>(),
+            [AuditOperation::Get, AuditOperation::Mark]
+        );
+        let id = ids[1].parse::<u64>().unwrap();
+        assert!(records
+            .iter()
+            .all(|record| record.clip_ids == [id]
+                && record.transport == AuditTransport::SearchProvider));
+    }
+
+    #[tokio::test]
+    async fn test_max_results() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let mut manager = ClipboardManager::new(backend, DummyNotification::default());
+        for n in 0..MAX_RESULTS * 2 {
+            let _id = manager
+                .insert(ClipEntry::from_string(format!("clip {n}"), ClipboardKind::Clipboard));
+        }
+        let service =
+            SearchProviderService::new(Arc::new(Mutex::new(manager)), AuditLog::default());
+
+        let results = service.get_initial_result_set(vec!["clip".to_string()]).await;
+        assert_eq!(results.len(), MAX_RESULTS);
+    }
+}
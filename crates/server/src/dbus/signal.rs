@@ -0,0 +1,117 @@
+use std::future::Future;
+
+use clipcat_base::{DBUS_KLIPPER_OBJECT_PATH, DBUS_MANAGER_OBJECT_PATH, DBUS_WATCHER_OBJECT_PATH};
+use tokio::sync::broadcast::{self, error::RecvError};
+use zbus::object_server::InterfaceRef;
+
+use crate::{
+    dbus::{KlipperService, ManagerService, WatcherService},
+    events::Event,
+    notification,
+};
+
+struct Interfaces<Notification>
+where
+    Notification: notification::Notification + 'static,
+{
+    manager: InterfaceRef<ManagerService<Notification>>,
+
+    watcher: InterfaceRef<WatcherService<Notification>>,
+
+    klipper: Option<InterfaceRef<KlipperService<Notification>>>,
+}
+
+/// Emits D-Bus signals for the events of the clipboard history and the
+/// clipboard watcher until `shutdown_signal` resolves.
+///
+/// # Errors
+///
+/// This function will return an error if the services are not served by
+/// `connection`.
+pub async fn emit_signals<Notification, Signal>(
+    connection: &zbus::Connection,
+    mut event_receiver: broadcast::Receiver<Event>,
+    shutdown_signal: Signal,
+) -> zbus::Result<()>
+where
+    Notification: notification::Notification + 'static,
+    Signal: Future<Output = ()>,
+{
+    let object_server = connection.object_server();
+    let interfaces = Interfaces::<Notification> {
+        manager: object_server.interface(DBUS_MANAGER_OBJECT_PATH).await?,
+        watcher: object_server.interface(DBUS_WATCHER_OBJECT_PATH).await?,
+        klipper: object_server.interface(DBUS_KLIPPER_OBJECT_PATH).await.ok(),
+    };
+    let mut shutdown_signal = std::pin::pin!(shutdown_signal);
+
+    loop {
+        let event = tokio::select! {
+            () = &mut shutdown_signal => return Ok(()),
+            event = event_receiver.recv() => event,
+        };
+        match event {
+            Ok(event) => {
+                if let Err(err) = interfaces.emit(event).await {
+                    tracing::warn!("Could not emit D-Bus signal, error: {err}");
+                }
+            }
+            Err(RecvError::Lagged(count)) => {
+                tracing::warn!("{count} event(s) are dropped before emitting D-Bus signals");
+            }
+            Err(RecvError::Closed) => {
+                shutdown_signal.await;
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<Notification> Interfaces<Notification>
+where
+    Notification: notification::Notification + 'static,
+{
+    async fn emit(&self, event: Event) -> zbus::Result<()> {
+        let emitter = self.manager.signal_emitter();
+        match event {
+            Event::ClipAdded { metadata } => {
+                ManagerService::<Notification>::clip_added(
+                    emitter,
+                    metadata.id,
+                    metadata.kind.into(),
+                    metadata.mime.essence_str(),
+                    &metadata.preview,
+                )
+                .await?;
+            }
+            Event::ClipRemoved { id } => {
+                ManagerService::<Notification>::clip_removed(emitter, id).await?;
+            }
+            Event::ClipMarked { .. } => {}
+            // pinning changes neither the contents nor the length of the history
+            Event::ClipPinned { .. } => return Ok(()),
+            Event::HistoryCleared => {
+                ManagerService::<Notification>::history_cleared(emitter).await?;
+            }
+            Event::WatcherStateChanged { state } => {
+                return WatcherService::<Notification>::watcher_state_changed(
+                    self.watcher.signal_emitter(),
+                    state.into(),
+                )
+                .await;
+            }
+        }
+
+        // every change of the history may change the current clip and the length
+        let manager = self.manager.get().await;
+        manager.clipboard_text_contents_changed(emitter).await?;
+        manager.length_changed(emitter).await?;
+        drop(manager);
+
+        if let Some(klipper) = &self.klipper {
+            KlipperService::<Notification>::clipboard_history_updated(klipper.signal_emitter())
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use clipcat_dbus_variant as dbus_variant;
-use zbus::interface;
+use zbus::{interface, object_server::SignalEmitter};
 
 use crate::{metrics, notification, ClipboardWatcherToggle};
 
@@ -49,4 +49,10 @@ where
 
         self.watcher_toggle.state().into()
     }
+
+    #[zbus(signal)]
+    pub async fn watcher_state_changed(
+        emitter: &SignalEmitter<'_>,
+        state: dbus_variant::WatcherState,
+    ) -> zbus::Result<()>;
 }
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::{net::SocketAddr, path::PathBuf};
 
 use snafu::{Backtrace, Snafu};
 
@@ -10,9 +10,61 @@ pub enum Error {
     #[snafu(display("Error occurs while starting tonic server, error: {source}"))]
     StartTonicServer { source: tonic::transport::Error, backtrace: Backtrace },
 
+    #[snafu(display("Could not read TLS file `{}`, error: {source}", path.display()))]
+    ReadTlsFile { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not configure TLS of gRPC server, error: {source}"))]
+    ConfigureGrpcTls { source: tonic::transport::Error },
+
     #[snafu(display("Error occurs while creating Unix domain socket listener on `{}`, error: {source}", socket_path.display()))]
     CreateUnixListener { socket_path: PathBuf, source: std::io::Error, backtrace: Backtrace },
 
+    #[snafu(display(
+        "Error occurs while binding gRPC server on {listen_address}, error: {source}"
+    ))]
+    BindGrpcServer { listen_address: SocketAddr, source: std::io::Error },
+
+    #[snafu(display(
+        "Error occurs while binding HTTP server on {listen_address}, error: {source}"
+    ))]
+    BindHttpServer { listen_address: SocketAddr, source: std::io::Error },
+
+    #[snafu(display(
+        "Refuse to serve {name} on {listen_address} without an access token, set \
+         `grpc.access_token` or listen on a loopback address"
+    ))]
+    UnprotectedHttpServer { name: &'static str, listen_address: SocketAddr },
+
+    #[snafu(display("Error occurs while serving HTTP server, error: {source}"))]
+    ServeHttpServer { source: std::io::Error },
+
+    #[snafu(display("Could not open audit log `{}`, error: {source}", path.display()))]
+    OpenAuditLog { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not write audit log `{}`, error: {source}", path.display()))]
+    WriteAuditLog { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not read audit log `{}`, error: {source}", path.display()))]
+    ReadAuditLog { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not open event log `{}`, error: {source}", path.display()))]
+    OpenEventLog { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not write event log `{}`, error: {source}", path.display()))]
+    WriteEventLog { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not run notification command `{}`, error: {source}", program.display()))]
+    RunNotificationCommand { program: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not emit D-Bus notification signal, error: {source}"))]
+    EmitNotificationSignal { source: zbus::Error },
+
+    #[snafu(display("Could not open terminal `{}`, error: {source}", path.display()))]
+    OpenBellTerminal { path: PathBuf, source: std::io::Error },
+
+    #[snafu(display("Could not ring the bell of terminal `{}`, error: {source}", path.display()))]
+    RingBell { path: PathBuf, source: std::io::Error },
+
     #[snafu(display("Could not create clipboard backend, error: {source}"))]
     CreateClipboardBackend { source: crate::backend::Error },
 
@@ -37,6 +89,21 @@ pub enum Error {
     #[snafu(display("Could not generate clip filter, error: {source}"))]
     GenerateClipFilter { source: crate::watcher::ClipboardWatcherOptionsError },
 
+    #[snafu(display("Could not generate selection synchronization filter, error: {source}"))]
+    GenerateSynchronizationFilter { source: regex::Error },
+
+    #[snafu(display("Could not load configuration, error: {message}"))]
+    LoadConfig { message: String },
+
+    #[snafu(display("Could not reload configuration, no configuration loader is provided"))]
+    NoConfigLoader,
+
+    #[snafu(display("Could not reload configuration, the clipboard worker is shut down"))]
+    ReloadConfigWorkerClosed,
+
+    #[snafu(display("Could not create UNIX signal listener, error: {source}"))]
+    CreateSignalListener { source: std::io::Error },
+
     #[snafu(display("{source}"))]
     Metrics { source: clipcat_metrics::Error },
 }
@@ -0,0 +1,65 @@
+use clipcat_base::{ClipEntryMetadata, ClipboardKind, ClipboardWatcherState};
+use tokio::sync::broadcast;
+
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Length of the previews carried by [`Event::ClipAdded`].
+pub const PREVIEW_LENGTH: usize = 100;
+
+/// A change of the clipboard history or of the clipboard watcher.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    ClipAdded { metadata: ClipEntryMetadata },
+
+    ClipRemoved { id: u64 },
+
+    ClipMarked { id: u64, kind: ClipboardKind },
+
+    ClipPinned { id: u64, pinned: bool },
+
+    HistoryCleared,
+
+    WatcherStateChanged { state: ClipboardWatcherState },
+}
+
+/// Delivers [`Event`]s from the `ClipboardManager` and the clipboard watcher
+/// to the services notifying their clients, e.g. the D-Bus service.
+#[derive(Clone, Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl EventBus {
+    #[must_use]
+    pub fn new() -> Self {
+        let (sender, _receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    #[inline]
+    pub fn publish(&self, event: Event) {
+        // nobody is listening if sending fails
+        let _unused = self.sender.send(event);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> { self.sender.subscribe() }
+}
+
+impl From<Event> for clipcat_base::ClipboardEvent {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::ClipAdded { metadata } => Self::ClipAdded { metadata },
+            Event::ClipRemoved { id } => Self::ClipRemoved { id },
+            Event::ClipMarked { id, kind } => Self::ClipMarked { id, kind },
+            Event::ClipPinned { id, pinned } => Self::ClipPinned { id, pinned },
+            Event::HistoryCleared => Self::HistoryCleared,
+            Event::WatcherStateChanged { state } => Self::WatcherStateChanged { state },
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,33 @@
+use clipcat_base::AccessScope;
+use clipcat_proto as proto;
+use tonic::{Request, Response, Status};
+
+use crate::{audit::AuditLog, grpc::Grant};
+
+pub struct AuditService {
+    audit_log: AuditLog,
+}
+
+impl AuditService {
+    pub const fn new(audit_log: AuditLog) -> Self { Self { audit_log } }
+}
+
+#[tonic::async_trait]
+impl proto::Audit for AuditService {
+    async fn list_records(
+        &self,
+        request: Request<proto::ListAuditRecordsRequest>,
+    ) -> Result<Response<proto::ListAuditRecordsResponse>, Status> {
+        let _grant = Grant::check(&request, AccessScope::ReadAudit)?;
+        let proto::ListAuditRecordsRequest { limit } = request.into_inner();
+        let records = self
+            .audit_log
+            .list(usize::try_from(limit).unwrap_or(usize::MAX))
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?
+            .into_iter()
+            .map(proto::AuditRecord::from)
+            .collect();
+        Ok(Response::new(proto::ListAuditRecordsResponse { records }))
+    }
+}
@@ -0,0 +1,120 @@
+use std::{pin::Pin, time::Duration};
+
+use clipcat_proto as proto;
+use futures::Stream;
+use tonic::{Request, Response, Status};
+
+use crate::health::Health;
+
+/// Interval of checking the readiness for streaming changes of the status.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The standard gRPC health service, the empty service name stands for
+/// `clipcatd` as a whole.
+pub struct HealthService {
+    health: Health,
+}
+
+impl HealthService {
+    #[inline]
+    pub const fn new(health: Health) -> Self { Self { health } }
+}
+
+fn is_known_service(service: &str) -> bool {
+    service.is_empty() || proto::SERVICE_NAMES.contains(&service)
+}
+
+fn serving_status(health: &Health) -> proto::HealthServingStatus {
+    if health.is_ready() {
+        proto::HealthServingStatus::Serving
+    } else {
+        proto::HealthServingStatus::NotServing
+    }
+}
+
+#[tonic::async_trait]
+impl proto::Health for HealthService {
+    type WatchStream =
+        Pin<Box<dyn Stream<Item = Result<proto::HealthCheckResponse, Status>> + Send>>;
+
+    async fn check(
+        &self,
+        request: Request<proto::HealthCheckRequest>,
+    ) -> Result<Response<proto::HealthCheckResponse>, Status> {
+        let proto::HealthCheckRequest { service } = request.into_inner();
+        if !is_known_service(&service) {
+            return Err(Status::not_found(format!("Unknown service `{service}`")));
+        }
+        let status = serving_status(&self.health);
+        Ok(Response::new(proto::HealthCheckResponse { status: status.into() }))
+    }
+
+    async fn watch(
+        &self,
+        request: Request<proto::HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let proto::HealthCheckRequest { service } = request.into_inner();
+        if !is_known_service(&service) {
+            let status = proto::HealthServingStatus::ServiceUnknown;
+            let response = proto::HealthCheckResponse { status: status.into() };
+            return Ok(Response::new(Box::pin(futures::stream::iter([Ok(response)]))));
+        }
+
+        // the current status is sent first, then only the changes are sent
+        let stream =
+            futures::stream::unfold((self.health.clone(), None), |(health, last)| async move {
+                loop {
+                    let status = serving_status(&health);
+                    if last != Some(status) {
+                        let response = proto::HealthCheckResponse { status: status.into() };
+                        return Some((Ok(response), (health, Some(status))));
+                    }
+                    tokio::time::sleep(WATCH_INTERVAL).await;
+                }
+            });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use clipcat_proto::{self as proto, Health as _};
+    use futures::StreamExt;
+    use tonic::{Code, Request};
+
+    use super::HealthService;
+    use crate::health::{Health, ListenerStates};
+
+    fn request(service: &str) -> Request<proto::HealthCheckRequest> {
+        Request::new(proto::HealthCheckRequest { service: service.to_string() })
+    }
+
+    #[tokio::test]
+    async fn test_health_service() {
+        let is_watching = Arc::new(AtomicBool::new(true));
+        let service =
+            HealthService::new(Health::new(ListenerStates::default(), true, is_watching.clone()));
+
+        for name in ["", "clipcat.Manager"] {
+            let response = service.check(request(name)).await.unwrap().into_inner();
+            assert_eq!(response.status(), proto::HealthServingStatus::Serving);
+        }
+        assert_eq!(service.check(request("unknown")).await.unwrap_err().code(), Code::NotFound);
+
+        let mut stream = service.watch(request("unknown")).await.unwrap().into_inner();
+        let response = stream.next().await.unwrap().unwrap();
+        assert_eq!(response.status(), proto::HealthServingStatus::ServiceUnknown);
+
+        let mut stream = service.watch(request("")).await.unwrap().into_inner();
+        let response = stream.next().await.unwrap().unwrap();
+        assert_eq!(response.status(), proto::HealthServingStatus::Serving);
+        is_watching.store(false, Ordering::Release);
+        let response = stream.next().await.unwrap().unwrap();
+        assert_eq!(response.status(), proto::HealthServingStatus::NotServing);
+    }
+}
@@ -1,44 +1,257 @@
-use std::fmt;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
 
+use clipcat_base::AccessScope;
 use tonic::{metadata::AsciiMetadataValue, Request, Status};
 
-use crate::metrics;
+use crate::{config::ScopedAccessToken, metrics};
 
-#[derive(Clone, Debug, Default)]
+/// Name of the access token which is configured without a name.
+const DEFAULT_TOKEN_NAME: &str = "default";
+
+/// Longest preview of clips listed without the [`AccessScope::ReadContent`]
+/// scope.
+const MAX_METADATA_PREVIEW_LENGTH: usize = 100;
+
+/// Permissions granted to a request by its access token.
+#[derive(Debug)]
+pub struct Grant {
+    // `None` if no access token is configured
+    token_name: Option<String>,
+
+    scopes: HashSet<AccessScope>,
+}
+
+impl Grant {
+    fn unrestricted(token_name: Option<String>) -> Self {
+        Self { token_name, scopes: AccessScope::all_scopes().into_iter().collect() }
+    }
+
+    /// Name of the access token, for logging who performs an operation.
+    pub fn token_name(&self) -> &str { self.token_name.as_deref().unwrap_or("anonymous") }
+
+    pub fn allows(&self, scope: AccessScope) -> bool { self.scopes.contains(&scope) }
+
+    /// Returns the length of previews for listing clips, `preview_length` is
+    /// limited without the [`AccessScope::ReadContent`] scope, and `0`, which
+    /// means no limit, is replaced by `default`.
+    pub fn preview_length(&self, preview_length: usize, default: usize) -> usize {
+        if self.allows(AccessScope::ReadContent) {
+            preview_length
+        } else if preview_length == 0 {
+            default.min(MAX_METADATA_PREVIEW_LENGTH)
+        } else {
+            preview_length.min(MAX_METADATA_PREVIEW_LENGTH)
+        }
+    }
+
+    /// Returns the grant of `request` if it allows `scope`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request is not granted
+    /// `scope`, or it does not pass through [`Interceptor`].
+    pub fn check<T>(
+        request: &Request<T>,
+        scope: AccessScope,
+    ) -> Result<Arc<Self>, PermissionDenied> {
+        match request.extensions().get::<Arc<Self>>() {
+            Some(grant) if grant.allows(scope) => Ok(grant.clone()),
+            Some(grant) => Err(PermissionDenied {
+                message: format!(
+                    "Access token `{}` is not granted the `{scope}` scope",
+                    grant.token_name()
+                ),
+            }),
+            None => Err(PermissionDenied { message: "No grant of access token".to_string() }),
+        }
+    }
+}
+
+/// Converted into `PERMISSION_DENIED` status of gRPC.
+#[derive(Debug)]
+pub struct PermissionDenied {
+    message: String,
+}
+
+impl From<PermissionDenied> for Status {
+    fn from(PermissionDenied { message }: PermissionDenied) -> Self {
+        Self::permission_denied(message)
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Interceptor {
-    authorization_metadata_value: Option<AsciiMetadataValue>,
+    // keyed by the expected value of the `authorization` header
+    grants: HashMap<AsciiMetadataValue, Arc<Grant>>,
+
+    // granted to every request if no access token is configured
+    anonymous: Arc<Grant>,
+}
+
+impl Default for Interceptor {
+    fn default() -> Self {
+        Self { grants: HashMap::new(), anonymous: Arc::new(Grant::unrestricted(None)) }
+    }
 }
 
 impl Interceptor {
+    /// Creates an interceptor accepting `access_token` with all scopes.
     pub fn new<S>(access_token: Option<S>) -> Self
     where
         S: fmt::Display,
     {
-        let authorization_metadata_value = match access_token {
-            Some(token) if token.to_string().is_empty() => None,
-            Some(token) => AsciiMetadataValue::try_from(format!("Bearer {token}"))
-                .map_err(|err| {
-                    tracing::warn!("{err}");
-                })
-                .ok(),
-            None => None,
-        };
+        let mut interceptor = Self::default();
+        if let Some(token) = access_token {
+            interceptor.insert(&token, Grant::unrestricted(Some(DEFAULT_TOKEN_NAME.to_string())));
+        }
+        interceptor
+    }
 
-        Self { authorization_metadata_value }
+    /// Accepts `tokens` in addition, each of them is granted only its scopes.
+    #[must_use]
+    pub fn with_scoped_access_tokens<I>(mut self, tokens: I) -> Self
+    where
+        I: IntoIterator<Item = ScopedAccessToken>,
+    {
+        for ScopedAccessToken { name, token, scopes } in tokens {
+            self.insert(&token, Grant { token_name: Some(name), scopes });
+        }
+        self
+    }
+
+    fn insert<S>(&mut self, token: &S, grant: Grant)
+    where
+        S: fmt::Display,
+    {
+        let token = token.to_string();
+        if token.is_empty() {
+            return;
+        }
+        match AsciiMetadataValue::try_from(format!("Bearer {token}")) {
+            Ok(value) => {
+                if let Some(previous) = self.grants.insert(value, Arc::new(grant)) {
+                    tracing::warn!(
+                        "Access token `{}` has the same value as another token, it is ignored",
+                        previous.token_name()
+                    );
+                }
+            }
+            Err(err) => tracing::warn!("{err}"),
+        }
+    }
+
+    /// Returns `true` if any access token is configured.
+    #[inline]
+    #[must_use]
+    pub fn requires_token(&self) -> bool { !self.grants.is_empty() }
+
+    /// Checks the value of the `authorization` header, any request is
+    /// granted all scopes if no access token is configured.
+    pub fn authorize(&self, authorization: Option<&[u8]>) -> Option<Arc<Grant>> {
+        if self.grants.is_empty() {
+            return Some(self.anonymous.clone());
+        }
+        let authorization = AsciiMetadataValue::try_from(authorization?).ok()?;
+        self.grants.get(&authorization).cloned()
     }
 }
 
 impl tonic::service::Interceptor for Interceptor {
-    fn call(&mut self, req: Request<()>) -> Result<Request<()>, Status> {
+    fn call(&mut self, mut req: Request<()>) -> Result<Request<()>, Status> {
         metrics::grpc::REQUESTS_TOTAL.inc();
 
-        if let Some(ref expected) = self.authorization_metadata_value {
-            match req.metadata().get("authorization") {
-                Some(token) if expected == token => Ok(req),
-                _ => Err(Status::unauthenticated("No valid authorization token")),
-            }
-        } else {
-            Ok(req)
-        }
+        let grant = self
+            .authorize(req.metadata().get("authorization").map(AsciiMetadataValue::as_bytes))
+            .ok_or_else(|| Status::unauthenticated("No valid authorization token"))?;
+        let _unused = req.extensions_mut().insert(grant);
+        Ok(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use clipcat_base::AccessScope;
+    use tonic::{service::Interceptor as _, Code, Request, Status};
+
+    use super::{Grant, Interceptor};
+    use crate::config::ScopedAccessToken;
+
+    #[test]
+    fn test_authorize() {
+        let interceptor = Interceptor::default();
+        assert!(!interceptor.requires_token());
+        let grant = interceptor.authorize(None).unwrap();
+        assert_eq!(grant.token_name(), "anonymous");
+        assert!(grant.allows(AccessScope::Delete));
+
+        let interceptor =
+            Interceptor::new(Some("secret")).with_scoped_access_tokens([ScopedAccessToken {
+                name: "status-bar".to_string(),
+                token: "preview".to_string(),
+                scopes: HashSet::from([AccessScope::ReadMetadata]),
+            }]);
+        assert!(interceptor.requires_token());
+        assert!(interceptor.authorize(None).is_none());
+        assert!(interceptor.authorize(Some(b"Bearer unknown")).is_none());
+
+        let grant = interceptor.authorize(Some(b"Bearer secret")).unwrap();
+        assert_eq!(grant.token_name(), "default");
+        assert!(AccessScope::all_scopes().into_iter().all(|scope| grant.allows(scope)));
+
+        let grant = interceptor.authorize(Some(b"Bearer preview")).unwrap();
+        assert_eq!(grant.token_name(), "status-bar");
+        assert!(grant.allows(AccessScope::ReadMetadata));
+        assert!(!grant.allows(AccessScope::ReadContent));
+        assert!(!grant.allows(AccessScope::Delete));
+    }
+
+    #[test]
+    fn test_check() {
+        let mut interceptor =
+            Interceptor::new(None::<String>).with_scoped_access_tokens([ScopedAccessToken {
+                name: "status-bar".to_string(),
+                token: "preview".to_string(),
+                scopes: HashSet::from([AccessScope::ReadMetadata]),
+            }]);
+
+        let mut request = Request::new(());
+        let _unused =
+            request.metadata_mut().insert("authorization", "Bearer preview".parse().unwrap());
+        let request = interceptor.call(request).unwrap();
+        assert!(Grant::check(&request, AccessScope::ReadMetadata).is_ok());
+        let status = Status::from(Grant::check(&request, AccessScope::Delete).unwrap_err());
+        assert_eq!(status.code(), Code::PermissionDenied);
+
+        let status = interceptor.call(Request::new(())).unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+        let status =
+            Status::from(Grant::check(&Request::new(()), AccessScope::ReadMetadata).unwrap_err());
+        assert_eq!(status.code(), Code::PermissionDenied);
+    }
+
+    #[test]
+    fn test_preview_length() {
+        let interceptor =
+            Interceptor::new(Some("secret")).with_scoped_access_tokens([ScopedAccessToken {
+                name: "status-bar".to_string(),
+                token: "preview".to_string(),
+                scopes: HashSet::from([AccessScope::ReadMetadata]),
+            }]);
+
+        let grant = interceptor.authorize(Some(b"Bearer secret")).unwrap();
+        assert_eq!(grant.preview_length(0, 30), 0);
+        assert_eq!(grant.preview_length(1000, 30), 1000);
+
+        // previews do not reveal the whole contents without `ReadContent`
+        let grant = interceptor.authorize(Some(b"Bearer preview")).unwrap();
+        assert_eq!(grant.preview_length(0, 30), 30);
+        assert_eq!(grant.preview_length(20, 30), 20);
+        assert_eq!(grant.preview_length(1000, 30), super::MAX_METADATA_PREVIEW_LENGTH);
     }
 }
@@ -0,0 +1,102 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use tonic::codegen::http;
+
+use crate::metrics;
+
+/// `grpc-status` of requests to unknown services or methods, they are not
+/// recorded so that the number of label values is bounded.
+const UNIMPLEMENTED: &str = "12";
+
+/// Records latencies of gRPC requests per method.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LatencyLayer;
+
+impl<S> tower::Layer<S> for LatencyLayer {
+    type Service = Latency<S>;
+
+    fn layer(&self, inner: S) -> Self::Service { Latency { inner } }
+}
+
+#[derive(Clone, Debug)]
+pub struct Latency<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> tower::Service<http::Request<ReqBody>> for Latency<S>
+where
+    S: tower::Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<ReqBody>) -> Self::Future {
+        // the path is `/<package>.<service>/<method>`
+        let method = req.uri().path().to_string();
+        let start = Instant::now();
+        let future = self.inner.call(req);
+        Box::pin(async move {
+            let result = future.await;
+            let is_unimplemented = result.as_ref().is_ok_and(|response| {
+                response.headers().get("grpc-status").is_some_and(|status| status == UNIMPLEMENTED)
+            });
+            if !is_unimplemented {
+                metrics::grpc::REQUEST_DURATION_SECONDS
+                    .with_label_values(&[method.as_str()])
+                    .observe(start.elapsed().as_secs_f64());
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tonic::codegen::http;
+    use tower::{Layer, ServiceExt};
+
+    use super::{LatencyLayer, UNIMPLEMENTED};
+    use crate::metrics;
+
+    async fn call(path: &str, grpc_status: &str) {
+        let grpc_status = grpc_status.to_string();
+        let service = LatencyLayer.layer(tower::service_fn(move |_: http::Request<()>| {
+            let response =
+                http::Response::builder().header("grpc-status", grpc_status.as_str()).body(());
+            async move { Ok::<_, Infallible>(response.unwrap()) }
+        }));
+        let request = http::Request::builder().uri(path).body(()).unwrap();
+        let _response = service.oneshot(request).await.unwrap();
+    }
+
+    fn sample_count(path: &str) -> u64 {
+        metrics::grpc::REQUEST_DURATION_SECONDS.with_label_values(&[path]).get_sample_count()
+    }
+
+    #[tokio::test]
+    async fn test_latency() {
+        let path = "/clipcat.LatencyTest/Recorded";
+        call(path, "0").await;
+        // failed requests are recorded
+        call(path, "5").await;
+        assert_eq!(sample_count(path), 2);
+
+        // unknown methods are not recorded
+        let path = "/clipcat.LatencyTest/Unknown";
+        call(path, UNIMPLEMENTED).await;
+        assert_eq!(sample_count(path), 0);
+    }
+}
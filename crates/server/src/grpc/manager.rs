@@ -1,18 +1,38 @@
-use std::{str::FromStr, sync::Arc};
+use std::{pin::Pin, str::FromStr, sync::Arc};
 
+use clipcat_base::{AccessScope, AuditOperation, ClipboardEvent};
 use clipcat_proto as proto;
-use tokio::sync::Mutex;
+use futures::{Stream, StreamExt};
+use tokio::sync::{broadcast::error::RecvError, watch, Mutex};
 use tonic::{Request, Response, Status};
 
-use crate::{notification, ClipboardManager};
+use crate::{
+    audit::AuditLog,
+    events::EventBus,
+    grpc::{self, Grant},
+    notification, ClipboardManager,
+};
 
 pub struct ManagerService<Notification> {
     manager: Arc<Mutex<ClipboardManager<Notification>>>,
+
+    event_bus: EventBus,
+
+    audit_log: AuditLog,
+
+    // event streams are closed when the server is shutting down, otherwise
+    // the graceful shutdown waits for them forever
+    shutdown: watch::Receiver<bool>,
 }
 
 impl<Notification> ManagerService<Notification> {
-    pub const fn new(manager: Arc<Mutex<ClipboardManager<Notification>>>) -> Self {
-        Self { manager }
+    pub const fn new(
+        manager: Arc<Mutex<ClipboardManager<Notification>>>,
+        event_bus: EventBus,
+        audit_log: AuditLog,
+        shutdown: watch::Receiver<bool>,
+    ) -> Self {
+        Self { manager, event_bus, audit_log, shutdown }
     }
 }
 
@@ -21,10 +41,14 @@ impl<Notification> proto::Manager for ManagerService<Notification>
 where
     Notification: notification::Notification + 'static,
 {
+    type SubscribeStream =
+        Pin<Box<dyn Stream<Item = Result<proto::ClipboardEvent, Status>> + Send>>;
+
     async fn insert(
         &self,
         request: Request<proto::InsertRequest>,
     ) -> Result<Response<proto::InsertResponse>, Status> {
+        let _grant = Grant::check(&request, AccessScope::Insert)?;
         let proto::InsertRequest { data, mime, kind } = request.into_inner();
         let id = {
             let mime = mime::Mime::from_str(&mime).unwrap_or(mime::APPLICATION_OCTET_STREAM);
@@ -43,11 +67,17 @@ where
         &self,
         request: Request<proto::RemoveRequest>,
     ) -> Result<Response<proto::RemoveResponse>, Status> {
+        let grant = Grant::check(&request, AccessScope::Delete)?;
+        let peer = grpc::audit_peer(&request, &grant);
         let id = request.into_inner().id;
         let ok = {
             let mut manager = self.manager.lock().await;
             manager.remove(id)
         };
+        self.audit_log.record(&peer, AuditOperation::Remove, vec![id]).await;
+        if ok {
+            tracing::info!("Clip {id} is removed with access token `{}`", grant.token_name());
+        }
         Ok(Response::new(proto::RemoveResponse { ok }))
     }
 
@@ -55,19 +85,34 @@ where
         &self,
         request: Request<proto::BatchRemoveRequest>,
     ) -> Result<Response<proto::BatchRemoveResponse>, Status> {
+        let grant = Grant::check(&request, AccessScope::Delete)?;
+        let peer = grpc::audit_peer(&request, &grant);
         let ids = request.into_inner().ids;
-        let ids = {
+        let ids: Vec<_> = {
             let mut manager = self.manager.lock().await;
             ids.into_iter().filter(|id| manager.remove(*id)).collect()
         };
+        self.audit_log.record(&peer, AuditOperation::Remove, ids.clone()).await;
+        if !ids.is_empty() {
+            tracing::info!(
+                "{count} clip(s) are removed with access token `{token}`",
+                count = ids.len(),
+                token = grant.token_name()
+            );
+        }
         Ok(Response::new(proto::BatchRemoveResponse { ids }))
     }
 
-    async fn clear(&self, _request: Request<()>) -> Result<Response<()>, Status> {
+    async fn clear(&self, request: Request<()>) -> Result<Response<()>, Status> {
+        let grant = Grant::check(&request, AccessScope::Delete)?;
         {
             let mut manager = self.manager.lock().await;
             manager.clear();
         }
+        self.audit_log
+            .record(&grpc::audit_peer(&request, &grant), AuditOperation::Clear, Vec::new())
+            .await;
+        tracing::info!("History is cleared with access token `{}`", grant.token_name());
         Ok(Response::new(()))
     }
 
@@ -75,23 +120,50 @@ where
         &self,
         request: Request<proto::GetRequest>,
     ) -> Result<Response<proto::GetResponse>, Status> {
+        let grant = Grant::check(&request, AccessScope::ReadContent)?;
+        let peer = grpc::audit_peer(&request, &grant);
         let proto::GetRequest { id } = request.into_inner();
         let data = {
             let manager = self.manager.lock().await;
             manager.get(id).map(Into::into)
         };
+        self.audit_log.record(&peer, AuditOperation::Get, vec![id]).await;
         Ok(Response::new(proto::GetResponse { data }))
     }
 
+    async fn get_metadata(
+        &self,
+        request: Request<proto::GetMetadataRequest>,
+    ) -> Result<Response<proto::GetMetadataResponse>, Status> {
+        let grant = Grant::check(&request, AccessScope::ReadMetadata)?;
+        let peer = grpc::audit_peer(&request, &grant);
+        let proto::GetMetadataRequest { id, preview_length } = request.into_inner();
+        let preview_length =
+            grant.preview_length(usize::try_from(preview_length).unwrap_or(30), 30);
+        let metadata = {
+            let manager = self.manager.lock().await;
+            manager.get_metadata(id, preview_length).map(proto::ClipEntryMetadata::from)
+        };
+        // the preview is not limited, it contains the whole contents
+        if preview_length == 0 {
+            self.audit_log.record(&peer, AuditOperation::Get, vec![id]).await;
+        }
+        Ok(Response::new(proto::GetMetadataResponse { metadata }))
+    }
+
     async fn get_current_clip(
         &self,
         request: Request<proto::GetCurrentClipRequest>,
     ) -> Result<Response<proto::GetCurrentClipResponse>, Status> {
-        let data = {
+        let grant = Grant::check(&request, AccessScope::ReadContent)?;
+        let peer = grpc::audit_peer(&request, &grant);
+        let data: Option<proto::ClipEntry> = {
             let kind = request.into_inner().kind.into();
             let manager = self.manager.lock().await;
             manager.get_current_clip(kind).map(|clip| clip.clone().into())
         };
+        let clip_ids = data.iter().map(|clip| clip.id).collect();
+        self.audit_log.record(&peer, AuditOperation::GetCurrentClip, clip_ids).await;
         Ok(Response::new(proto::GetCurrentClipResponse { data }))
     }
 
@@ -99,15 +171,20 @@ where
         &self,
         request: Request<proto::ListRequest>,
     ) -> Result<Response<proto::ListResponse>, Status> {
+        let grant = Grant::check(&request, AccessScope::ReadMetadata)?;
+        let peer = grpc::audit_peer(&request, &grant);
         let proto::ListRequest { preview_length } = request.into_inner();
-        let metadata = {
+        let preview_length =
+            grant.preview_length(usize::try_from(preview_length).unwrap_or(30), 30);
+        let metadata: Vec<proto::ClipEntryMetadata> = {
             let manager = self.manager.lock().await;
-            manager
-                .list(usize::try_from(preview_length).unwrap_or(30))
-                .into_iter()
-                .map(proto::ClipEntryMetadata::from)
-                .collect()
+            manager.list(preview_length).into_iter().map(proto::ClipEntryMetadata::from).collect()
         };
+        // previews are not limited, they contain the whole contents
+        if preview_length == 0 {
+            let clip_ids = metadata.iter().map(|metadata| metadata.id).collect();
+            self.audit_log.record(&peer, AuditOperation::Export, clip_ids).await;
+        }
         Ok(Response::new(proto::ListResponse { metadata }))
     }
 
@@ -115,12 +192,23 @@ where
         &self,
         request: Request<proto::UpdateRequest>,
     ) -> Result<Response<proto::UpdateResponse>, Status> {
+        // the original clip is removed
+        let _grant = Grant::check(&request, AccessScope::Insert)?;
+        let grant = Grant::check(&request, AccessScope::Delete)?;
+        let peer = grpc::audit_peer(&request, &grant);
         let proto::UpdateRequest { id, data, mime } = request.into_inner();
         let (ok, new_id) = {
             let mime = mime::Mime::from_str(&mime).unwrap_or(mime::APPLICATION_OCTET_STREAM);
             let mut manager = self.manager.lock().await;
             manager.replace(id, &data, &mime)
         };
+        if ok {
+            tracing::info!(
+                "Clip {id} is replaced by clip {new_id} with access token `{}`",
+                grant.token_name()
+            );
+            self.audit_log.record(&peer, AuditOperation::Remove, vec![id]).await;
+        }
         Ok(Response::new(proto::UpdateResponse { ok, new_id }))
     }
 
@@ -128,22 +216,101 @@ where
         &self,
         request: Request<proto::MarkRequest>,
     ) -> Result<Response<proto::MarkResponse>, Status> {
+        let grant = Grant::check(&request, AccessScope::Mark)?;
+        let peer = grpc::audit_peer(&request, &grant);
         let proto::MarkRequest { id, kind } = request.into_inner();
         let ok = {
             let mut manager = self.manager.lock().await;
             manager.mark(id, kind.into()).await.is_ok()
         };
+        self.audit_log.record(&peer, AuditOperation::Mark, vec![id]).await;
         Ok(Response::new(proto::MarkResponse { ok }))
     }
 
     async fn length(
         &self,
-        _request: Request<()>,
+        request: Request<()>,
     ) -> Result<Response<proto::LengthResponse>, Status> {
+        let _grant = Grant::check(&request, AccessScope::ReadMetadata)?;
         let length = {
             let manager = self.manager.lock().await;
             manager.len() as u64
         };
         Ok(Response::new(proto::LengthResponse { length }))
     }
+
+    async fn subscribe(
+        &self,
+        request: Request<()>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let _grant = Grant::check(&request, AccessScope::ReadMetadata)?;
+        let receiver = self.event_bus.subscribe();
+        let mut shutdown = self.shutdown.clone();
+        let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+            let event = match receiver.recv().await {
+                Ok(event) => ClipboardEvent::from(event),
+                // the subscriber is told how many events are missed
+                Err(RecvError::Lagged(missed)) => ClipboardEvent::Lagged { missed },
+                Err(RecvError::Closed) => return None,
+            };
+            Some((Ok(proto::ClipboardEvent::from(event)), receiver))
+        })
+        .take_until(async move {
+            let _unused = shutdown.wait_for(|shutdown| *shutdown).await;
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use clipcat_base::{ClipEntry, ClipboardEvent, ClipboardKind};
+    use clipcat_proto::Manager as _;
+    use futures::StreamExt;
+    use tokio::sync::{watch, Mutex};
+    use tonic::Request;
+
+    use super::ManagerService;
+    use crate::{
+        audit::AuditLog, backend::LocalClipboardBackend, events::EventBus, grpc::Interceptor,
+        manager::ClipboardManager, notification::DummyNotification,
+    };
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let event_bus = EventBus::new();
+        let manager = Arc::new(Mutex::new(ClipboardManager::with_capacity(
+            Arc::new(LocalClipboardBackend::new()),
+            10,
+            event_bus.clone(),
+            DummyNotification::default(),
+        )));
+        let (shutdown_sender, shutdown) = watch::channel(false);
+        let service =
+            ManagerService::new(manager.clone(), event_bus, AuditLog::default(), shutdown);
+
+        let request = Interceptor::default().authorize(None).map(|grant| {
+            let mut request = Request::new(());
+            let _unused = request.extensions_mut().insert(grant);
+            request
+        });
+        let mut stream = service.subscribe(request.unwrap()).await.unwrap().into_inner();
+
+        let id =
+            manager.lock().await.insert(ClipEntry::from_string("clipcat", ClipboardKind::Primary));
+        let event = stream.next().await.unwrap().unwrap().event.map(ClipboardEvent::from);
+        assert!(matches!(event, Some(ClipboardEvent::ClipAdded { metadata }) if metadata.id == id));
+        assert!(manager.lock().await.remove(id));
+        let event = stream.next().await.unwrap().unwrap().event.map(ClipboardEvent::from);
+        assert_eq!(event, Some(ClipboardEvent::ClipRemoved { id }));
+
+        // the stream is closed when the server is shutting down
+        shutdown_sender.send(true).unwrap();
+        assert!(stream.next().await.is_none());
+
+        // requests without a grant are rejected
+        assert!(service.subscribe(Request::new(())).await.is_err());
+    }
 }
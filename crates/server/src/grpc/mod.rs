@@ -1,9 +1,183 @@
+mod audit;
+mod health;
 mod interceptor;
+mod latency;
 mod manager;
 mod system;
 mod watcher;
 
+use std::path::Path;
+
+use clipcat_base::AuditTransport;
+use snafu::ResultExt;
+use tonic::{
+    transport::{server::UdsConnectInfo, Certificate, Identity, ServerTlsConfig},
+    Request,
+};
+
 pub use self::{
-    interceptor::Interceptor, manager::ManagerService, system::SystemService,
+    audit::AuditService,
+    health::HealthService,
+    interceptor::{Grant, Interceptor},
+    latency::LatencyLayer,
+    manager::ManagerService,
+    system::SystemService,
     watcher::WatcherService,
 };
+use crate::{
+    audit::Peer,
+    config::GrpcTlsConfig,
+    error::{self, Error},
+};
+
+/// Identifies the peer of `request` for the audit log, the credentials of
+/// peers are available on the local socket only.
+pub fn audit_peer<T>(request: &Request<T>, grant: &Grant) -> Peer {
+    if let Some(UdsConnectInfo { peer_cred, .. }) = request.extensions().get::<UdsConnectInfo>() {
+        Peer::new(AuditTransport::GrpcLocalSocket)
+            .with_token_name(grant.token_name())
+            .with_credentials(
+                peer_cred.map(|credentials| credentials.uid()),
+                peer_cred.and_then(|credentials| credentials.pid()),
+            )
+    } else {
+        Peer::new(AuditTransport::GrpcHttp)
+            .with_token_name(grant.token_name())
+            .with_address(request.remote_addr())
+    }
+}
+
+/// Reads the certificate, the private key and the optional client CA of
+/// the gRPC HTTP server.
+///
+/// # Errors
+///
+/// This function will return an error if any of the files could not be read.
+pub fn load_tls_config(config: &GrpcTlsConfig) -> Result<ServerTlsConfig, Error> {
+    let read = |path: &Path| {
+        std::fs::read(path).context(error::ReadTlsFileSnafu { path: path.to_path_buf() })
+    };
+
+    let identity =
+        Identity::from_pem(read(&config.certificate_file_path)?, read(&config.key_file_path)?);
+    let mut tls_config = ServerTlsConfig::new().identity(identity);
+    if let Some(ref client_ca_file_path) = config.client_ca_file_path {
+        tls_config = tls_config.client_ca_root(Certificate::from_pem(read(client_ca_file_path)?));
+    }
+    Ok(tls_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use clipcat_proto::{SystemClient, SystemServer};
+    use rcgen::{BasicConstraints, CertificateParams, IsCa, KeyPair};
+    use tokio::{net::TcpListener, sync::oneshot};
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity, Server};
+
+    use super::{load_tls_config, SystemService};
+    use crate::{config::GrpcTlsConfig, reload::ReloadHandle};
+
+    struct Pem {
+        certificate: String,
+        key: String,
+    }
+
+    fn generate_certificates() -> (Pem, Pem, Pem) {
+        let ca_key = KeyPair::generate().unwrap();
+        let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+
+        let sign = |subject: &str| {
+            let key = KeyPair::generate().unwrap();
+            let certificate = CertificateParams::new(vec![subject.to_string()])
+                .unwrap()
+                .signed_by(&key, &ca, &ca_key)
+                .unwrap();
+            Pem { certificate: certificate.pem(), key: key.serialize_pem() }
+        };
+        let server = sign("localhost");
+        let client = sign("clipcatctl");
+        (Pem { certificate: ca.pem(), key: ca_key.serialize_pem() }, server, client)
+    }
+
+    fn write(directory: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = directory.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    async fn get_version(port: u16, tls_config: ClientTlsConfig) -> Result<u64, tonic::Status> {
+        let channel = Channel::from_shared(format!("https://localhost:{port}"))
+            .unwrap()
+            .tls_config(tls_config)
+            .unwrap()
+            .connect()
+            .await
+            .map_err(|err| tonic::Status::unavailable(err.to_string()))?;
+        Ok(SystemClient::new(channel).get_version(()).await?.into_inner().major)
+    }
+
+    #[tokio::test]
+    async fn test_mutual_tls() {
+        let directory = tempfile::tempdir().unwrap();
+        let directory = directory.path();
+        let (ca, server, client) = generate_certificates();
+        let tls_config = GrpcTlsConfig {
+            certificate_file_path: write(directory, "server.pem", &server.certificate),
+            key_file_path: write(directory, "server.key", &server.key),
+            client_ca_file_path: Some(write(directory, "ca.pem", &ca.certificate)),
+        };
+        let server_tls_config = load_tls_config(&tls_config).unwrap();
+        assert!(load_tls_config(&GrpcTlsConfig {
+            key_file_path: directory.join("missing.key"),
+            ..tls_config
+        })
+        .is_err());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let serve = tokio::spawn(
+            Server::builder()
+                .tls_config(server_tls_config)
+                .unwrap()
+                .add_service(SystemServer::new(SystemService::new(ReloadHandle::new().0)))
+                .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async move {
+                    let _unused = shutdown_receiver.await;
+                }),
+        );
+
+        let ca_certificate = Certificate::from_pem(&ca.certificate);
+        let major = get_version(
+            port,
+            ClientTlsConfig::new()
+                .ca_certificate(ca_certificate.clone())
+                .identity(Identity::from_pem(&client.certificate, &client.key)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(major, clipcat_base::PROJECT_SEMVER.major);
+
+        // the server rejects clients without a certificate signed by the client CA
+        assert!(get_version(port, ClientTlsConfig::new().ca_certificate(ca_certificate))
+            .await
+            .is_err());
+        // the client rejects servers with a certificate not signed by its CA
+        let (other_ca, ..) = generate_certificates();
+        assert!(get_version(
+            port,
+            ClientTlsConfig::new()
+                .ca_certificate(Certificate::from_pem(&other_ca.certificate))
+                .identity(Identity::from_pem(&client.certificate, &client.key)),
+        )
+        .await
+        .is_err());
+
+        shutdown_sender.send(()).unwrap();
+        serve.await.unwrap().unwrap();
+    }
+}
@@ -1,7 +1,10 @@
+use clipcat_base::AccessScope;
 use clipcat_proto as proto;
 use once_cell::sync::Lazy;
 use tonic::{Request, Response, Status};
 
+use crate::{error::Error, grpc::Grant, reload::ReloadHandle};
+
 static GET_SYSTEM_VERSION_RESPONSE: Lazy<proto::GetSystemVersionResponse> =
     Lazy::new(|| proto::GetSystemVersionResponse {
         major: clipcat_base::PROJECT_SEMVER.major,
@@ -9,11 +12,13 @@ static GET_SYSTEM_VERSION_RESPONSE: Lazy<proto::GetSystemVersionResponse> =
         patch: clipcat_base::PROJECT_SEMVER.patch,
     });
 
-pub struct SystemService {}
+pub struct SystemService {
+    reload_handle: ReloadHandle,
+}
 
 impl SystemService {
     #[inline]
-    pub const fn new() -> Self { Self {} }
+    pub const fn new(reload_handle: ReloadHandle) -> Self { Self { reload_handle } }
 }
 
 #[tonic::async_trait]
@@ -24,4 +29,19 @@ impl proto::System for SystemService {
     ) -> Result<Response<proto::GetSystemVersionResponse>, Status> {
         Ok(Response::new(*GET_SYSTEM_VERSION_RESPONSE))
     }
+
+    async fn reload_config(
+        &self,
+        request: Request<()>,
+    ) -> Result<Response<proto::ReloadConfigResponse>, Status> {
+        let _grant = Grant::check(&request, AccessScope::ReloadConfig)?;
+        match self.reload_handle.reload().await {
+            Ok(restart_required) => Ok(Response::new(proto::ReloadConfigResponse {
+                restart_required: restart_required.into_iter().map(ToString::to_string).collect(),
+            })),
+            Err(err @ Error::NoConfigLoader) => Err(Status::unimplemented(err.to_string())),
+            Err(err @ Error::ReloadConfigWorkerClosed) => Err(Status::unavailable(err.to_string())),
+            Err(err) => Err(Status::failed_precondition(err.to_string())),
+        }
+    }
 }
@@ -1,7 +1,8 @@
+use clipcat_base::AccessScope;
 use clipcat_proto as proto;
 use tonic::{Request, Response, Status};
 
-use crate::{notification, ClipboardWatcherToggle};
+use crate::{grpc::Grant, notification, ClipboardWatcherToggle};
 
 pub struct WatcherService<Notification> {
     watcher_toggle: ClipboardWatcherToggle<Notification>,
@@ -21,8 +22,9 @@ where
 {
     async fn enable_watcher(
         &self,
-        _request: Request<()>,
+        request: Request<()>,
     ) -> Result<Response<proto::WatcherStateReply>, Status> {
+        let _grant = Grant::check(&request, AccessScope::WatcherControl)?;
         self.watcher_toggle.enable();
         let state = proto::WatcherStateReply { state: self.watcher_toggle.state().into() };
         Ok(Response::new(state))
@@ -30,8 +32,9 @@ where
 
     async fn disable_watcher(
         &self,
-        _request: Request<()>,
+        request: Request<()>,
     ) -> Result<Response<proto::WatcherStateReply>, Status> {
+        let _grant = Grant::check(&request, AccessScope::WatcherControl)?;
         self.watcher_toggle.disable();
         let state = proto::WatcherStateReply { state: self.watcher_toggle.state().into() };
         Ok(Response::new(state))
@@ -39,8 +42,9 @@ where
 
     async fn toggle_watcher(
         &self,
-        _request: Request<()>,
+        request: Request<()>,
     ) -> Result<Response<proto::WatcherStateReply>, Status> {
+        let _grant = Grant::check(&request, AccessScope::WatcherControl)?;
         self.watcher_toggle.toggle();
         let state = proto::WatcherStateReply { state: self.watcher_toggle.state().into() };
         Ok(Response::new(state))
@@ -48,8 +52,9 @@ where
 
     async fn get_watcher_state(
         &self,
-        _request: Request<()>,
+        request: Request<()>,
     ) -> Result<Response<proto::WatcherStateReply>, Status> {
+        let _grant = Grant::check(&request, AccessScope::ReadMetadata)?;
         let state = proto::WatcherStateReply { state: self.watcher_toggle.state().into() };
         Ok(Response::new(state))
     }
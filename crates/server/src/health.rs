@@ -0,0 +1,113 @@
+use std::{
+    collections::BTreeSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use clipcat_base::ClipboardKind;
+use clipcat_clipboard::ListenerKind;
+use parking_lot::Mutex;
+
+/// Tracks the connections of clipboard listeners to the display server.
+#[derive(Clone, Debug, Default)]
+pub struct ListenerStates {
+    disconnected: Arc<Mutex<BTreeSet<ClipboardKind>>>,
+}
+
+impl ListenerStates {
+    fn disconnected(&self) -> Vec<ClipboardKind> {
+        self.disconnected.lock().iter().copied().collect()
+    }
+}
+
+impl clipcat_clipboard::EventObserver for ListenerStates {
+    fn on_connected(&self, _: ListenerKind, clipboard_kind: ClipboardKind, _: &str) {
+        let _unused = self.disconnected.lock().remove(&clipboard_kind);
+    }
+
+    fn on_disconnected(&self, _: ListenerKind, clipboard_kind: ClipboardKind, _: &str) {
+        let _unused = self.disconnected.lock().insert(clipboard_kind);
+    }
+
+    fn on_reconnected(&self, _: ListenerKind, clipboard_kind: ClipboardKind, _: &str) {
+        let _unused = self.disconnected.lock().remove(&clipboard_kind);
+    }
+}
+
+/// Readiness of `clipcatd`, it is ready if all clipboard listeners are
+/// connected, the history is loaded and the watcher is watching.
+#[derive(Clone, Debug)]
+pub struct Health {
+    listeners: ListenerStates,
+
+    is_history_loaded: bool,
+
+    is_watching: Arc<AtomicBool>,
+}
+
+impl Health {
+    pub const fn new(
+        listeners: ListenerStates,
+        is_history_loaded: bool,
+        is_watching: Arc<AtomicBool>,
+    ) -> Self {
+        Self { listeners, is_history_loaded, is_watching }
+    }
+
+    pub fn is_ready(&self) -> bool { self.check_readiness().is_empty() }
+
+    /// Returns the reasons why `clipcatd` is not ready.
+    pub fn check_readiness(&self) -> Vec<String> {
+        let mut reasons = self
+            .listeners
+            .disconnected()
+            .into_iter()
+            .map(|kind| format!("Listener of {kind} is re-connecting to display server"))
+            .collect::<Vec<_>>();
+        if !self.is_history_loaded {
+            reasons.push("History could not be loaded".to_string());
+        }
+        if !self.is_watching.load(Ordering::Acquire) {
+            reasons.push("Watcher is not watching for clipboard event".to_string());
+        }
+        reasons
+    }
+}
+
+impl clipcat_metrics::Health for Health {
+    fn check_readiness(&self) -> Vec<String> { Self::check_readiness(self) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use clipcat_base::ClipboardKind;
+    use clipcat_clipboard::{EventObserver, ListenerKind};
+
+    use super::{Health, ListenerStates};
+
+    #[test]
+    fn test_readiness() {
+        let listeners = ListenerStates::default();
+        let is_watching = Arc::new(AtomicBool::new(true));
+        let health = Health::new(listeners.clone(), true, is_watching.clone());
+        assert!(health.is_ready());
+
+        listeners.on_disconnected(ListenerKind::X11, ClipboardKind::Primary, ":0");
+        assert_eq!(health.check_readiness().len(), 1);
+        is_watching.store(false, Ordering::Release);
+        assert_eq!(health.check_readiness().len(), 2);
+
+        listeners.on_reconnected(ListenerKind::X11, ClipboardKind::Primary, ":0");
+        is_watching.store(true, Ordering::Release);
+        assert!(health.is_ready());
+
+        assert!(!Health::new(listeners, false, is_watching).is_ready());
+    }
+}
@@ -144,6 +144,8 @@ impl FileSystemDriver {
     pub fn clips_file_path(&self) -> PathBuf { clips_file_path(&self.file_path) }
 
     pub fn image_dir_path(&self) -> PathBuf { image_dir_path(&self.file_path) }
+
+    pub fn pinned_file_path(&self) -> PathBuf { pinned_file_path(&self.file_path) }
 }
 
 #[async_trait]
@@ -301,6 +303,27 @@ impl Driver for FileSystemDriver {
 
         self.update_header().await
     }
+
+    async fn load_pinned(&mut self) -> Result<HashSet<String>, Error> {
+        let file_path = self.pinned_file_path();
+        let content = match tokio::fs::read(&file_path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(HashSet::new()),
+            Err(source) => return Err(Error::ReadFile { source, file_path }),
+        };
+        let model::v2::PinnedClips { digests } =
+            serde_json::from_slice(&content).context(error::DeseriailizePinnedClipsSnafu)?;
+        Ok(digests.into_iter().collect())
+    }
+
+    async fn save_pinned(&mut self, pinned_clips: &[ClipEntry]) -> Result<(), Error> {
+        let file_path = self.pinned_file_path();
+        let content = serde_json::to_vec_pretty(&model::v2::PinnedClips {
+            digests: pinned_clips.iter().map(|clip| hex::encode(clip.sha256_digest())).collect(),
+        })
+        .context(error::SeriailizePinnedClipsSnafu)?;
+        tokio::fs::write(&file_path, content).await.context(error::WriteFileSnafu { file_path })
+    }
 }
 
 fn header_file_path<P>(file_path: P) -> PathBuf
@@ -317,6 +340,13 @@ where
     [file_path.as_ref(), Path::new("clips")].into_iter().collect()
 }
 
+fn pinned_file_path<P>(file_path: P) -> PathBuf
+where
+    P: AsRef<Path>,
+{
+    [file_path.as_ref(), Path::new("pinned.json")].into_iter().collect()
+}
+
 fn image_dir_path<P>(file_path: P) -> PathBuf
 where
     P: AsRef<Path>,
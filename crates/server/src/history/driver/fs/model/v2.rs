@@ -16,6 +16,12 @@ impl FileHeader {
     pub const SCHEMA_VERSION: u64 = 2;
 }
 
+/// Pinned clips are identified by the SHA-256 digests of their contents.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PinnedClips {
+    pub digests: Vec<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, Serialize)]
 pub struct ClipboardValue {
     pub timestamp: OffsetDateTime,
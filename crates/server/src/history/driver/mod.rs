@@ -1,5 +1,7 @@
 mod fs;
 
+use std::collections::HashSet;
+
 use async_trait::async_trait;
 use clipcat_base::ClipEntry;
 
@@ -18,6 +20,11 @@ pub trait Driver: Send + Sync {
 
     async fn shrink_to(&mut self, min_capacity: usize) -> Result<(), Error>;
 
+    /// Returns the hex-encoded SHA-256 digests of the pinned clips.
+    async fn load_pinned(&mut self) -> Result<HashSet<String>, Error>;
+
+    async fn save_pinned(&mut self, pinned_clips: &[ClipEntry]) -> Result<(), Error>;
+
     async fn save_and_shrink_to(
         &mut self,
         data: &[ClipEntry],
@@ -40,4 +40,10 @@ pub enum Error {
 
     #[snafu(display("Failed to deserialize history header, error: {source}"))]
     DeseriailizeHistoryHeader { source: serde_json::Error },
+
+    #[snafu(display("Failed to serialize pinned clips, error: {source}"))]
+    SeriailizePinnedClips { source: serde_json::Error },
+
+    #[snafu(display("Failed to deserialize pinned clips, error: {source}"))]
+    DeseriailizePinnedClips { source: serde_json::Error },
 }
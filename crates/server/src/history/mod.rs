@@ -1,11 +1,15 @@
 mod driver;
 mod error;
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
 use clipcat_base::ClipEntry;
 
 pub use self::error::Error;
+use crate::metrics;
 
 pub struct HistoryManager {
     file_path: PathBuf,
@@ -29,26 +33,57 @@ impl HistoryManager {
 
     #[inline]
     pub async fn put(&mut self, data: &ClipEntry) -> Result<(), Error> {
-        self.driver.put(data).await
+        let _histogram_timer = metrics::history::WRITE_DURATION_SECONDS.start_timer();
+        self.driver.put(data).await.inspect_err(|_| metrics::history::WRITE_ERRORS_TOTAL.inc())
     }
 
     #[allow(dead_code)]
     #[inline]
-    pub async fn clear(&mut self) -> Result<(), Error> { self.driver.clear().await }
+    pub async fn clear(&mut self) -> Result<(), Error> {
+        let _histogram_timer = metrics::history::WRITE_DURATION_SECONDS.start_timer();
+        self.driver.clear().await.inspect_err(|_| metrics::history::WRITE_ERRORS_TOTAL.inc())
+    }
 
     #[inline]
     pub async fn load(&mut self) -> Result<Vec<ClipEntry>, Error> { self.driver.load().await }
 
+    /// Returns the identifiers of the pinned clips among `clips`.
+    pub async fn load_pinned<'a, I>(&mut self, clips: I) -> Result<HashSet<u64>, Error>
+    where
+        I: IntoIterator<Item = &'a ClipEntry> + Send,
+    {
+        let digests = self.driver.load_pinned().await?;
+        Ok(clips
+            .into_iter()
+            .filter(|clip| digests.contains(&hex::encode(clip.sha256_digest())))
+            .map(ClipEntry::id)
+            .collect())
+    }
+
+    #[inline]
+    pub async fn save_pinned(&mut self, pinned_clips: &[ClipEntry]) -> Result<(), Error> {
+        let _histogram_timer = metrics::history::WRITE_DURATION_SECONDS.start_timer();
+        self.driver
+            .save_pinned(pinned_clips)
+            .await
+            .inspect_err(|_| metrics::history::WRITE_ERRORS_TOTAL.inc())
+    }
+
     #[allow(dead_code)]
     #[inline]
     pub async fn save(&mut self, data: &[ClipEntry]) -> Result<(), Error> {
-        self.driver.save(data).await
+        let _histogram_timer = metrics::history::WRITE_DURATION_SECONDS.start_timer();
+        self.driver.save(data).await.inspect_err(|_| metrics::history::WRITE_ERRORS_TOTAL.inc())
     }
 
     #[allow(dead_code)]
     #[inline]
     pub async fn shrink_to(&mut self, min_capacity: usize) -> Result<(), Error> {
-        self.driver.shrink_to(min_capacity).await
+        let _histogram_timer = metrics::history::WRITE_DURATION_SECONDS.start_timer();
+        self.driver
+            .shrink_to(min_capacity)
+            .await
+            .inspect_err(|_| metrics::history::WRITE_ERRORS_TOTAL.inc())
     }
 
     #[inline]
@@ -57,6 +92,10 @@ impl HistoryManager {
         data: &[ClipEntry],
         min_capacity: usize,
     ) -> Result<(), Error> {
-        self.driver.save_and_shrink_to(data, min_capacity).await
+        let _histogram_timer = metrics::history::WRITE_DURATION_SECONDS.start_timer();
+        self.driver
+            .save_and_shrink_to(data, min_capacity)
+            .await
+            .inspect_err(|_| metrics::history::WRITE_ERRORS_TOTAL.inc())
     }
 }
@@ -0,0 +1,497 @@
+use std::{cmp::Reverse, convert::Infallible, str::FromStr, sync::Arc};
+
+use axum::{
+    body::Bytes,
+    extract::{Extension, Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{self, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing, Json, Router,
+};
+use clipcat_base::{
+    AccessScope, AuditOperation, AuditTransport, ClipEntry, ClipboardContent, ClipboardKind,
+    ClipboardWatcherState,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    audit,
+    events::Event,
+    grpc::Grant,
+    http::{auth, State as HttpState},
+    notification,
+};
+
+const DEFAULT_PREVIEW_LENGTH: usize = 100;
+
+pub fn router<Notification>() -> Router<HttpState<Notification>>
+where
+    Notification: notification::Notification + 'static,
+{
+    Router::new()
+        .route(
+            "/clips",
+            routing::get(list_clips::<Notification>)
+                .post(insert_clip::<Notification>)
+                .delete(clear_clips::<Notification>),
+        )
+        .route("/clips/current", routing::get(get_current_clip::<Notification>))
+        .route(
+            "/clips/{id}",
+            routing::get(get_clip::<Notification>)
+                .put(update_clip::<Notification>)
+                .delete(remove_clip::<Notification>),
+        )
+        .route("/clips/{id}/mark", routing::post(mark_clip::<Notification>))
+        .route(
+            "/clips/{id}/pin",
+            routing::put(pin_clip::<Notification>).delete(unpin_clip::<Notification>),
+        )
+        .route(
+            "/watcher",
+            routing::get(get_watcher_state::<Notification>).put(set_watcher_state::<Notification>),
+        )
+        .route("/watcher/toggle", routing::post(toggle_watcher::<Notification>))
+        .route("/length", routing::get(length::<Notification>))
+        .route("/version", routing::get(version))
+        .route("/events", routing::get(events::<Notification>))
+}
+
+/// A clip in the list of the HTTP API.
+///
+/// The identifiers are strings, numbers in JavaScript can not represent all
+/// `u64` values.
+#[derive(Debug, Serialize)]
+struct Clip {
+    id: String,
+
+    kind: String,
+
+    mime: String,
+
+    timestamp: i64,
+
+    preview: String,
+
+    pinned: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListQuery {
+    search: Option<String>,
+
+    preview_length: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KindQuery {
+    kind: Option<String>,
+}
+
+impl KindQuery {
+    // the clipboard is used if no kind is given
+    fn kind(&self) -> Result<ClipboardKind, StatusCode> {
+        self.kind.as_deref().map_or(Ok(ClipboardKind::Clipboard), |kind| {
+            ClipboardKind::from_str(kind).map_err(|_| StatusCode::BAD_REQUEST)
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ClipId {
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Length {
+    length: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Version {
+    major: u64,
+
+    minor: u64,
+
+    patch: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct WatcherState {
+    state: ClipboardWatcherState,
+}
+
+fn audit_peer(grant: &Grant) -> audit::Peer {
+    audit::Peer::new(AuditTransport::HttpApi).with_token_name(grant.token_name())
+}
+
+fn matches_search(clip: &ClipEntry, search: &str) -> bool {
+    match clip.as_ref() {
+        ClipboardContent::Plaintext(text) => text.to_lowercase().contains(search),
+        ClipboardContent::Image { .. } => clip.mime().essence_str().contains(search),
+    }
+}
+
+// newest clip comes first
+async fn list_clips<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Query(ListQuery { search, preview_length }): Query<ListQuery>,
+) -> Result<Json<Vec<Clip>>, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::ReadMetadata)?;
+    // searching reveals the contents of clips
+    if search.is_some() {
+        auth::check(&grant, AccessScope::ReadContent)?;
+    }
+    let search = search.map(|search| search.to_lowercase()).unwrap_or_default();
+    let preview_length = grant
+        .preview_length(preview_length.unwrap_or(DEFAULT_PREVIEW_LENGTH), DEFAULT_PREVIEW_LENGTH);
+    let mut clips = {
+        let manager = state.manager.lock().await;
+        manager
+            .iter()
+            .filter(|clip| matches_search(clip, &search))
+            .map(|clip| {
+                let metadata = clip.metadata(Some(preview_length));
+                Clip {
+                    id: metadata.id.to_string(),
+                    kind: metadata.kind.as_str().to_lowercase(),
+                    mime: metadata.mime.essence_str().to_string(),
+                    timestamp: metadata.timestamp.unix_timestamp(),
+                    preview: metadata.preview,
+                    pinned: manager.is_pinned(metadata.id),
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    clips.sort_unstable_by_key(|clip| Reverse(clip.timestamp));
+    // previews are not limited, they contain the whole contents
+    if preview_length == 0 {
+        let clip_ids = clips.iter().filter_map(|clip| clip.id.parse().ok()).collect();
+        state.audit_log.record(&audit_peer(&grant), AuditOperation::Export, clip_ids).await;
+    }
+    Ok(Json(clips))
+}
+
+// `curl --data` sends `application/x-www-form-urlencoded` if no content type is
+// given, it is treated as plaintext like a request without content type
+fn content_mime(headers: &HeaderMap) -> Result<mime::Mime, StatusCode> {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE) else {
+        return Ok(mime::TEXT_PLAIN_UTF_8);
+    };
+    let mime = content_type
+        .to_str()
+        .ok()
+        .and_then(|content_type| mime::Mime::from_str(content_type).ok())
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    if mime == mime::APPLICATION_WWW_FORM_URLENCODED {
+        Ok(mime::TEXT_PLAIN_UTF_8)
+    } else {
+        Ok(mime)
+    }
+}
+
+// marks the inserted clip like the `Insert` method of gRPC
+async fn insert_clip<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Query(query): Query<KindQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<ClipId>), StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::Insert)?;
+    let kind = query.kind()?;
+    let mime = content_mime(&headers)?;
+    let clip =
+        ClipEntry::new(&body, &mime, kind, None).map_err(|_| StatusCode::UNSUPPORTED_MEDIA_TYPE)?;
+
+    let mut manager = state.manager.lock().await;
+    let id = manager.insert(clip);
+    if let Err(err) = manager.mark(id, kind).await {
+        tracing::warn!("{err}");
+    }
+    drop(manager);
+    Ok((StatusCode::CREATED, Json(ClipId { id: id.to_string() })))
+}
+
+async fn update_clip<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Path(id): Path<u64>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<ClipId>, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    // the original clip is removed
+    auth::check(&grant, AccessScope::Insert)?;
+    auth::check(&grant, AccessScope::Delete)?;
+    let mime = content_mime(&headers)?;
+    let mut manager = state.manager.lock().await;
+    if manager.get(id).is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let (ok, new_id) = manager.replace(id, &body, &mime);
+    drop(manager);
+    if ok {
+        tracing::info!(
+            "Clip {id} is replaced by clip {new_id} with access token `{}`",
+            grant.token_name()
+        );
+        state.audit_log.record(&audit_peer(&grant), AuditOperation::Remove, vec![id]).await;
+        Ok(Json(ClipId { id: new_id.to_string() }))
+    } else {
+        Err(StatusCode::UNSUPPORTED_MEDIA_TYPE)
+    }
+}
+
+async fn clear_clips<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+) -> Result<StatusCode, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::Delete)?;
+    state.manager.lock().await.clear();
+    state.audit_log.record(&audit_peer(&grant), AuditOperation::Clear, Vec::new()).await;
+    tracing::info!("History is cleared with access token `{}`", grant.token_name());
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// responds with the contents of the clip, images are encoded as PNG
+fn clip_contents(clip: &ClipEntry) -> Result<Response, StatusCode> {
+    let data = clip.encoded().map_err(|err| {
+        tracing::warn!("{err}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let content_type = HeaderValue::from_str(clip.mime().as_ref())
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(([(header::CONTENT_TYPE, content_type)], data).into_response())
+}
+
+async fn get_clip<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Path(id): Path<u64>,
+) -> Result<Response, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::ReadContent)?;
+    let clip = state.manager.lock().await.get(id);
+    state.audit_log.record(&audit_peer(&grant), AuditOperation::Get, vec![id]).await;
+    clip_contents(&clip.ok_or(StatusCode::NOT_FOUND)?)
+}
+
+async fn get_current_clip<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Query(query): Query<KindQuery>,
+) -> Result<Response, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::ReadContent)?;
+    let kind = query.kind()?;
+    let clip = state.manager.lock().await.get_current_clip(kind).cloned();
+    let clip_ids = clip.iter().map(ClipEntry::id).collect();
+    state.audit_log.record(&audit_peer(&grant), AuditOperation::GetCurrentClip, clip_ids).await;
+    clip_contents(&clip.ok_or(StatusCode::NOT_FOUND)?)
+}
+
+async fn remove_clip<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Path(id): Path<u64>,
+) -> Result<StatusCode, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::Delete)?;
+    let removed = state.manager.lock().await.remove(id);
+    state.audit_log.record(&audit_peer(&grant), AuditOperation::Remove, vec![id]).await;
+    if removed {
+        tracing::info!("Clip {id} is removed with access token `{}`", grant.token_name());
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+async fn mark_clip<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Path(id): Path<u64>,
+    Query(query): Query<KindQuery>,
+) -> Result<StatusCode, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::Mark)?;
+    let kind = query.kind()?;
+    state.audit_log.record(&audit_peer(&grant), AuditOperation::Mark, vec![id]).await;
+
+    let mut manager = state.manager.lock().await;
+    if manager.get(id).is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    match manager.mark(id, kind).await {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(err) => {
+            tracing::warn!("{err}");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn pin_clip<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Path(id): Path<u64>,
+) -> Result<StatusCode, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::Mark)?;
+    state.audit_log.record(&audit_peer(&grant), AuditOperation::Mark, vec![id]).await;
+    if state.manager.lock().await.pin(id) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+async fn unpin_clip<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Path(id): Path<u64>,
+) -> Result<StatusCode, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::Mark)?;
+    state.audit_log.record(&audit_peer(&grant), AuditOperation::Mark, vec![id]).await;
+    if state.manager.lock().await.unpin(id) {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+async fn get_watcher_state<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+) -> Result<Json<WatcherState>, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::ReadMetadata)?;
+    Ok(Json(WatcherState { state: state.watcher_toggle.state() }))
+}
+
+async fn set_watcher_state<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+    Json(WatcherState { state: watcher_state }): Json<WatcherState>,
+) -> Result<Json<WatcherState>, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::WatcherControl)?;
+    match watcher_state {
+        ClipboardWatcherState::Enabled => state.watcher_toggle.enable(),
+        ClipboardWatcherState::Disabled => state.watcher_toggle.disable(),
+    }
+    Ok(Json(WatcherState { state: state.watcher_toggle.state() }))
+}
+
+async fn toggle_watcher<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+) -> Result<Json<WatcherState>, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::WatcherControl)?;
+    state.watcher_toggle.toggle();
+    Ok(Json(WatcherState { state: state.watcher_toggle.state() }))
+}
+
+async fn length<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+) -> Result<Json<Length>, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::ReadMetadata)?;
+    Ok(Json(Length { length: state.manager.lock().await.len() }))
+}
+
+async fn version() -> Json<Version> {
+    let semver::Version { major, minor, patch, .. } = *clipcat_base::PROJECT_SEMVER;
+    Json(Version { major, minor, patch })
+}
+
+// converts events to server-sent events, the web UI reloads the history when
+// the history is changed
+fn to_sse_event(event: &Event) -> sse::Event {
+    let (name, data) = match event {
+        Event::ClipAdded { metadata } => {
+            ("clip_added", serde_json::json!({ "id": metadata.id.to_string() }))
+        }
+        Event::ClipRemoved { id } => ("clip_removed", serde_json::json!({ "id": id.to_string() })),
+        Event::ClipMarked { id, kind } => (
+            "clip_marked",
+            serde_json::json!({ "id": id.to_string(), "kind": kind.as_str().to_lowercase() }),
+        ),
+        Event::ClipPinned { id, pinned } => {
+            ("clip_pinned", serde_json::json!({ "id": id.to_string(), "pinned": pinned }))
+        }
+        Event::HistoryCleared => ("history_cleared", serde_json::json!({})),
+        Event::WatcherStateChanged { state } => {
+            ("watcher_state_changed", serde_json::json!({ "state": state }))
+        }
+    };
+    sse::Event::default().event(name).data(data.to_string())
+}
+
+async fn events<Notification>(
+    State(state): State<HttpState<Notification>>,
+    Extension(grant): Extension<Arc<Grant>>,
+) -> Result<Sse<impl Stream<Item = Result<sse::Event, Infallible>>>, StatusCode>
+where
+    Notification: notification::Notification,
+{
+    auth::check(&grant, AccessScope::ReadMetadata)?;
+    let receiver = state.event_bus.subscribe();
+    let mut shutdown = state.shutdown;
+    let stream = futures::stream::unfold(receiver, |mut receiver| async move {
+        match receiver.recv().await {
+            Ok(event) => Some((to_sse_event(&event), receiver)),
+            // some events are missed, ask the web UI to reload everything
+            Err(RecvError::Lagged(_)) => {
+                Some((sse::Event::default().event("lagged").data("{}"), receiver))
+            }
+            Err(RecvError::Closed) => None,
+        }
+    })
+    .take_until(async move {
+        let _unused = shutdown.wait_for(|shutdown| *shutdown).await;
+    })
+    .map(Ok);
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use clipcat_base::AccessScope;
+
+use crate::{grpc, metrics};
+
+/// Checks the access token of gRPC with the same [`grpc::Interceptor`], the
+/// [`grpc::Grant`] of the token is passed to handlers as an extension.
+///
+/// The token is only accepted in the `Authorization` header, tokens in query
+/// strings would be leaked into logs of proxies and the browser history.
+pub async fn authorize(
+    State(interceptor): State<grpc::Interceptor>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    metrics::http::REQUESTS_TOTAL.inc();
+
+    let authorization = request.headers().get(header::AUTHORIZATION).map(HeaderValue::as_bytes);
+    if let Some(grant) = interceptor.authorize(authorization) {
+        let _unused = request.extensions_mut().insert(grant);
+        next.run(request).await
+    } else {
+        (StatusCode::UNAUTHORIZED, "No valid authorization token").into_response()
+    }
+}
+
+/// Responds with `403 Forbidden` if `grant` does not allow `scope`.
+pub fn check(grant: &Arc<grpc::Grant>, scope: AccessScope) -> Result<(), StatusCode> {
+    if grant.allows(scope) {
+        Ok(())
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
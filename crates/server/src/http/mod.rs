@@ -0,0 +1,441 @@
+mod api;
+mod auth;
+
+use std::{
+    future::Future,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use axum::{middleware, response::Html, routing, Router};
+use snafu::ResultExt;
+use tokio::{
+    net::TcpListener,
+    sync::{watch, Mutex},
+};
+
+use crate::{
+    audit::AuditLog,
+    error::{self, Error},
+    events::EventBus,
+    grpc, local_socket, notification, ClipboardManager, ClipboardWatcherToggle,
+};
+
+static INDEX_HTML: &str = include_str!("index.html");
+
+/// Shared by the handlers of the HTTP API.
+struct State<Notification> {
+    manager: Arc<Mutex<ClipboardManager<Notification>>>,
+
+    watcher_toggle: Arc<ClipboardWatcherToggle<Notification>>,
+
+    event_bus: EventBus,
+
+    audit_log: AuditLog,
+
+    // event streams are closed when the server is shutting down, otherwise
+    // the graceful shutdown waits for them forever
+    shutdown: watch::Receiver<bool>,
+}
+
+impl<Notification> Clone for State<Notification> {
+    fn clone(&self) -> Self {
+        Self {
+            manager: self.manager.clone(),
+            watcher_toggle: self.watcher_toggle.clone(),
+            event_bus: self.event_bus.clone(),
+            audit_log: self.audit_log.clone(),
+            shutdown: self.shutdown.clone(),
+        }
+    }
+}
+
+async fn index() -> Html<&'static str> { Html(INDEX_HTML) }
+
+/// Serves the JSON HTTP API at `/api`, and the web UI at `/` if it is enabled.
+pub struct Server<Notification> {
+    state: State<Notification>,
+
+    interceptor: grpc::Interceptor,
+
+    shutdown_sender: watch::Sender<bool>,
+
+    web_ui: bool,
+}
+
+impl<Notification> Server<Notification>
+where
+    Notification: notification::Notification + 'static,
+{
+    pub fn new(
+        interceptor: grpc::Interceptor,
+        clipboard_watcher_toggle: ClipboardWatcherToggle<Notification>,
+        clipboard_manager: Arc<Mutex<ClipboardManager<Notification>>>,
+        event_bus: EventBus,
+    ) -> Self {
+        let (shutdown_sender, shutdown) = watch::channel(false);
+        let state = State {
+            manager: clipboard_manager,
+            watcher_toggle: Arc::new(clipboard_watcher_toggle),
+            event_bus,
+            audit_log: AuditLog::default(),
+            shutdown,
+        };
+        Self { state, interceptor, shutdown_sender, web_ui: false }
+    }
+
+    #[must_use]
+    pub const fn with_web_ui(mut self) -> Self {
+        self.web_ui = true;
+        self
+    }
+
+    /// Records accesses to the history through the API in `audit_log`.
+    #[must_use]
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.state.audit_log = audit_log;
+        self
+    }
+
+    fn router(&self) -> Router {
+        let api = api::router::<Notification>()
+            .route_layer(middleware::from_fn_with_state(self.interceptor.clone(), auth::authorize))
+            .with_state(self.state.clone());
+
+        let router = Router::new().nest("/api", api);
+        if self.web_ui {
+            router.route("/", routing::get(index))
+        } else {
+            router
+        }
+    }
+
+    /// Serves on `listen_address` until `shutdown_signal` resolves.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the server could not bind
+    /// `listen_address`.
+    pub async fn serve_with_shutdown<ShutdownSignal>(
+        self,
+        listen_address: SocketAddr,
+        shutdown_signal: ShutdownSignal,
+    ) -> Result<(), Error>
+    where
+        ShutdownSignal: Future<Output = ()> + Send + 'static,
+    {
+        let listener = TcpListener::bind(&listen_address)
+            .await
+            .context(error::BindHttpServerSnafu { listen_address })?;
+        let router = self.router();
+        let shutdown_sender = self.shutdown_sender;
+        axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                shutdown_signal.await;
+                let _unused = shutdown_sender.send(true);
+            })
+            .await
+            .context(error::ServeHttpServerSnafu)
+    }
+
+    /// Serves on the Unix domain socket `local_socket` until `shutdown_signal`
+    /// resolves, connections are checked by `peer_filter`. The socket is
+    /// removed after the server is shut down.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the server could not bind
+    /// `local_socket`.
+    pub async fn serve_with_local_socket_and_shutdown<ShutdownSignal>(
+        self,
+        local_socket: &Path,
+        peer_filter: local_socket::PeerFilter,
+        shutdown_signal: ShutdownSignal,
+    ) -> Result<(), Error>
+    where
+        ShutdownSignal: Future<Output = ()> + Send + 'static,
+    {
+        let listener = peer_filter.listener(local_socket::bind(local_socket).await?);
+        let router = self.router();
+        let shutdown_sender = self.shutdown_sender;
+        let result = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                shutdown_signal.await;
+                let _unused = shutdown_sender.send(true);
+            })
+            .await
+            .context(error::ServeHttpServerSnafu);
+
+        remove_local_socket(local_socket.to_path_buf()).await;
+        result
+    }
+}
+
+async fn remove_local_socket(socket_path: PathBuf) {
+    tracing::info!("Remove Unix domain socket `{path}`", path = socket_path.display());
+    drop(tokio::fs::remove_file(socket_path).await);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        sync::{atomic::AtomicBool, Arc},
+    };
+
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, Request, StatusCode},
+        Router,
+    };
+    use clipcat_base::{AccessScope, ClipEntry, ClipboardKind};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::UnixStream,
+        sync::{oneshot, Mutex},
+    };
+    use tower::ServiceExt;
+
+    use super::Server;
+    use crate::{
+        backend::LocalClipboardBackend,
+        config::{LocalSocketAccessConfig, ScopedAccessToken},
+        events::EventBus,
+        grpc,
+        local_socket::PeerFilter,
+        manager::ClipboardManager,
+        notification::DummyNotification,
+        ClipboardWatcherToggle,
+    };
+
+    fn create_server(clips: &[&str]) -> (Server<DummyNotification>, Vec<u64>) {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let mut manager = ClipboardManager::new(backend, DummyNotification::default());
+        let ids = clips
+            .iter()
+            .map(|clip| manager.insert(ClipEntry::from_string(clip, ClipboardKind::Primary)))
+            .collect();
+        let event_bus = EventBus::new();
+        let toggle = ClipboardWatcherToggle::new(
+            Arc::new(AtomicBool::new(true)),
+            event_bus.clone(),
+            DummyNotification::default(),
+        );
+        let interceptor =
+            grpc::Interceptor::new(Some("token")).with_scoped_access_tokens([ScopedAccessToken {
+                name: "status-bar".to_string(),
+                token: "preview".to_string(),
+                scopes: HashSet::from([AccessScope::ReadMetadata]),
+            }]);
+        let server = Server::new(interceptor, toggle, Arc::new(Mutex::new(manager)), event_bus);
+        (server, ids)
+    }
+
+    async fn request(
+        router: &Router,
+        method: &str,
+        uri: &str,
+        body: Option<(&str, &str)>,
+    ) -> (StatusCode, String) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, "Bearer token");
+        let request = match body {
+            Some((content_type, body)) => request
+                .header(header::CONTENT_TYPE, content_type)
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        };
+        let response = router.clone().oneshot(request.unwrap()).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_web_ui() {
+        let (server, ids) = create_server(&["clipcat"]);
+        let id = ids[0];
+        let router = server.with_web_ui().router();
+
+        let response =
+            router.clone().oneshot(Request::get("/").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = router
+            .clone()
+            .oneshot(Request::get("/api/clips").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        // tokens in query strings are not accepted
+        let response = router
+            .clone()
+            .oneshot(Request::get("/api/clips?access_token=token").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let (status, body) = request(&router, "GET", "/api/clips?search=CLIP", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(&format!(r#""id":"{id}""#)));
+        assert!(body.contains(r#""pinned":false"#));
+        let (_, body) = request(&router, "GET", "/api/clips?search=menu", None).await;
+        assert_eq!(body, "[]");
+
+        let (status, _) = request(&router, "PUT", &format!("/api/clips/{id}/pin"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, body) = request(&router, "GET", "/api/clips", None).await;
+        assert!(body.contains(r#""pinned":true"#));
+
+        let state = Some(("application/json", r#"{"state":"Disabled"}"#));
+        let (status, body) = request(&router, "PUT", "/api/watcher", state).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, r#"{"state":"Disabled"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_api() {
+        let (server, ids) = create_server(&["clipcat"]);
+        let router = server.router();
+
+        let response =
+            router.clone().oneshot(Request::get("/").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let (status, body) = request(&router, "GET", &format!("/api/clips/{}", ids[0]), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "clipcat");
+
+        let clip = Some(("text/plain", "clipcatd"));
+        let (status, body) = request(&router, "POST", "/api/clips?kind=primary", clip).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = body.trim_start_matches(r#"{"id":""#).trim_end_matches(r#""}"#).to_string();
+        let (status, body) = request(&router, "GET", "/api/clips/current?kind=primary", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "clipcatd");
+        let clip = Some(("application/x-unknown", "clipcatd"));
+        let (status, _) = request(&router, "POST", "/api/clips", clip).await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let clip = Some(("text/plain", "clipcat-menu"));
+        let (status, body) = request(&router, "PUT", &format!("/api/clips/{id}"), clip).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!body.contains(&id));
+        let (_, body) = request(&router, "GET", "/api/length", None).await;
+        assert_eq!(body, r#"{"length":2}"#);
+
+        let uri = format!("/api/clips/{}/mark?kind=clipboard", ids[0]);
+        let (status, _) = request(&router, "POST", &uri, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let uri = format!("/api/clips/{}/mark?kind=unknown", ids[0]);
+        let (status, _) = request(&router, "POST", &uri, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = request(&router, "DELETE", &format!("/api/clips/{}", ids[0]), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = request(&router, "DELETE", &format!("/api/clips/{}", ids[0]), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = request(&router, "DELETE", "/api/clips", None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, body) = request(&router, "GET", "/api/length", None).await;
+        assert_eq!(body, r#"{"length":0}"#);
+
+        let (_, body) = request(&router, "POST", "/api/watcher/toggle", None).await;
+        assert_eq!(body, r#"{"state":"Disabled"}"#);
+        let (_, body) = request(&router, "GET", "/api/watcher", None).await;
+        assert_eq!(body, r#"{"state":"Disabled"}"#);
+
+        let (status, body) = request(&router, "GET", "/api/version", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(r#""major":"#));
+    }
+
+    #[tokio::test]
+    async fn test_scoped_access_token() {
+        let secret = "secret ".repeat(50);
+        let (server, ids) = create_server(&[&secret]);
+        let router = server.router();
+        let send = |method: &str, uri: &str| {
+            router.clone().oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header(header::AUTHORIZATION, "Bearer preview")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        for uri in ["/api/clips", "/api/length", "/api/watcher", "/api/version"] {
+            assert_eq!(send("GET", uri).await.unwrap().status(), StatusCode::OK);
+        }
+        // the whole contents can not be read through previews
+        for uri in ["/api/clips?preview_length=0", "/api/clips?preview_length=1000"] {
+            let response = send("GET", uri).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let body = String::from_utf8(body.to_vec()).unwrap();
+            assert!(body.contains("secret"));
+            assert!(!body.contains(secret.trim_end()));
+        }
+        let clip = format!("/api/clips/{}", ids[0]);
+        for (method, uri) in [
+            ("GET", "/api/clips?search=secret"),
+            ("GET", clip.as_str()),
+            ("GET", "/api/clips/current"),
+            ("DELETE", clip.as_str()),
+            ("DELETE", "/api/clips"),
+            ("POST", &format!("{clip}/mark")),
+            ("PUT", &format!("{clip}/pin")),
+            ("POST", "/api/watcher/toggle"),
+        ] {
+            assert_eq!(send(method, uri).await.unwrap().status(), StatusCode::FORBIDDEN);
+        }
+        // nothing is changed by the forbidden requests
+        let (_, body) = request(&router, "GET", "/api/length", None).await;
+        assert_eq!(body, r#"{"length":1}"#);
+    }
+
+    #[tokio::test]
+    async fn test_local_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("http.sock");
+        let (server, _ids) = create_server(&["clipcat"]);
+        let peer_filter = PeerFilter::new(LocalSocketAccessConfig::default());
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let serve = tokio::spawn({
+            let socket_path = socket_path.clone();
+            async move {
+                server
+                    .serve_with_local_socket_and_shutdown(&socket_path, peer_filter, async move {
+                        let _unused = shutdown_receiver.await;
+                    })
+                    .await
+            }
+        });
+
+        let mut stream = loop {
+            if let Ok(stream) = UnixStream::connect(&socket_path).await {
+                break stream;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        stream
+            .write_all(
+                b"GET /api/length HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer \
+                  token\r\nConnection: close\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"length":1}"#));
+
+        shutdown_sender.send(()).unwrap();
+        serve.await.unwrap().unwrap();
+        assert!(!socket_path.exists());
+    }
+}
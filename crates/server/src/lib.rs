@@ -1,3 +1,4 @@
+mod audit;
 pub mod backend;
 pub mod config;
 #[cfg(all(
@@ -11,80 +12,145 @@ pub mod config;
 ))]
 mod dbus;
 mod error;
+mod events;
 mod grpc;
+mod health;
 mod history;
+mod http;
+mod local_socket;
 mod manager;
 mod metrics;
 mod notification;
+mod reload;
 mod snippets;
+mod synchronizer;
+mod systemd;
 mod watcher;
 
-use std::{future::Future, net::SocketAddr, path::PathBuf, pin::Pin, sync::Arc};
+use std::{
+    collections::HashSet, future::Future, net::SocketAddr, path::PathBuf, pin::Pin, sync::Arc,
+};
 
-use clipcat_base::ClipboardKind;
-use clipcat_proto::{ManagerServer, SystemServer, WatcherServer};
+use clipcat_base::ClipEntry;
+use clipcat_proto::{AuditServer, HealthServer, ManagerServer, SystemServer, WatcherServer};
 use futures::FutureExt;
 use notification::Notification;
 use sigfinn::{ExitStatus, Handle, LifecycleManager, Shutdown};
 use snafu::ResultExt;
 use snippets::SnippetWatcherEvent;
 use tokio::{
-    net::UnixListener,
-    sync::{broadcast::error::RecvError, Mutex},
+    net::{TcpListener, UnixListener},
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc, watch, Mutex,
+    },
 };
-use tokio_stream::wrappers::UnixListenerStream;
+use tonic::transport::server::TcpIncoming;
 
-pub use self::{
-    config::Config,
-    error::{Error, Result},
-    watcher::ClipboardWatcherOptions,
-};
 use self::{
+    audit::AuditLog,
+    events::{Event, EventBus},
+    health::{Health, ListenerStates},
     history::HistoryManager,
     manager::ClipboardManager,
     metrics::Metrics,
-    watcher::{ClipboardWatcher, ClipboardWatcherToggle, ClipboardWatcherWorker},
+    reload::{ReloadHandle, Reloader},
+    synchronizer::Synchronizer,
+    watcher::{
+        ClipboardWatcher, ClipboardWatcherCurrentContents, ClipboardWatcherToggle,
+        ClipboardWatcherWorker, SharedClipFilter,
+    },
+};
+pub use self::{
+    config::Config,
+    error::{Error, Result},
+    reload::ConfigLoader,
+    systemd::take_environment as take_systemd_environment,
+    watcher::ClipboardWatcherOptions,
 };
 use crate::snippets::SnippetWatcherEventReceiver;
 
+/// The configuration is reloaded with `config_loader` on `SIGHUP` or on
+/// request, reloading is not supported if it is `None`.
+///
 /// # Errors
 ///
 /// This function will return an error if the server fails to start.
 #[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
 pub async fn serve_with_shutdown(
-    Config {
+    config: Config,
+    config_loader: Option<ConfigLoader>,
+) -> Result<()> {
+    let running_config = config.clone();
+    let Config {
         grpc_listen_address,
         grpc_local_socket,
+        grpc_local_socket_access,
         grpc_access_token,
-        primary_threshold,
+        grpc_scoped_access_tokens,
+        grpc_tls,
         max_history,
+        backend: backend_config,
+        tmux: tmux_config,
         history_file_path,
-        synchronize_selection_with_clipboard,
+        synchronization,
         watcher: watcher_opts,
         desktop_notification: desktop_notification_config,
+        notifications: notification_sinks_config,
         dbus,
         metrics: metrics_config,
+        web_ui: web_ui_config,
+        http_api: http_api_config,
+        audit_log: audit_log_config,
         snippets,
-    }: Config,
-) -> Result<()> {
+    } = config;
+    let interceptor = grpc::Interceptor::new(grpc_access_token)
+        .with_scoped_access_tokens(grpc_scoped_access_tokens);
+    let grpc_tls_config = grpc_tls.as_ref().map(grpc::load_tls_config).transpose()?;
+
+    let audit_log = if let Some(audit_log_config) = audit_log_config {
+        tracing::info!(
+            "Audit log file path: `{path}`",
+            path = audit_log_config.file_path.display()
+        );
+        AuditLog::open(audit_log_config).await?
+    } else {
+        AuditLog::default()
+    };
+
     let clip_filter =
         Arc::new(watcher_opts.generate_clip_filter().context(error::GenerateClipFilterSnafu)?);
 
-    let (desktop_notification, desktop_notification_worker) =
-        notification::DesktopNotification::new(
-            desktop_notification_config.icon,
-            desktop_notification_config.timeout,
-            desktop_notification_config.long_plaintext_length,
-        );
+    let is_desktop_notification_enabled = desktop_notification_config.enable;
+    let (desktop_notification, mut desktop_notification_worker) =
+        notification::DesktopNotification::new(desktop_notification_config);
+    let (notification_sinks, notification_sinks_worker) =
+        notification::NotificationSinks::open(notification_sinks_config).await;
+    let notifier = notification::Notifier::new(desktop_notification, notification_sinks);
 
+    let listener_states = ListenerStates::default();
     let clipboard_backend = backend::new_shared(
+        &backend_config,
+        &tmux_config,
         watcher_opts.clipboard_kinds(),
         &clip_filter,
-        &[Arc::new(desktop_notification.clone())],
+        &[
+            Arc::new(notifier.clone()),
+            Arc::new(metrics::ListenerObserver),
+            Arc::new(listener_states.clone()),
+        ],
     )
     .context(error::CreateClipboardBackendSnafu)?;
 
-    let (clipboard_manager, history_manager, snippets_watcher, snippet_event_receiver) = {
+    let event_bus = EventBus::new();
+
+    let (
+        clipboard_manager,
+        history_manager,
+        is_history_loaded,
+        snippets_watcher,
+        snippet_event_receiver,
+    ) = {
         let ((snippets_watcher, snippet_event_receiver), snippets) =
             snippets::load_and_create_watcher(&snippets).await?;
         tracing::info!("History file path: `{path}`", path = history_file_path.display());
@@ -93,17 +159,22 @@ pub async fn serve_with_shutdown(
             .context(error::CreateHistoryManagerSnafu)?;
 
         tracing::info!("Load history from `{path}`", path = history_manager.path().display());
-        let history_clips = history_manager
-            .load()
+        let history_clips = history_manager.load().await.map_err(|err| {
+            tracing::error!(
+                "Could not load history, data might be corrupted, please remove `{path}`, error: \
+                 {err}",
+                path = history_manager.path().display()
+            );
+        });
+        let is_history_loaded = history_clips.is_ok();
+        let history_clips = history_clips.unwrap_or_default();
+        let pinned_ids = history_manager
+            .load_pinned(history_clips.iter().chain(&snippets))
             .await
-            .map_err(|err| {
-                tracing::error!(
-                    "Could not load history, data might be corrupted, please remove `{path}`, \
-                     error: {err}",
-                    path = history_manager.path().display()
-                );
-            })
-            .unwrap_or_default();
+            .unwrap_or_else(|err| {
+                tracing::warn!("Could not load pinned clips, error: {err}");
+                HashSet::new()
+            });
         let clip_count = history_clips.len();
         if clip_count > 0 {
             tracing::info!("{clip_count} clip(s) loaded");
@@ -117,40 +188,88 @@ pub async fn serve_with_shutdown(
         let mut clipboard_manager = ClipboardManager::with_capacity(
             clipboard_backend.clone(),
             max_history,
-            primary_threshold,
-            desktop_notification.clone(),
+            event_bus.clone(),
+            notifier.clone(),
         );
 
         tracing::info!("Import {clip_count} clip(s) into ClipboardManager");
-        clipboard_manager.import(&history_clips);
+        clipboard_manager.import(&history_clips, &pinned_ids);
 
         tracing::info!("Import {snippet_count} snippet(s) into ClipboardManager");
         clipboard_manager.insert_snippets(&snippets);
+        for snippet in &snippets {
+            if pinned_ids.contains(&snippet.id()) {
+                let _ = clipboard_manager.pin(snippet.id());
+            }
+        }
 
         (
             Arc::new(Mutex::new(clipboard_manager)),
             history_manager,
+            is_history_loaded,
             snippets_watcher,
             snippet_event_receiver,
         )
     };
 
+    let clip_filter = SharedClipFilter::new(clip_filter);
     let (clipboard_watcher, clipboard_watcher_worker) = ClipboardWatcher::new(
         clipboard_backend,
         watcher_opts.clone(),
+        clip_filter.clone(),
+        event_bus.clone(),
+        notifier.clone(),
+    );
+    let health = Health::new(listener_states, is_history_loaded, clipboard_watcher.watching_flag());
+
+    let (reload_handle, reload_request_receiver) = ReloadHandle::new();
+    let watch_config_file_path =
+        config_loader.as_ref().and_then(ConfigLoader::watch_file_path).map(ToOwned::to_owned);
+    let is_reloadable = config_loader.is_some();
+    let reloader = Reloader::new(
+        config_loader,
+        running_config,
         clip_filter,
-        desktop_notification.clone(),
+        notifier.clone(),
+        snippets_watcher,
     );
 
+    let (synchronizer, synchronizer_sender) = Synchronizer::new(
+        &synchronization,
+        clipboard_manager.clone(),
+        clipboard_watcher.current_contents(),
+    )
+    .context(error::GenerateSynchronizationFilterSnafu)?;
+
     let lifecycle_manager = LifecycleManager::<Error>::new();
 
-    if desktop_notification_config.enable {
+    // `SIGHUP` terminates the daemon if the configuration is not reloadable
+    if is_reloadable {
+        let _handle = lifecycle_manager.spawn(
+            "Configuration reload trigger",
+            create_config_reload_trigger_future(reload_handle.clone(), watch_config_file_path),
+        );
+    }
+
+    if is_desktop_notification_enabled {
+        desktop_notification_worker.set_action_handler(
+            clipboard_manager.clone(),
+            clipboard_watcher.get_toggle(),
+            audit_log.clone(),
+        );
         let _handle = lifecycle_manager.spawn(
             "Desktop notification worker",
             create_desktop_notification_worker_future(desktop_notification_worker),
         );
     }
 
+    if let Some(notification_sinks_worker) = notification_sinks_worker {
+        let _handle = lifecycle_manager.spawn(
+            "Notification sinks worker",
+            create_notification_sinks_worker_future(notification_sinks_worker),
+        );
+    }
+
     #[cfg(all(
         unix,
         not(any(
@@ -165,8 +284,11 @@ pub async fn serve_with_shutdown(
             "D-Bus",
             create_dbus_service_future(
                 clipboard_watcher.get_toggle(),
+                clipboard_watcher.current_contents(),
                 clipboard_manager.clone(),
-                dbus.identifier,
+                audit_log.clone(),
+                event_bus.subscribe(),
+                dbus,
             ),
         );
     }
@@ -175,25 +297,102 @@ pub async fn serve_with_shutdown(
     drop(dbus);
 
     if let Some(grpc_listen_address) = grpc_listen_address {
+        let listener = TcpListener::bind(grpc_listen_address)
+            .await
+            .context(error::BindGrpcServerSnafu { listen_address: grpc_listen_address })?;
         let _handle = lifecycle_manager.spawn(
             "gRPC HTTP server",
             create_grpc_http_server_future(
                 grpc_listen_address,
-                grpc_access_token.clone(),
+                listener,
+                interceptor.clone(),
+                grpc_tls_config,
                 clipboard_watcher.get_toggle(),
                 clipboard_manager.clone(),
+                event_bus.clone(),
+                audit_log.clone(),
+                health.clone(),
+                reload_handle.clone(),
+            ),
+        );
+    }
+
+    if web_ui_config.enable {
+        ensure_protected("Web UI", web_ui_config.listen_address, &interceptor)?;
+        let _handle = lifecycle_manager.spawn(
+            "Web UI server",
+            create_http_server_future(
+                "Web UI",
+                web_ui_config.listen_address,
+                http::Server::new(
+                    interceptor.clone(),
+                    clipboard_watcher.get_toggle(),
+                    clipboard_manager.clone(),
+                    event_bus.clone(),
+                )
+                .with_audit_log(audit_log.clone())
+                .with_web_ui(),
             ),
         );
     }
 
-    if let Some(grpc_local_socket) = grpc_local_socket {
+    if let Some(listen_address) = http_api_config.listen_address {
+        ensure_protected("HTTP API", listen_address, &interceptor)?;
+        let _handle = lifecycle_manager.spawn(
+            "HTTP API server",
+            create_http_server_future(
+                "HTTP API",
+                listen_address,
+                http::Server::new(
+                    interceptor.clone(),
+                    clipboard_watcher.get_toggle(),
+                    clipboard_manager.clone(),
+                    event_bus.clone(),
+                )
+                .with_audit_log(audit_log.clone()),
+            ),
+        );
+    }
+
+    if let Some(local_socket) = http_api_config.local_socket {
+        let _handle = lifecycle_manager.spawn(
+            "HTTP API local socket server",
+            create_http_local_socket_server_future(
+                local_socket,
+                local_socket::PeerFilter::new(grpc_local_socket_access.clone()),
+                http::Server::new(
+                    interceptor.clone(),
+                    clipboard_watcher.get_toggle(),
+                    clipboard_manager.clone(),
+                    event_bus.clone(),
+                )
+                .with_audit_log(audit_log.clone()),
+            ),
+        );
+    }
+
+    // the socket passed by systemd takes precedence over the configured one
+    let grpc_local_socket = if let Some(listener) = systemd::take_unix_listener() {
+        Some((listener, None))
+    } else if let Some(socket_path) = grpc_local_socket {
+        Some((local_socket::bind(&socket_path).await?, Some(socket_path)))
+    } else {
+        None
+    };
+    if let Some((listener, socket_path)) = grpc_local_socket {
         let _handle = lifecycle_manager.spawn(
             "gRPC local socket server",
             create_grpc_local_socket_server_future(
-                grpc_local_socket,
-                grpc_access_token,
+                listener,
+                socket_path,
+                local_socket::PeerFilter::new(grpc_local_socket_access),
+                interceptor,
                 clipboard_watcher.get_toggle(),
                 clipboard_manager.clone(),
+                event_bus.clone(),
+                audit_log,
+                health.clone(),
+                reload_handle,
             ),
         );
     }
@@ -203,10 +402,13 @@ pub async fn serve_with_shutdown(
 
         let _handle = lifecycle_manager.spawn(
             "Metrics server",
-            create_metrics_server_future(metrics_config.listen_address, metrics),
+            create_metrics_server_future(metrics_config.listen_address, metrics, health),
         );
     }
 
+    let _handle =
+        lifecycle_manager.spawn("Selection synchronizer", create_synchronizer_future(synchronizer));
+
     let handle = lifecycle_manager.spawn(
         "Clipboard Watcher worker",
         create_clipboard_watcher_worker_future(clipboard_watcher_worker),
@@ -218,52 +420,53 @@ pub async fn serve_with_shutdown(
             clipboard_watcher,
             clipboard_manager,
             history_manager,
-            synchronize_selection_with_clipboard,
+            synchronizer_sender,
             snippet_event_receiver,
+            reloader,
+            reload_request_receiver,
             handle,
         ),
     );
 
-    desktop_notification.on_started();
+    notifier.on_started();
+    systemd::notify_ready();
 
     if let Ok(Err(err)) = lifecycle_manager.serve().await {
         tracing::error!("{err}");
         Err(err)
     } else {
-        drop(snippets_watcher);
         Ok(())
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_grpc_local_socket_server_future(
-    local_socket: PathBuf,
-    grpc_access_token: Option<String>,
-    clipboard_watcher_toggle: ClipboardWatcherToggle<notification::DesktopNotification>,
-    clipboard_manager: Arc<Mutex<ClipboardManager<notification::DesktopNotification>>>,
+    listener: UnixListener,
+    socket_path: Option<PathBuf>,
+    peer_filter: local_socket::PeerFilter,
+    interceptor: grpc::Interceptor,
+    clipboard_watcher_toggle: ClipboardWatcherToggle<notification::Notifier>,
+    clipboard_manager: Arc<Mutex<ClipboardManager<notification::Notifier>>>,
+    event_bus: EventBus,
+    audit_log: AuditLog,
+    health: Health,
+    reload_handle: ReloadHandle,
 ) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
     move |signal| {
         async move {
-            tracing::info!("Listen Clipcat gRPC endpoint on {}", local_socket.display());
-            if let Some(local_socket_parent) = local_socket.parent() {
-                if let Err(err) = tokio::fs::create_dir_all(&local_socket_parent)
-                    .await
-                    .context(error::CreateUnixListenerSnafu { socket_path: local_socket.clone() })
-                {
-                    return ExitStatus::FatalError(err);
-                }
+            if let Some(ref socket_path) = socket_path {
+                tracing::info!("Listen Clipcat gRPC endpoint on {}", socket_path.display());
+            } else {
+                tracing::info!("Listen Clipcat gRPC endpoint on socket passed by systemd");
             }
+            let uds_stream = peer_filter.incoming(listener);
+            let (shutdown_sender, shutdown) = watch::channel(false);
 
-            let uds_stream = match UnixListener::bind(&local_socket)
-                .context(error::CreateUnixListenerSnafu { socket_path: local_socket.clone() })
-            {
-                Ok(uds) => UnixListenerStream::new(uds),
-                Err(err) => return ExitStatus::FatalError(err),
-            };
-
-            let interceptor = grpc::Interceptor::new(grpc_access_token);
             let result = tonic::transport::Server::builder()
+                .layer(grpc::LatencyLayer)
+                .add_service(HealthServer::new(grpc::HealthService::new(health)))
                 .add_service(SystemServer::with_interceptor(
-                    grpc::SystemService::new(),
+                    grpc::SystemService::new(reload_handle),
                     interceptor.clone(),
                 ))
                 .add_service(WatcherServer::with_interceptor(
@@ -271,20 +474,35 @@ fn create_grpc_local_socket_server_future(
                     interceptor.clone(),
                 ))
                 .add_service(ManagerServer::with_interceptor(
-                    grpc::ManagerService::new(clipboard_manager),
+                    grpc::ManagerService::new(
+                        clipboard_manager,
+                        event_bus,
+                        audit_log.clone(),
+                        shutdown,
+                    ),
+                    interceptor.clone(),
+                ))
+                .add_service(AuditServer::with_interceptor(
+                    grpc::AuditService::new(audit_log),
                     interceptor,
                 ))
-                .serve_with_incoming_shutdown(uds_stream, signal)
+                .serve_with_incoming_shutdown(uds_stream, async move {
+                    signal.await;
+                    let _unused = shutdown_sender.send(true);
+                })
                 .await
                 .context(error::StartTonicServerSnafu);
 
             match result {
                 Ok(()) => {
-                    tracing::info!(
-                        "Remove Unix domain socket `{path}`",
-                        path = local_socket.display()
-                    );
-                    drop(tokio::fs::remove_file(local_socket).await);
+                    // the socket passed by systemd is removed by systemd
+                    if let Some(socket_path) = socket_path {
+                        tracing::info!(
+                            "Remove Unix domain socket `{path}`",
+                            path = socket_path.display()
+                        );
+                        drop(tokio::fs::remove_file(socket_path).await);
+                    }
                     tracing::info!("gRPC local socket server is shut down gracefully");
                     ExitStatus::Success
                 }
@@ -305,13 +523,25 @@ fn create_grpc_local_socket_server_future(
     ))
 ))]
 fn create_dbus_service_future(
-    clipboard_watcher_toggle: ClipboardWatcherToggle<notification::DesktopNotification>,
-    clipboard_manager: Arc<Mutex<ClipboardManager<notification::DesktopNotification>>>,
-    identifier: Option<String>,
+    clipboard_watcher_toggle: ClipboardWatcherToggle<notification::Notifier>,
+    current_contents: ClipboardWatcherCurrentContents,
+    clipboard_manager: Arc<Mutex<ClipboardManager<notification::Notifier>>>,
+    audit_log: AuditLog,
+    event_receiver: broadcast::Receiver<Event>,
+    dbus_config: config::DBusConfig,
 ) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
     move |signal| {
         async move {
-            match serve_dbus(clipboard_watcher_toggle, clipboard_manager, identifier, signal).await
+            match serve_dbus(
+                clipboard_watcher_toggle,
+                current_contents,
+                clipboard_manager,
+                audit_log,
+                event_receiver,
+                dbus_config,
+                signal,
+            )
+            .await
             {
                 Ok(()) => {
                     tracing::info!("D-Bus service is shut down gracefully");
@@ -338,6 +568,20 @@ fn create_desktop_notification_worker_future(
     }
 }
 
+fn create_notification_sinks_worker_future(
+    worker: notification::NotificationSinksWorker,
+) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
+    move |signal| {
+        async move {
+            tracing::info!("Notification sinks worker is started");
+            worker.serve(signal).await;
+            tracing::info!("Notification sinks worker is shut down gracefully");
+            ExitStatus::Success
+        }
+        .boxed()
+    }
+}
+
 fn create_clipboard_watcher_worker_future(
     worker: ClipboardWatcherWorker,
 ) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
@@ -358,20 +602,48 @@ fn create_clipboard_watcher_worker_future(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_grpc_http_server_future(
     listen_address: SocketAddr,
-    grpc_access_token: Option<String>,
-    clipboard_watcher_toggle: ClipboardWatcherToggle<notification::DesktopNotification>,
-    clipboard_manager: Arc<Mutex<ClipboardManager<notification::DesktopNotification>>>,
+    listener: TcpListener,
+    interceptor: grpc::Interceptor,
+    tls_config: Option<tonic::transport::ServerTlsConfig>,
+    clipboard_watcher_toggle: ClipboardWatcherToggle<notification::Notifier>,
+    clipboard_manager: Arc<Mutex<ClipboardManager<notification::Notifier>>>,
+    event_bus: EventBus,
+    audit_log: AuditLog,
+    health: Health,
+    reload_handle: ReloadHandle,
 ) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
     move |signal| {
         async move {
-            tracing::info!("Listen Clipcat gRPC endpoint on {listen_address}");
+            let incoming = match TcpIncoming::from_listener(listener, true, None) {
+                Ok(incoming) => incoming,
+                Err(err) => {
+                    return ExitStatus::FatalError(Error::BindGrpcServer {
+                        listen_address,
+                        source: std::io::Error::other(err),
+                    })
+                }
+            };
 
-            let interceptor = grpc::Interceptor::new(grpc_access_token);
-            let result = tonic::transport::Server::builder()
+            let (shutdown_sender, shutdown) = watch::channel(false);
+            let mut server = tonic::transport::Server::builder();
+            if let Some(tls_config) = tls_config {
+                tracing::info!("Listen Clipcat gRPC endpoint on {listen_address} with TLS");
+                server = match server.tls_config(tls_config).context(error::ConfigureGrpcTlsSnafu) {
+                    Ok(server) => server,
+                    Err(err) => return ExitStatus::FatalError(err),
+                };
+            } else {
+                tracing::info!("Listen Clipcat gRPC endpoint on {listen_address}");
+            }
+
+            let result = server
+                .layer(grpc::LatencyLayer)
+                .add_service(HealthServer::new(grpc::HealthService::new(health)))
                 .add_service(SystemServer::with_interceptor(
-                    grpc::SystemService::new(),
+                    grpc::SystemService::new(reload_handle),
                     interceptor.clone(),
                 ))
                 .add_service(WatcherServer::with_interceptor(
@@ -379,10 +651,22 @@ fn create_grpc_http_server_future(
                     interceptor.clone(),
                 ))
                 .add_service(ManagerServer::with_interceptor(
-                    grpc::ManagerService::new(clipboard_manager),
+                    grpc::ManagerService::new(
+                        clipboard_manager,
+                        event_bus,
+                        audit_log.clone(),
+                        shutdown,
+                    ),
+                    interceptor.clone(),
+                ))
+                .add_service(AuditServer::with_interceptor(
+                    grpc::AuditService::new(audit_log),
                     interceptor,
                 ))
-                .serve_with_shutdown(listen_address, signal)
+                .serve_with_incoming_shutdown(incoming, async move {
+                    signal.await;
+                    let _unused = shutdown_sender.send(true);
+                })
                 .await
                 .context(error::StartTonicServerSnafu);
 
@@ -398,12 +682,15 @@ fn create_grpc_http_server_future(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_clipboard_worker_future(
-    clipboard_watcher: ClipboardWatcher<notification::DesktopNotification>,
-    clipboard_manager: Arc<Mutex<ClipboardManager<notification::DesktopNotification>>>,
+    clipboard_watcher: ClipboardWatcher<notification::Notifier>,
+    clipboard_manager: Arc<Mutex<ClipboardManager<notification::Notifier>>>,
     history_manager: HistoryManager,
-    synchronize_selection_with_clipboard: bool,
+    synchronizer_sender: mpsc::UnboundedSender<ClipEntry>,
     snippet_event_receiver: SnippetWatcherEventReceiver,
+    reloader: Reloader,
+    reload_request_receiver: mpsc::UnboundedReceiver<reload::Responder>,
     handle: Handle<Error>,
 ) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
     move |shutdown_signal| {
@@ -412,8 +699,10 @@ fn create_clipboard_worker_future(
                 clipboard_watcher,
                 clipboard_manager,
                 history_manager,
-                synchronize_selection_with_clipboard,
+                synchronizer_sender,
                 snippet_event_receiver,
+                reloader,
+                reload_request_receiver,
                 handle,
                 shutdown_signal,
             )
@@ -430,9 +719,100 @@ fn create_clipboard_worker_future(
     }
 }
 
+fn create_config_reload_trigger_future(
+    reload_handle: ReloadHandle,
+    watch_file_path: Option<PathBuf>,
+) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
+    move |signal| {
+        async move {
+            tracing::info!("Configuration reload trigger is started");
+            match reload::serve_triggers(reload_handle, watch_file_path, signal).await {
+                Ok(()) => {
+                    tracing::info!("Configuration reload trigger is shut down gracefully");
+                    ExitStatus::Success
+                }
+                Err(err) => ExitStatus::FatalError(err),
+            }
+        }
+        .boxed()
+    }
+}
+
+fn create_synchronizer_future(
+    synchronizer: Synchronizer<notification::Notifier>,
+) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
+    move |signal| {
+        async move {
+            tracing::info!("Selection synchronizer is started");
+            synchronizer.serve(signal).await;
+            tracing::info!("Selection synchronizer is shut down gracefully");
+            ExitStatus::Success
+        }
+        .boxed()
+    }
+}
+
+// the clipboard history is served to anyone who could connect if no access
+// token is configured
+fn ensure_protected(
+    name: &'static str,
+    listen_address: SocketAddr,
+    interceptor: &grpc::Interceptor,
+) -> Result<()> {
+    if listen_address.ip().is_loopback() || interceptor.requires_token() {
+        Ok(())
+    } else {
+        Err(Error::UnprotectedHttpServer { name, listen_address })
+    }
+}
+
+fn create_http_server_future(
+    server_name: &'static str,
+    listen_address: SocketAddr,
+    server: http::Server<notification::Notifier>,
+) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
+    move |signal| {
+        async move {
+            tracing::info!("Listen {server_name} endpoint on {listen_address}");
+            match server.serve_with_shutdown(listen_address, signal).await {
+                Ok(()) => {
+                    tracing::info!("{server_name} server is shut down gracefully");
+                    ExitStatus::Success
+                }
+                Err(err) => ExitStatus::FatalError(err),
+            }
+        }
+        .boxed()
+    }
+}
+
+fn create_http_local_socket_server_future(
+    local_socket: PathBuf,
+    peer_filter: local_socket::PeerFilter,
+    server: http::Server<notification::Notifier>,
+) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>> {
+    move |signal| {
+        async move {
+            tracing::info!("Listen HTTP API endpoint on {}", local_socket.display());
+            match server
+                .serve_with_local_socket_and_shutdown(&local_socket, peer_filter, signal)
+                .await
+            {
+                Ok(()) => {
+                    tracing::info!("HTTP API local socket server is shut down gracefully");
+                    ExitStatus::Success
+                }
+                Err(err) => ExitStatus::FatalError(err),
+            }
+        }
+        .boxed()
+    }
+}
+
 fn create_metrics_server_future<Metrics>(
     listen_address: SocketAddr,
     metrics: Metrics,
+    health: Health,
 ) -> impl FnOnce(Shutdown) -> Pin<Box<dyn Future<Output = ExitStatus<Error>> + Send>>
 where
     Metrics: clipcat_metrics::Metrics + 'static,
@@ -441,7 +821,8 @@ where
         async move {
             tracing::info!("Listen metrics endpoint on {listen_address}");
             let result =
-                clipcat_metrics::start_metrics_server(listen_address, metrics, signal).await;
+                clipcat_metrics::start_metrics_server(listen_address, metrics, health, signal)
+                    .await;
             match result {
                 Ok(()) => {
                     tracing::info!("Metrics server is shut down gracefully");
@@ -454,33 +835,54 @@ where
     }
 }
 
-#[allow(clippy::cognitive_complexity, clippy::redundant_pub_crate)]
+#[allow(
+    clippy::cognitive_complexity,
+    clippy::redundant_pub_crate,
+    clippy::too_many_arguments,
+    clippy::too_many_lines
+)]
 async fn serve_worker(
-    clipboard_watcher: ClipboardWatcher<notification::DesktopNotification>,
-    clipboard_manager: Arc<Mutex<ClipboardManager<notification::DesktopNotification>>>,
+    clipboard_watcher: ClipboardWatcher<notification::Notifier>,
+    clipboard_manager: Arc<Mutex<ClipboardManager<notification::Notifier>>>,
     mut history_manager: HistoryManager,
-    synchronize_selection_with_clipboard: bool,
-    mut snippet_event_receiver: SnippetWatcherEventReceiver,
+    synchronizer_sender: mpsc::UnboundedSender<ClipEntry>,
+    snippet_event_receiver: SnippetWatcherEventReceiver,
+    mut reloader: Reloader,
+    mut reload_request_receiver: mpsc::UnboundedReceiver<reload::Responder>,
     handle: Handle<Error>,
     shutdown_signal: Shutdown,
 ) -> Result<()> {
     enum Event {
-        NewClip(clipcat_base::ClipEntry),
-        NewSnippet(clipcat_base::ClipEntry),
+        NewClip(ClipEntry),
+        NewSnippet(ClipEntry),
         RemoveSnippet(u64),
+        Reload(reload::Responder),
+        Watchdog,
         Shutdown,
     }
 
-    let (send, mut recv) = tokio::sync::mpsc::unbounded_channel();
-    let snippets_event_handle = tokio::spawn({
+    let spawn_snippet_event_forwarder =
+        |mut snippet_event_receiver: SnippetWatcherEventReceiver,
+         send: mpsc::UnboundedSender<Event>| {
+            tokio::spawn(async move {
+                while let Some(event) = snippet_event_receiver.recv().await {
+                    let event = match event {
+                        SnippetWatcherEvent::Add(clip) => Event::NewSnippet(clip),
+                        SnippetWatcherEvent::Remove(id) => Event::RemoveSnippet(id),
+                    };
+                    drop(send.send(event));
+                }
+            })
+        };
+
+    let (send, mut recv) = mpsc::unbounded_channel();
+    let mut snippets_event_handle =
+        spawn_snippet_event_forwarder(snippet_event_receiver, send.clone());
+    let reload_request_handle = tokio::spawn({
         let send = send.clone();
         async move {
-            while let Some(event) = snippet_event_receiver.recv().await {
-                let event = match event {
-                    SnippetWatcherEvent::Add(clip) => Event::NewSnippet(clip),
-                    SnippetWatcherEvent::Remove(id) => Event::RemoveSnippet(id),
-                };
-                drop(send.send(event));
+            while let Some(responder) = reload_request_receiver.recv().await {
+                drop(send.send(Event::Reload(responder)));
             }
         }
     });
@@ -507,14 +909,48 @@ async fn serve_worker(
             }
         }
     });
-    let shutdown_handle = tokio::spawn(async move {
-        shutdown_signal.await;
-        drop(send.send(Event::Shutdown));
+    // the watchdog is pinged by this loop, so that a stuck loop is restarted by
+    // systemd
+    let watchdog_handle = systemd::watchdog_interval().map(|interval| {
+        tracing::info!("Ping systemd watchdog every {}ms", interval.as_millis());
+        let send = send.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                let _ = interval.tick().await;
+                if send.send(Event::Watchdog).is_err() {
+                    break;
+                }
+            }
+        })
+    });
+    let shutdown_handle = tokio::spawn({
+        let send = send.clone();
+        async move {
+            shutdown_signal.await;
+            drop(send.send(Event::Shutdown));
+        }
     });
 
     while let Some(event) = recv.recv().await {
         match event {
-            Event::Shutdown => break,
+            Event::Shutdown => {
+                systemd::notify_stopping();
+                break;
+            }
+            Event::Watchdog => systemd::notify_watchdog(),
+            Event::Reload(responder) => {
+                let result = reloader.reload(&clipboard_manager).await.map(
+                    |(restart_required, snippet_event_receiver)| {
+                        // the snippets are watched by a new watcher
+                        snippets_event_handle.abort();
+                        snippets_event_handle =
+                            spawn_snippet_event_forwarder(snippet_event_receiver, send.clone());
+                        restart_required
+                    },
+                );
+                drop(responder.send(result));
+            }
             Event::RemoveSnippet(clip_id) => {
                 let mut clipboard_manager = clipboard_manager.lock().await;
                 let _ = clipboard_manager.remove_snippet(clip_id);
@@ -529,17 +965,8 @@ async fn serve_worker(
                     kind = clip.kind(),
                     basic_info = clip.basic_information()
                 );
-                {
-                    let mut clipboard_manager = clipboard_manager.lock().await;
-                    let id = clipboard_manager.insert(clip.clone());
-                    if synchronize_selection_with_clipboard
-                        && clip.kind() == ClipboardKind::Clipboard
-                    {
-                        if let Err(err) = clipboard_manager.mark(id, ClipboardKind::Primary).await {
-                            tracing::warn!("{err}");
-                        }
-                    }
-                }
+                let _id = clipboard_manager.lock().await.insert(clip.clone());
+                drop(synchronizer_sender.send(clip.clone()));
 
                 if let Err(err) = history_manager.put(&clip).await {
                     tracing::error!("{err}");
@@ -548,22 +975,31 @@ async fn serve_worker(
         }
     }
 
-    let (clips, history_capacity) = {
+    let (clips, pinned_clips, history_capacity) = {
         let manager = clipboard_manager.lock().await;
-        (manager.export(false), manager.capacity())
+        (manager.export(false), manager.pinned_clips(), manager.capacity())
     };
 
     {
+        // pinned clips are retained beyond the capacity
+        let history_capacity = history_capacity.max(clips.len());
         tracing::info!("Save history and shrink to capacity {history_capacity}");
         if let Err(err) = history_manager.save_and_shrink_to(&clips, history_capacity).await {
             tracing::warn!("Failed to save history, error: {err}");
         }
+        if let Err(err) = history_manager.save_pinned(&pinned_clips).await {
+            tracing::warn!("Failed to save pinned clips, error: {err}");
+        }
         tracing::info!("Clips are stored in `{path}`", path = history_manager.path().display());
     }
 
     snippets_event_handle.abort();
+    reload_request_handle.abort();
     clip_reciever_handle.abort();
     shutdown_handle.abort();
+    if let Some(watchdog_handle) = watchdog_handle {
+        watchdog_handle.abort();
+    }
 
     Ok(())
 }
@@ -578,9 +1014,12 @@ async fn serve_worker(
     ))
 ))]
 async fn serve_dbus(
-    clipboard_watcher_toggle: ClipboardWatcherToggle<notification::DesktopNotification>,
-    clipboard_manager: Arc<Mutex<ClipboardManager<notification::DesktopNotification>>>,
-    identifier: Option<String>,
+    clipboard_watcher_toggle: ClipboardWatcherToggle<notification::Notifier>,
+    current_contents: ClipboardWatcherCurrentContents,
+    clipboard_manager: Arc<Mutex<ClipboardManager<notification::Notifier>>>,
+    audit_log: AuditLog,
+    event_receiver: broadcast::Receiver<Event>,
+    config::DBusConfig { identifier, klipper_compatible, .. }: config::DBusConfig,
     signal: Shutdown,
 ) -> Result<()> {
     let dbus_service_name = identifier.map_or_else(
@@ -592,17 +1031,61 @@ async fn serve_dbus(
 
     let system = dbus::SystemService::new();
     let watcher = dbus::WatcherService::new(clipboard_watcher_toggle);
-    let manager = dbus::ManagerService::new(clipboard_manager);
-    let _conn = zbus::connection::Builder::session()?
+    let manager = dbus::ManagerService::new(clipboard_manager.clone(), audit_log.clone());
+    let search_provider =
+        dbus::SearchProviderService::new(clipboard_manager.clone(), audit_log.clone());
+    let conn = zbus::connection::Builder::session()?
         .name(dbus_service_name)?
         .serve_at(clipcat_base::DBUS_SYSTEM_OBJECT_PATH, system)?
         .serve_at(clipcat_base::DBUS_WATCHER_OBJECT_PATH, watcher)?
         .serve_at(clipcat_base::DBUS_MANAGER_OBJECT_PATH, manager)?
+        .serve_at(clipcat_base::DBUS_SEARCH_PROVIDER_OBJECT_PATH, search_provider)?
         .build()
         .await?;
 
+    if klipper_compatible {
+        serve_klipper(&conn, clipboard_manager, current_contents, audit_log).await?;
+    }
+
     tracing::info!("D-Bus service is created");
-    signal.await;
+    dbus::emit_signals::<notification::Notifier, _>(&conn, event_receiver, signal).await?;
+
+    Ok(())
+}
 
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "android",
+        target_os = "emscripten"
+    ))
+))]
+async fn serve_klipper(
+    conn: &zbus::Connection,
+    clipboard_manager: Arc<Mutex<ClipboardManager<notification::Notifier>>>,
+    current_contents: ClipboardWatcherCurrentContents,
+    audit_log: AuditLog,
+) -> Result<()> {
+    let _unused = conn
+        .object_server()
+        .at(
+            clipcat_base::DBUS_KLIPPER_OBJECT_PATH,
+            dbus::KlipperService::new(clipboard_manager, current_contents, audit_log),
+        )
+        .await?;
+
+    // Klipper may be running, the Klipper interface is optional
+    match conn.request_name(clipcat_base::DBUS_KLIPPER_SERVICE_NAME).await {
+        Ok(()) => tracing::info!(
+            "Provide Klipper D-Bus service at {}",
+            clipcat_base::DBUS_KLIPPER_SERVICE_NAME
+        ),
+        Err(err) => tracing::warn!(
+            "Could not provide Klipper D-Bus service at {}, error: {err}",
+            clipcat_base::DBUS_KLIPPER_SERVICE_NAME
+        ),
+    }
     Ok(())
 }
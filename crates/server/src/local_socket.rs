@@ -0,0 +1,242 @@
+use std::{
+    collections::HashSet,
+    io,
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use futures::{Stream, StreamExt};
+use snafu::ResultExt;
+use tokio::net::{UnixListener, UnixStream};
+use tokio_stream::wrappers::UnixListenerStream;
+
+use crate::{
+    config::LocalSocketAccessConfig,
+    error::{self, Error},
+};
+
+/// Only the owner can connect to the socket.
+const SOCKET_MODE: u32 = 0o600;
+
+/// Only the owner can access the parent directory created for the socket.
+const DIRECTORY_MODE: u32 = 0o700;
+
+/// Binds a Unix domain socket at `socket_path` which is accessible only by
+/// the user of `clipcatd`, the parent directory is created with access only
+/// by the user if it does not exist, so the socket is not connectable by
+/// others before its permissions are restricted. A socket left behind by a
+/// `clipcatd` which was not shut down gracefully is removed.
+///
+/// # Errors
+///
+/// This function will return an error if the socket could not be created.
+pub async fn bind(socket_path: &Path) -> Result<UnixListener, Error> {
+    if let Some(parent) = socket_path.parent() {
+        tokio::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(DIRECTORY_MODE)
+            .create(parent)
+            .await
+            .context(error::CreateUnixListenerSnafu { socket_path: socket_path.to_path_buf() })?;
+    }
+    if is_stale(socket_path).await {
+        tracing::info!("Remove stale Unix domain socket `{path}`", path = socket_path.display());
+        let _unused = tokio::fs::remove_file(socket_path).await;
+    }
+    let listener = UnixListener::bind(socket_path)
+        .context(error::CreateUnixListenerSnafu { socket_path: socket_path.to_path_buf() })?;
+    tokio::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(SOCKET_MODE))
+        .await
+        .context(error::CreateUnixListenerSnafu { socket_path: socket_path.to_path_buf() })?;
+    Ok(listener)
+}
+
+// nobody listens on a stale socket, a socket in use is not removed
+async fn is_stale(socket_path: &Path) -> bool {
+    match tokio::fs::symlink_metadata(socket_path).await {
+        Ok(metadata) if metadata.file_type().is_socket() => matches!(
+            UnixStream::connect(socket_path).await,
+            Err(err) if err.kind() == io::ErrorKind::ConnectionRefused
+        ),
+        _ => false,
+    }
+}
+
+/// Accepts connections from peers with the allowed UIDs and executables,
+/// the credentials of peers are provided by `SO_PEERCRED`.
+#[derive(Clone, Debug)]
+pub struct PeerFilter {
+    allowed_uids: HashSet<u32>,
+
+    // any executable is allowed if it is empty
+    allowed_executables: HashSet<PathBuf>,
+}
+
+impl PeerFilter {
+    pub fn new(
+        LocalSocketAccessConfig { allowed_uids, allowed_executables }: LocalSocketAccessConfig,
+    ) -> Self {
+        let allowed_uids =
+            if allowed_uids.is_empty() { HashSet::from([current_uid()]) } else { allowed_uids };
+        // `/proc/<pid>/exe` is always a canonical path
+        let allowed_executables = allowed_executables
+            .into_iter()
+            .map(|path| std::fs::canonicalize(&path).unwrap_or(path))
+            .collect();
+        Self { allowed_uids, allowed_executables }
+    }
+
+    /// Checks the credentials of the peer of `stream`.
+    pub fn accepts(&self, stream: &UnixStream) -> bool {
+        let credentials = match stream.peer_cred() {
+            Ok(credentials) => credentials,
+            Err(err) => {
+                tracing::warn!("Could not get credentials of peer, error: {err}");
+                return false;
+            }
+        };
+
+        let uid = credentials.uid();
+        if !self.allowed_uids.contains(&uid) {
+            tracing::warn!("Reject connection from UID {uid}");
+            return false;
+        }
+
+        self.accepts_executable(credentials.pid())
+    }
+
+    fn accepts_executable(&self, pid: Option<i32>) -> bool {
+        if self.allowed_executables.is_empty() {
+            return true;
+        }
+        match pid.map(executable_of) {
+            Some(Ok(executable)) if self.allowed_executables.contains(&executable) => true,
+            Some(Ok(executable)) => {
+                tracing::warn!("Reject connection from executable `{}`", executable.display());
+                false
+            }
+            Some(Err(err)) => {
+                tracing::warn!("Could not get executable of peer, error: {err}");
+                false
+            }
+            None => {
+                tracing::warn!("Could not get process ID of peer");
+                false
+            }
+        }
+    }
+
+    /// Accepts connections from `listener`, rejected connections are closed.
+    pub fn incoming(self, listener: UnixListener) -> impl Stream<Item = io::Result<UnixStream>> {
+        UnixListenerStream::new(listener).filter(move |stream| {
+            let accepted = stream.as_ref().map_or(true, |stream| self.accepts(stream));
+            futures::future::ready(accepted)
+        })
+    }
+
+    /// Wraps `listener` for `axum`, rejected connections are closed.
+    pub const fn listener(self, listener: UnixListener) -> Listener {
+        Listener { listener, filter: self }
+    }
+}
+
+/// A [`UnixListener`] accepting only the connections accepted by a
+/// [`PeerFilter`].
+#[derive(Debug)]
+pub struct Listener {
+    listener: UnixListener,
+
+    filter: PeerFilter,
+}
+
+impl axum::serve::Listener for Listener {
+    type Addr = tokio::net::unix::SocketAddr;
+    type Io = UnixStream;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            // errors of accepting are logged and retried by `UnixListener`
+            let (stream, addr) = axum::serve::Listener::accept(&mut self.listener).await;
+            if self.filter.accepts(&stream) {
+                return (stream, addr);
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> { self.listener.local_addr() }
+}
+
+#[allow(unsafe_code)]
+fn current_uid() -> u32 {
+    // SAFETY: `geteuid` is always successful
+    unsafe { libc::geteuid() }
+}
+
+#[cfg(target_os = "linux")]
+fn executable_of(pid: i32) -> io::Result<PathBuf> { std::fs::read_link(format!("/proc/{pid}/exe")) }
+
+#[cfg(not(target_os = "linux"))]
+fn executable_of(_pid: i32) -> io::Result<PathBuf> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "`/proc/<pid>/exe` is not available"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, os::unix::fs::PermissionsExt, path::PathBuf};
+
+    use tokio::net::UnixStream;
+
+    use super::{bind, current_uid, PeerFilter};
+    use crate::config::LocalSocketAccessConfig;
+
+    #[tokio::test]
+    async fn test_peer_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("clipcat").join("grpc.sock");
+        let listener = bind(&socket_path).await.unwrap();
+        let mode = std::fs::metadata(&socket_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let mode = std::fs::metadata(socket_path.parent().unwrap()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        let _client = UnixStream::connect(&socket_path).await.unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+
+        let uid = current_uid();
+        let filter = |allowed_uids: &[u32], allowed_executables: &[PathBuf]| {
+            PeerFilter::new(LocalSocketAccessConfig {
+                allowed_uids: allowed_uids.iter().copied().collect(),
+                allowed_executables: allowed_executables.iter().cloned().collect::<HashSet<_>>(),
+            })
+        };
+        assert!(filter(&[], &[]).accepts(&stream));
+        assert!(filter(&[uid], &[]).accepts(&stream));
+        assert!(!filter(&[uid.wrapping_add(1)], &[]).accepts(&stream));
+
+        let executable = std::env::current_exe().unwrap();
+        #[cfg(target_os = "linux")]
+        assert!(filter(&[], std::slice::from_ref(&executable)).accepts(&stream));
+        assert!(!filter(&[], &[executable.with_file_name("clipcatctl")]).accepts(&stream));
+    }
+
+    #[tokio::test]
+    async fn test_bind_stale_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("http.sock");
+
+        let listener = bind(&socket_path).await.unwrap();
+        // the socket is in use
+        assert!(bind(&socket_path).await.is_err());
+
+        // the socket file is left behind
+        drop(listener);
+        assert!(socket_path.exists());
+        let _listener = bind(&socket_path).await.unwrap();
+
+        // other files are not removed
+        let file_path = dir.path().join("file");
+        std::fs::write(&file_path, "").unwrap();
+        assert!(bind(&file_path).await.is_err());
+        assert!(file_path.exists());
+    }
+}
@@ -5,25 +5,31 @@ use std::{
     sync::Arc,
 };
 
-use clipcat_base::{ClipEntry, ClipEntryMetadata, ClipboardContent, ClipboardKind};
+use clipcat_base::{ClipEntry, ClipEntryMetadata, ClipSource, ClipboardKind};
 use snafu::ResultExt;
 use time::OffsetDateTime;
 
 pub use self::error::Error;
-use crate::{backend::ClipboardBackend, notification};
+use crate::{
+    backend::ClipboardBackend,
+    events::{self, Event, EventBus},
+    metrics, notification,
+};
 
 const DEFAULT_CAPACITY: usize = 40;
 
 pub struct ClipboardManager<Notification> {
     backend: Arc<dyn ClipboardBackend>,
 
-    primary_threshold: time::Duration,
-
     capacity: usize,
 
     // use id of ClipEntry as the key
     clips: HashMap<u64, ClipEntry>,
 
+    // total size of `clips`, it is updated with `clips` instead of being summed up
+    // on every change
+    bytes: usize,
+
     // store current clip for each clipboard kind
     current_clips: [Option<u64>; ClipboardKind::MAX_LENGTH],
 
@@ -32,6 +38,11 @@ pub struct ClipboardManager<Notification> {
 
     snippet_ids: HashSet<u64>,
 
+    // pinned clips are retained when the history is full or cleared
+    pinned_ids: HashSet<u64>,
+
+    event_bus: EventBus,
+
     notification: Notification,
 }
 
@@ -42,18 +53,20 @@ where
     pub fn with_capacity(
         backend: Arc<dyn ClipboardBackend>,
         capacity: usize,
-        primary_threshold: time::Duration,
+        event_bus: EventBus,
         notification: Notification,
     ) -> Self {
         let capacity = if capacity == 0 { DEFAULT_CAPACITY } else { capacity };
         Self {
             backend,
-            primary_threshold,
             capacity,
             clips: HashMap::new(),
+            bytes: 0,
             current_clips: [None; ClipboardKind::MAX_LENGTH],
             timestamp_to_id: BTreeMap::new(),
             snippet_ids: HashSet::new(),
+            pinned_ids: HashSet::new(),
+            event_bus,
             notification,
         }
     }
@@ -61,42 +74,80 @@ where
     #[cfg(test)]
     #[inline]
     pub fn new(backend: Arc<dyn ClipboardBackend>, notification: Notification) -> Self {
-        Self::with_capacity(
-            backend,
-            DEFAULT_CAPACITY,
-            time::Duration::milliseconds(0),
-            notification,
-        )
+        Self::with_capacity(backend, DEFAULT_CAPACITY, EventBus::new(), notification)
     }
 
     #[inline]
     pub const fn capacity(&self) -> usize { self.capacity }
 
+    /// Changes the capacity, the oldest clips are removed if the history
+    /// exceeds the new capacity.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = if capacity == 0 { DEFAULT_CAPACITY } else { capacity };
+        self.remove_oldest();
+        self.update_size_metrics();
+    }
+
     #[inline]
-    pub fn import(&mut self, clips: &[ClipEntry]) { self.import_iter(clips.iter()); }
+    pub fn import(&mut self, clips: &[ClipEntry], pinned_ids: &HashSet<u64>) {
+        self.import_iter(clips.iter(), pinned_ids);
+    }
 
+    /// Replaces the history with `clips`, the clips of `pinned_ids` are pinned.
     #[inline]
-    pub fn import_iter<'a>(&'a mut self, clips_iter: impl Iterator<Item = &'a ClipEntry>) {
+    pub fn import_iter<'a>(
+        &'a mut self,
+        clips_iter: impl Iterator<Item = &'a ClipEntry>,
+        pinned_ids: &HashSet<u64>,
+    ) {
         self.clips.clear();
+        self.bytes = 0;
         self.timestamp_to_id.clear();
+        self.pinned_ids.clear();
         for clip in clips_iter {
             let (id, timestamp) = (clip.id(), clip.timestamp());
             let _ = self.timestamp_to_id.insert(timestamp, id);
-            drop(self.clips.insert(id, clip.clone()));
+            self.insert_clip(clip.clone());
+            if pinned_ids.contains(&id) {
+                let _unused = self.pinned_ids.insert(id);
+            }
         }
 
         self.remove_oldest();
+        self.update_size_metrics();
     }
 
     pub fn insert_snippets(&mut self, snippets: &[ClipEntry]) {
         for clip in snippets {
             let (id, timestamp) = (clip.id(), clip.timestamp());
             let _ = self.timestamp_to_id.insert(timestamp, id);
-            drop(self.clips.insert(id, clip.clone()));
+            self.insert_clip(clip.clone());
             let _unused = self.snippet_ids.insert(id);
         }
 
         self.remove_oldest();
+        self.update_size_metrics();
+    }
+
+    /// Replaces all snippets with `snippets`.
+    pub fn replace_snippets(&mut self, snippets: &[ClipEntry]) {
+        let new_ids = snippets.iter().map(ClipEntry::id).collect::<HashSet<_>>();
+        let removed_ids =
+            self.snippet_ids.iter().filter(|id| !new_ids.contains(id)).copied().collect::<Vec<_>>();
+        for id in removed_ids {
+            let _unused = self.snippet_ids.remove(&id);
+            if let Some(clip) = self.remove_clip(id) {
+                let _id = self.timestamp_to_id.remove(&clip.timestamp());
+                self.event_bus.publish(Event::ClipRemoved { id });
+            }
+        }
+
+        let new_snippets = snippets
+            .iter()
+            .filter(|clip| !self.snippet_ids.contains(&clip.id()))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.insert_snippets(&new_snippets);
     }
 
     #[inline]
@@ -110,9 +161,28 @@ where
             .collect()
     }
 
+    #[inline]
+    pub fn pinned_clips(&self) -> Vec<ClipEntry> {
+        self.pinned_ids.iter().filter_map(|id| self.clips.get(id)).cloned().collect()
+    }
+
     #[inline]
     pub fn list(&self, preview_length: usize) -> Vec<ClipEntryMetadata> {
-        self.iter().map(|entry| entry.metadata(Some(preview_length))).collect()
+        self.iter().map(|entry| self.metadata_of(entry, preview_length)).collect()
+    }
+
+    #[inline]
+    pub fn get_metadata(&self, id: u64, preview_length: usize) -> Option<ClipEntryMetadata> {
+        self.clips.get(&id).map(|entry| self.metadata_of(entry, preview_length))
+    }
+
+    fn metadata_of(&self, entry: &ClipEntry, preview_length: usize) -> ClipEntryMetadata {
+        let id = entry.id();
+        ClipEntryMetadata {
+            pinned: self.is_pinned(id),
+            source: if self.is_snippet(id) { ClipSource::Snippet } else { ClipSource::History },
+            ..entry.metadata(Some(preview_length))
+        }
     }
 
     #[inline]
@@ -130,39 +200,21 @@ where
     pub fn insert(&mut self, data: ClipEntry) -> u64 { self.insert_inner(data) }
 
     fn insert_inner(&mut self, entry: ClipEntry) -> u64 {
-        // emit notification
-        match entry.as_ref() {
-            ClipboardContent::Image { width, height, bytes } => {
-                self.notification.on_image_fetched(bytes.len(), *width, *height);
-            }
-            ClipboardContent::Plaintext(text) => {
-                self.notification.on_plaintext_fetched(text.chars().count());
-
-                if let Some(id) = self.current_clips[usize::from(entry.kind())] {
-                    if let Some(current_clip) = self.clips.get(&id) {
-                        if entry.timestamp() - current_clip.timestamp() < self.primary_threshold {
-                            if let ClipboardContent::Plaintext(current_text) = current_clip.as_ref()
-                            {
-                                let text = text.as_bytes();
-                                let current_text = current_text.as_bytes();
-                                let len = text.len().min(current_text.len());
-                                if text[..len] == current_text[..len] {
-                                    if let Some(clip) = self.clips.remove(&id) {
-                                        let _id = self.timestamp_to_id.remove(&clip.timestamp());
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        self.notification.on_clip_fetched(&entry);
 
         let (id, timestamp) = (entry.id(), entry.timestamp());
         self.current_clips[usize::from(entry.kind())] = Some(id);
-        drop(self.clips.insert(id, entry));
+        // clips with the same contents share the same id, inserting them again only
+        // updates the existing clip
+        if !self.clips.contains_key(&id) {
+            self.event_bus.publish(Event::ClipAdded {
+                metadata: entry.metadata(Some(events::PREVIEW_LENGTH)),
+            });
+        }
+        self.insert_clip(entry);
         let _unused = self.timestamp_to_id.insert(timestamp, id);
         self.remove_oldest();
+        self.update_size_metrics();
         id
     }
 
@@ -177,18 +229,30 @@ where
             return;
         }
 
-        let snippet_count = self.snippet_ids.len();
+        // a pinned snippet is retained once
+        let retained_count = self.snippet_ids.union(&self.pinned_ids).count();
         let now = OffsetDateTime::now_utc();
 
-        while self.clips.len() > self.capacity + snippet_count {
-            if let Some((timestamp, id)) = self.timestamp_to_id.pop_first() {
-                if self.snippet_ids.contains(&id) {
-                    tracing::trace!("Retain snippet clip and update its timestamp (id: {id})");
-                    let _ = self.timestamp_to_id.insert(now, id);
-                    let _ = self.clips.get_mut(&id).map(|entry| entry.set_timestamp(now));
-                } else {
-                    tracing::trace!("Remove old clip (id: {id}, timestamp: {timestamp})");
-                    drop(self.clips.remove(&id));
+        while self.clips.len() > self.capacity + retained_count {
+            // pinned clips keep their timestamps, the oldest unpinned clip is taken instead
+            let Some((timestamp, id)) = self
+                .timestamp_to_id
+                .iter()
+                .map(|(&timestamp, &id)| (timestamp, id))
+                .find(|(_, id)| !self.is_pinned(*id))
+            else {
+                break;
+            };
+            let _unused = self.timestamp_to_id.remove(&timestamp);
+            if self.snippet_ids.contains(&id) {
+                tracing::trace!("Retain snippet clip and update its timestamp (id: {id})");
+                let _ = self.timestamp_to_id.insert(now, id);
+                let _ = self.clips.get_mut(&id).map(|entry| entry.set_timestamp(now));
+            } else {
+                tracing::trace!("Remove old clip (id: {id}, timestamp: {timestamp})");
+                if self.remove_clip(id).is_some() {
+                    metrics::history::EVICTIONS_TOTAL.inc();
+                    self.event_bus.publish(Event::ClipRemoved { id });
                 }
             }
         }
@@ -196,7 +260,12 @@ where
 
     pub fn remove_snippet(&mut self, id: u64) -> bool {
         if self.snippet_ids.remove(&id) {
-            self.clips.remove(&id).is_some()
+            let removed = self.remove_clip(id).is_some();
+            if removed {
+                self.event_bus.publish(Event::ClipRemoved { id });
+                self.update_size_metrics();
+            }
+            removed
         } else {
             false
         }
@@ -217,8 +286,11 @@ where
             }
         }
 
-        if let Some(clip) = self.clips.remove(&id) {
+        let _unused = self.pinned_ids.remove(&id);
+        if let Some(clip) = self.remove_clip(id) {
             let _id = self.timestamp_to_id.remove(&clip.timestamp());
+            self.event_bus.publish(Event::ClipRemoved { id });
+            self.update_size_metrics();
             Some(clip)
         } else {
             None
@@ -227,21 +299,61 @@ where
 
     #[inline]
     pub fn clear(&mut self) {
-        self.timestamp_to_id.retain(|_, id| self.snippet_ids.contains(id));
+        self.timestamp_to_id
+            .retain(|_, id| self.snippet_ids.contains(id) || self.pinned_ids.contains(id));
         self.current_clips = [None; ClipboardKind::MAX_LENGTH];
-        self.clips.retain(|id, _| self.snippet_ids.contains(id));
+        let mut removed_bytes = 0;
+        self.clips.retain(|id, clip| {
+            let retained = self.snippet_ids.contains(id) || self.pinned_ids.contains(id);
+            if !retained {
+                removed_bytes += clip.len();
+            }
+            retained
+        });
+        self.bytes -= removed_bytes;
+        self.event_bus.publish(Event::HistoryCleared);
         self.notification.on_history_cleared();
+        self.update_size_metrics();
     }
 
     pub fn replace(&mut self, old_id: u64, data: &[u8], mime: &mime::Mime) -> (bool, u64) {
+        let is_pinned = self.is_pinned(old_id);
         let kind = self.remove_inner(old_id).map_or(ClipboardKind::Primary, |clip| clip.kind());
         ClipEntry::new(data, mime, kind, None).map_or((false, old_id), |entry| {
             let new_id = entry.id();
             let _ = self.insert_inner(entry);
+            if is_pinned {
+                let _ = self.pin(new_id);
+            }
             (true, new_id)
         })
     }
 
+    /// Pins a clip, returns `false` if the clip does not exist.
+    pub fn pin(&mut self, id: u64) -> bool {
+        if !self.clips.contains_key(&id) {
+            return false;
+        }
+        if self.pinned_ids.insert(id) {
+            self.event_bus.publish(Event::ClipPinned { id, pinned: true });
+        }
+        true
+    }
+
+    /// Unpins a clip, returns `false` if the clip is not pinned.
+    pub fn unpin(&mut self, id: u64) -> bool {
+        let unpinned = self.pinned_ids.remove(&id);
+        if unpinned {
+            self.event_bus.publish(Event::ClipPinned { id, pinned: false });
+            self.remove_oldest();
+            self.update_size_metrics();
+        }
+        unpinned
+    }
+
+    #[inline]
+    pub fn is_pinned(&self, id: u64) -> bool { self.pinned_ids.contains(&id) }
+
     pub async fn mark(&mut self, id: u64, clipboard_kind: ClipboardKind) -> Result<(), Error> {
         if let Some(clip) = self.clips.get_mut(&id) {
             clip.mark(clipboard_kind);
@@ -249,23 +361,68 @@ where
                 .store(clipboard_kind, clip.as_ref().clone())
                 .await
                 .context(error::StoreClipboardContentSnafu)?;
+            self.event_bus.publish(Event::ClipMarked { id, kind: clipboard_kind });
         }
 
         Ok(())
     }
 
+    /// Removes the clip captured from `clipboard_kind` and puts the previous
+    /// clip of the same kind back into the clipboard, returns `false` if the
+    /// clip does not exist.
+    pub async fn undo_capture(
+        &mut self,
+        id: u64,
+        clipboard_kind: ClipboardKind,
+    ) -> Result<bool, Error> {
+        if self.is_snippet(id) || self.remove_inner(id).is_none() {
+            return Ok(false);
+        }
+
+        let previous_id = self.timestamp_to_id.values().rev().copied().find(|id| {
+            !self.is_snippet(*id)
+                && self.clips.get(id).is_some_and(|clip| clip.kind() == clipboard_kind)
+        });
+        if let Some(previous_id) = previous_id {
+            self.mark(previous_id, clipboard_kind).await?;
+            self.current_clips[usize::from(clipboard_kind)] = Some(previous_id);
+        }
+        Ok(true)
+    }
+
     #[inline]
     fn is_snippet(&self, id: u64) -> bool { self.snippet_ids.contains(&id) }
+
+    // clips are only added to and removed from `clips` with the following
+    // methods, so that `bytes` is kept up to date
+    fn insert_clip(&mut self, clip: ClipEntry) {
+        self.bytes += clip.len();
+        if let Some(replaced) = self.clips.insert(clip.id(), clip) {
+            self.bytes -= replaced.len();
+        }
+    }
+
+    fn remove_clip(&mut self, id: u64) -> Option<ClipEntry> {
+        let clip = self.clips.remove(&id)?;
+        self.bytes -= clip.len();
+        Some(clip)
+    }
+
+    fn update_size_metrics(&self) {
+        metrics::history::ENTRIES.set(i64::try_from(self.clips.len()).unwrap_or(i64::MAX));
+        metrics::history::BYTES.set(i64::try_from(self.bytes).unwrap_or(i64::MAX));
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::{collections::HashSet, sync::Arc, time::Duration};
 
-    use clipcat_base::{ClipEntry, ClipboardKind};
+    use clipcat_base::{ClipEntry, ClipSource, ClipboardKind};
 
     use crate::{
-        backend::LocalClipboardBackend,
+        backend::{ClipboardBackend, LocalClipboardBackend},
+        events::{Event, EventBus, PREVIEW_LENGTH},
         manager::{ClipboardManager, DEFAULT_CAPACITY},
         notification::DummyNotification,
     };
@@ -294,12 +451,7 @@ mod tests {
 
         let cap = 20;
         let backend = Arc::new(LocalClipboardBackend::new());
-        let mgr = ClipboardManager::with_capacity(
-            backend,
-            cap,
-            time::Duration::milliseconds(0),
-            notification,
-        );
+        let mgr = ClipboardManager::with_capacity(backend, cap, EventBus::new(), notification);
         assert!(mgr.is_empty());
         assert_eq!(mgr.len(), 0);
         assert_eq!(mgr.capacity(), cap);
@@ -312,12 +464,7 @@ mod tests {
         let backend = Arc::new(LocalClipboardBackend::new());
         let notification = DummyNotification::default();
         let cap = 10;
-        let mut mgr = ClipboardManager::with_capacity(
-            backend,
-            cap,
-            time::Duration::milliseconds(0),
-            notification,
-        );
+        let mut mgr = ClipboardManager::with_capacity(backend, cap, EventBus::new(), notification);
         assert_eq!(mgr.len(), 0);
         assert_eq!(mgr.capacity(), cap);
 
@@ -332,7 +479,7 @@ mod tests {
 
         let n = 25;
         let clips = create_clips(n);
-        mgr.import(&clips);
+        mgr.import(&clips, &HashSet::new());
 
         assert_eq!(mgr.len(), cap);
         assert_eq!(mgr.capacity(), cap);
@@ -345,6 +492,69 @@ mod tests {
         assert_eq!(exported, clips);
     }
 
+    #[test]
+    fn test_set_capacity() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let notification = DummyNotification::default();
+        let mut mgr = ClipboardManager::with_capacity(backend, 20, EventBus::new(), notification);
+
+        let n = 20;
+        let clips = create_clips(n);
+        mgr.import(&clips, &HashSet::new());
+        assert_eq!(mgr.len(), n);
+
+        let cap = 5;
+        mgr.set_capacity(cap);
+        assert_eq!(mgr.capacity(), cap);
+        assert_eq!(mgr.len(), cap);
+
+        let mut exported = mgr.export(false);
+        exported.sort_unstable();
+        let mut clips = clips[(n - cap)..].to_vec();
+        clips.sort_unstable();
+        assert_eq!(exported, clips);
+
+        mgr.set_capacity(0);
+        assert_eq!(mgr.capacity(), DEFAULT_CAPACITY);
+        assert_eq!(mgr.len(), cap);
+    }
+
+    #[test]
+    fn test_replace_snippets() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let notification = DummyNotification::default();
+        let mut mgr = ClipboardManager::with_capacity(backend, 20, EventBus::new(), notification);
+
+        let clips = create_clips(3);
+        mgr.import(&clips, &HashSet::new());
+        let snippets = (0..6)
+            .map(|i| {
+                // sleep for 1 millisecond, avoiding duplicated timestamp
+                std::thread::sleep(Duration::from_millis(1));
+
+                ClipEntry::from_string(format!("snippet {i}"), ClipboardKind::Clipboard)
+            })
+            .collect::<Vec<_>>();
+        mgr.insert_snippets(&snippets[..4]);
+        assert_eq!(mgr.len(), 7);
+
+        let metadata = mgr.list(10);
+        let sources = |source| metadata.iter().filter(|metadata| metadata.source == source).count();
+        assert_eq!(sources(ClipSource::Snippet), 4);
+        assert_eq!(sources(ClipSource::History), clips.len());
+
+        mgr.replace_snippets(&snippets[2..]);
+        assert_eq!(mgr.len(), 7);
+        assert!(mgr.get(snippets[0].id()).is_none());
+        assert!(mgr.get(snippets[1].id()).is_none());
+        assert!(snippets[2..].iter().all(|snippet| mgr.get(snippet.id()).is_some()));
+        assert!(clips.iter().all(|clip| mgr.get(clip.id()).is_some()));
+        assert_eq!(mgr.export(false).len(), clips.len());
+
+        mgr.replace_snippets(&[]);
+        assert_eq!(mgr.len(), clips.len());
+    }
+
     #[allow(clippy::mutable_key_type)]
     #[test]
     fn test_insert() {
@@ -373,14 +583,9 @@ mod tests {
         let mut clips = create_clips(n);
         let backend = Arc::new(LocalClipboardBackend::new());
         let notification = DummyNotification::default();
-        let mut mgr = ClipboardManager::with_capacity(
-            backend,
-            20,
-            time::Duration::milliseconds(0),
-            notification,
-        );
+        let mut mgr = ClipboardManager::with_capacity(backend, 20, EventBus::new(), notification);
 
-        mgr.import(&clips);
+        mgr.import(&clips, &HashSet::new());
         assert_eq!(mgr.len(), n);
 
         assert!(mgr.get_current_clip(ClipboardKind::Clipboard).is_none());
@@ -450,7 +655,7 @@ mod tests {
         let clips = create_clips(n);
         let mut mgr = ClipboardManager::new(backend, notification);
 
-        mgr.import(&clips);
+        mgr.import(&clips, &HashSet::new());
         assert!(!mgr.is_empty());
         assert_eq!(mgr.len(), n);
 
@@ -458,4 +663,175 @@ mod tests {
         assert!(mgr.is_empty());
         assert_eq!(mgr.len(), 0);
     }
+
+    #[test]
+    fn test_pin() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let notification = DummyNotification::default();
+        let cap = 5;
+        let mut mgr = ClipboardManager::with_capacity(backend, cap, EventBus::new(), notification);
+        let clips = create_clips(cap);
+        mgr.import(&clips, &HashSet::new());
+        let (oldest, second) = (clips[0].id(), clips[1].id());
+
+        assert!(!mgr.pin(43));
+        assert!(mgr.pin(oldest));
+        assert!(mgr.is_pinned(oldest));
+        assert!(mgr.list(10).iter().all(|metadata| metadata.pinned == (metadata.id == oldest)));
+
+        // the oldest unpinned clip is removed when the history is full
+        for i in 0..cap {
+            std::thread::sleep(Duration::from_millis(1));
+            let _ = mgr.insert(ClipEntry::from_string(format!("new {i}"), ClipboardKind::Primary));
+        }
+        assert_eq!(mgr.len(), cap + 1);
+        assert!(mgr.get(oldest).is_some());
+        assert!(mgr.get(second).is_none());
+
+        let (ok, new_id) = mgr.replace(oldest, b"pinned", &mime::TEXT_PLAIN_UTF_8);
+        assert!(ok);
+        assert!(mgr.is_pinned(new_id));
+        assert!(!mgr.is_pinned(oldest));
+
+        mgr.clear();
+        assert_eq!(mgr.len(), 1);
+        assert!(mgr.get(new_id).is_some());
+
+        assert!(mgr.unpin(new_id));
+        assert!(!mgr.unpin(new_id));
+        mgr.clear();
+        assert!(mgr.is_empty());
+    }
+
+    #[test]
+    fn test_import_pinned() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let notification = DummyNotification::default();
+        let cap = 2;
+        let mut mgr = ClipboardManager::with_capacity(backend, cap, EventBus::new(), notification);
+        let clips = create_clips(cap + 1);
+        let oldest = clips[0].id();
+
+        // the oldest clip is retained beyond the capacity
+        mgr.import(&clips, &HashSet::from([oldest]));
+        assert_eq!(mgr.len(), cap + 1);
+        assert!(mgr.is_pinned(oldest));
+        assert_eq!(mgr.pinned_clips(), [clips[0].clone()]);
+    }
+
+    #[test]
+    fn test_pinned_snippet() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let notification = DummyNotification::default();
+        let cap = 2;
+        let mut mgr = ClipboardManager::with_capacity(backend, cap, EventBus::new(), notification);
+        let snippet = ClipEntry::from_string("snippet", ClipboardKind::Clipboard);
+        mgr.insert_snippets(std::slice::from_ref(&snippet));
+        assert!(mgr.pin(snippet.id()));
+
+        // the pinned snippet takes a single place beyond the capacity
+        for clip in create_clips(cap + 1) {
+            let _ = mgr.insert(clip);
+        }
+        assert_eq!(mgr.len(), cap + 1);
+    }
+
+    #[tokio::test]
+    async fn test_undo_capture() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let notification = DummyNotification::default();
+        let mut mgr = ClipboardManager::new(backend.clone(), notification);
+        let clips = create_clips(3);
+        let (first, second, third) = (clips[0].id(), clips[1].id(), clips[2].id());
+        for clip in &clips {
+            let _ = mgr.insert(clip.clone());
+        }
+        let other = mgr.insert(ClipEntry::from_string("other", ClipboardKind::Clipboard));
+
+        assert!(!mgr.undo_capture(43, ClipboardKind::Primary).await.unwrap());
+        assert!(mgr.undo_capture(third, ClipboardKind::Primary).await.unwrap());
+        assert!(mgr.get(third).is_none());
+        assert_eq!(mgr.get_current_clip(ClipboardKind::Primary).unwrap().id(), second);
+        assert_eq!(
+            backend.load(ClipboardKind::Primary, None).await.unwrap(),
+            clips[1].as_ref().clone()
+        );
+        assert_eq!(mgr.get_current_clip(ClipboardKind::Clipboard).unwrap().id(), other);
+
+        assert!(mgr.undo_capture(second, ClipboardKind::Primary).await.unwrap());
+        assert!(mgr.undo_capture(first, ClipboardKind::Primary).await.unwrap());
+        assert!(mgr.get_current_clip(ClipboardKind::Primary).is_none());
+        assert_eq!(mgr.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_events() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let event_bus = EventBus::new();
+        let mut event_receiver = event_bus.subscribe();
+        let mut mgr =
+            ClipboardManager::with_capacity(backend, 1, event_bus, DummyNotification::default());
+
+        let clips = create_clips(2);
+        let (first, second) = (clips[0].id(), clips[1].id());
+        let _ = mgr.insert(clips[0].clone());
+        let _ = mgr.insert(clips[1].clone());
+        // the clip is in the history already
+        let _ = mgr.insert(clips[1].clone());
+        mgr.mark(second, ClipboardKind::Primary).await.unwrap();
+        assert!(mgr.remove(second));
+        mgr.clear();
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_receiver.try_recv() {
+            events.push(event);
+        }
+        assert_eq!(
+            events,
+            [
+                Event::ClipAdded { metadata: clips[0].metadata(Some(PREVIEW_LENGTH)) },
+                Event::ClipAdded { metadata: clips[1].metadata(Some(PREVIEW_LENGTH)) },
+                // the capacity is 1, the first clip is removed
+                Event::ClipRemoved { id: first },
+                Event::ClipMarked { id: second, kind: ClipboardKind::Primary },
+                Event::ClipRemoved { id: second },
+                Event::HistoryCleared,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let mut mgr = ClipboardManager::with_capacity(
+            backend,
+            3,
+            EventBus::new(),
+            DummyNotification::default(),
+        );
+        let assert_bytes = |mgr: &ClipboardManager<DummyNotification>| {
+            assert_eq!(mgr.bytes, mgr.clips.values().map(ClipEntry::len).sum::<usize>());
+        };
+
+        let clips = create_clips(5);
+        mgr.import(&clips[..2], &HashSet::from([clips[0].id()]));
+        assert_bytes(&mgr);
+        mgr.insert_snippets(&[ClipEntry::from_string("snippet", ClipboardKind::Clipboard)]);
+        assert_bytes(&mgr);
+        // the oldest clips are removed
+        for clip in &clips[2..] {
+            let _id = mgr.insert(clip.clone());
+            let _id = mgr.insert(clip.clone());
+            assert_bytes(&mgr);
+        }
+        let (ok, _new_id) = mgr.replace(clips[4].id(), b"replaced", &mime::TEXT_PLAIN_UTF_8);
+        assert!(ok);
+        assert_bytes(&mgr);
+        assert!(mgr.remove(clips[3].id()));
+        assert_bytes(&mgr);
+        mgr.clear();
+        assert_bytes(&mgr);
+        mgr.set_capacity(1);
+        assert_bytes(&mgr);
+    }
 }
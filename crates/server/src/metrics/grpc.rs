@@ -1,7 +1,17 @@
 use once_cell::sync::Lazy;
-use prometheus::IntCounter;
+use prometheus::{HistogramOpts, HistogramVec, IntCounter};
 
 pub static REQUESTS_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
     IntCounter::new("grpc_requests_total", "Total number of request from gRPC")
         .expect("setup metrics")
 });
+pub static REQUEST_DURATION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    HistogramVec::new(
+        HistogramOpts::new(
+            "grpc_request_duration_seconds",
+            "Latencies of handling request with gRPC in seconds",
+        ),
+        &["method"],
+    )
+    .expect("setup metrics")
+});
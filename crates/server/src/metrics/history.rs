@@ -0,0 +1,29 @@
+use once_cell::sync::Lazy;
+use prometheus::{Histogram, HistogramOpts, IntCounter, IntGauge};
+
+pub static ENTRIES: Lazy<IntGauge> = Lazy::new(|| {
+    IntGauge::new("history_entries", "Number of clips in history, snippets included")
+        .expect("setup metrics")
+});
+pub static BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    IntGauge::new("history_bytes", "Total size of clips in history in bytes, snippets included")
+        .expect("setup metrics")
+});
+pub static EVICTIONS_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new(
+        "history_evictions_total",
+        "Total number of clips removed because history is full",
+    )
+    .expect("setup metrics")
+});
+pub static WRITE_DURATION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    Histogram::with_opts(HistogramOpts::new(
+        "history_write_duration_seconds",
+        "Latencies of writing history file in seconds",
+    ))
+    .expect("setup metrics")
+});
+pub static WRITE_ERRORS_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("history_write_errors_total", "Total number of failures of writing history")
+        .expect("setup metrics")
+});
@@ -0,0 +1,7 @@
+use once_cell::sync::Lazy;
+use prometheus::IntCounter;
+
+pub static REQUESTS_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("http_requests_total", "Total number of request from HTTP API and web UI")
+        .expect("setup metrics")
+});
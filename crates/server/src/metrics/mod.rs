@@ -1,9 +1,15 @@
 pub mod dbus;
 pub mod grpc;
+pub mod history;
+pub mod http;
+pub mod snippets;
+pub mod watcher;
 
 use clipcat_metrics::error;
 use snafu::ResultExt;
 
+pub use self::watcher::ListenerObserver;
+
 #[derive(Clone, Debug)]
 pub struct Metrics {
     registry: prometheus::Registry,
@@ -17,6 +23,9 @@ impl Metrics {
         registry
             .register(Box::new(grpc::REQUESTS_TOTAL.clone()))
             .context(error::SetupMetricsSnafu)?;
+        registry
+            .register(Box::new(grpc::REQUEST_DURATION_SECONDS.clone()))
+            .context(error::SetupMetricsSnafu)?;
 
         // D-Bus
         registry
@@ -26,6 +35,41 @@ impl Metrics {
             .register(Box::new(dbus::REQUEST_DURATION_SECONDS.clone()))
             .context(error::SetupMetricsSnafu)?;
 
+        // HTTP API and web UI
+        registry
+            .register(Box::new(http::REQUESTS_TOTAL.clone()))
+            .context(error::SetupMetricsSnafu)?;
+
+        // clipboard watcher
+        registry
+            .register(Box::new(watcher::CLIPS_CAPTURED_TOTAL.clone()))
+            .context(error::SetupMetricsSnafu)?;
+        registry
+            .register(Box::new(watcher::CLIPS_REJECTED_TOTAL.clone()))
+            .context(error::SetupMetricsSnafu)?;
+        registry.register(Box::new(watcher::ENABLED.clone())).context(error::SetupMetricsSnafu)?;
+        registry
+            .register(Box::new(watcher::LISTENER_RECONNECTS_TOTAL.clone()))
+            .context(error::SetupMetricsSnafu)?;
+
+        // history
+        registry.register(Box::new(history::ENTRIES.clone())).context(error::SetupMetricsSnafu)?;
+        registry.register(Box::new(history::BYTES.clone())).context(error::SetupMetricsSnafu)?;
+        registry
+            .register(Box::new(history::EVICTIONS_TOTAL.clone()))
+            .context(error::SetupMetricsSnafu)?;
+        registry
+            .register(Box::new(history::WRITE_DURATION_SECONDS.clone()))
+            .context(error::SetupMetricsSnafu)?;
+        registry
+            .register(Box::new(history::WRITE_ERRORS_TOTAL.clone()))
+            .context(error::SetupMetricsSnafu)?;
+
+        // snippets
+        registry
+            .register(Box::new(snippets::RELOADS_TOTAL.clone()))
+            .context(error::SetupMetricsSnafu)?;
+
         Ok(Self { registry })
     }
 }
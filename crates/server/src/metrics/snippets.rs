@@ -0,0 +1,7 @@
+use once_cell::sync::Lazy;
+use prometheus::IntCounter;
+
+pub static RELOADS_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+    IntCounter::new("snippet_reloads_total", "Total number of snippets reloaded after modification")
+        .expect("setup metrics")
+});
@@ -0,0 +1,50 @@
+use once_cell::sync::Lazy;
+use prometheus::{IntCounterVec, IntGauge, Opts};
+
+pub static CLIPS_CAPTURED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("watcher_clips_captured_total", "Total number of clips captured by watcher"),
+        &["kind", "mime"],
+    )
+    .expect("setup metrics")
+});
+pub static CLIPS_REJECTED_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new("watcher_clips_rejected_total", "Total number of clips rejected by filter"),
+        &["reason"],
+    )
+    .expect("setup metrics")
+});
+pub static ENABLED: Lazy<IntGauge> = Lazy::new(|| {
+    IntGauge::new("watcher_enabled", "Whether watcher is watching for clipboard event")
+        .expect("setup metrics")
+});
+pub static LISTENER_RECONNECTS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    IntCounterVec::new(
+        Opts::new(
+            "watcher_listener_reconnects_total",
+            "Total number of re-connections of clipboard listener to display server",
+        ),
+        &["listener"],
+    )
+    .expect("setup metrics")
+});
+
+/// Counts re-connections of clipboard listeners.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ListenerObserver;
+
+impl clipcat_clipboard::EventObserver for ListenerObserver {
+    fn on_reconnected(
+        &self,
+        backend_kind: clipcat_clipboard::ListenerKind,
+        _clipboard_kind: clipcat_base::ClipboardKind,
+        _connection_info: &str,
+    ) {
+        let listener = match backend_kind {
+            clipcat_clipboard::ListenerKind::X11 => "x11",
+            clipcat_clipboard::ListenerKind::Wayland => "wayland",
+        };
+        LISTENER_RECONNECTS_TOTAL.with_label_values(&[listener]).inc();
+    }
+}
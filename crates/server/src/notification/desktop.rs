@@ -1,15 +1,24 @@
-use std::{
-    fmt,
-    path::{Path, PathBuf},
-    time::Duration,
-};
+use std::{fmt, sync::Arc};
 
-use clipcat_base::{ClipboardKind, PROJECT_VERSION};
+use bytes::Bytes;
+use clipcat_base::{
+    AuditOperation, AuditTransport, ClipEntry, ClipboardContent, ClipboardKind, PROJECT_VERSION,
+};
 use futures::{FutureExt, StreamExt};
 use notify_rust::Notification as DesktopNotification;
-use tokio::sync::mpsc;
+use parking_lot::RwLock;
+use tokio::{
+    sync::{mpsc, Mutex},
+    time::Instant,
+};
 
-use crate::notification::traits;
+use crate::{
+    audit::{AuditLog, Peer},
+    config::{DesktopNotificationConfig, NotificationEvents},
+    manager::ClipboardManager,
+    notification::{traits, Notifier},
+    watcher::ClipboardWatcherToggle,
+};
 
 enum Event {
     DaemonStarted,
@@ -18,96 +27,275 @@ enum Event {
     WatcherDisabled,
     X11Connected { clipboard_kind: ClipboardKind, connection_info: String },
     WaylandConnected { clipboard_kind: ClipboardKind, connection_info: String },
-    ImageFetched { size: usize, width: usize, height: usize },
-    PlaintextFetched { character_count: usize },
+    ClipFetched(FetchedClip),
     Shutdown,
 }
 
+struct FetchedClip {
+    id: u64,
+    kind: ClipboardKind,
+    content: FetchedContent,
+}
+
+enum FetchedContent {
+    Image { width: usize, height: usize, bytes: Bytes },
+    Plaintext { character_count: usize, preview: String },
+}
+
+impl FetchedClip {
+    fn body(&self) -> String {
+        match self.content {
+            FetchedContent::Image { width, height, ref bytes } => format!(
+                "Fetched a new image.\n(size: {size}, width: {width}, height: {height})",
+                size = humansize::format_size(bytes.len(), humansize::BINARY)
+            ),
+            FetchedContent::Plaintext { character_count, ref preview } if preview.is_empty() => {
+                format!("Fetched a long plaintext.\n(size: {character_count})")
+            }
+            FetchedContent::Plaintext { character_count, ref preview } => {
+                format!("Fetched a long plaintext.\n(size: {character_count})\n{preview}")
+            }
+        }
+    }
+}
+
+/// Actions shown as buttons on notifications of new clips.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Action {
+    UndoCapture,
+    Pin,
+    Remove,
+    PauseWatcher,
+}
+
+impl Action {
+    const ALL: [Self; 4] = [Self::UndoCapture, Self::Pin, Self::Remove, Self::PauseWatcher];
+
+    const fn identifier(self) -> &'static str {
+        match self {
+            Self::UndoCapture => "undo-capture",
+            Self::Pin => "pin",
+            Self::Remove => "remove",
+            Self::PauseWatcher => "pause-watcher",
+        }
+    }
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::UndoCapture => "Undo capture",
+            Self::Pin => "Pin",
+            Self::Remove => "Remove",
+            Self::PauseWatcher => "Pause watcher",
+        }
+    }
+
+    fn from_identifier(identifier: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.identifier() == identifier)
+    }
+}
+
+struct InvokedAction {
+    action: Action,
+    clip_id: u64,
+    clip_kind: ClipboardKind,
+}
+
 #[derive(Clone, Debug)]
 pub struct Notification {
     event_sender: mpsc::UnboundedSender<Event>,
+
+    config: Arc<RwLock<DesktopNotificationConfig>>,
 }
 
 impl Notification {
-    pub fn new<IconPath>(
-        icon: IconPath,
-        timeout: Duration,
-        long_plaintext_length: usize,
-    ) -> (Self, Worker)
-    where
-        IconPath: AsRef<Path>,
-    {
+    pub fn new(config: DesktopNotificationConfig) -> (Self, Worker) {
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let config = Arc::new(RwLock::new(config));
         (
-            Self { event_sender },
-            Worker {
-                event_receiver,
-                icon: icon.as_ref().to_path_buf(),
-                timeout,
-                long_plaintext_length,
-            },
+            Self { event_sender, config: config.clone() },
+            Worker { event_receiver, config, action_handler: None },
         )
     }
+
+    /// Applies new settings to the notifications sent afterwards.
+    pub fn update_settings(&self, config: DesktopNotificationConfig) {
+        *self.config.write() = config;
+    }
+
+    fn send<F>(&self, is_enabled: F, event: Event)
+    where
+        F: FnOnce(&NotificationEvents) -> bool,
+    {
+        // the worker is not spawned if desktop notifications are disabled, events
+        // sent to it are never received
+        let config = self.config.read();
+        if config.enable && is_enabled(&config.events) {
+            drop(self.event_sender.send(event));
+        }
+    }
 }
 
 impl traits::Notification for Notification {
-    fn on_started(&self) { drop(self.event_sender.send(Event::DaemonStarted)); }
+    fn on_started(&self) { self.send(|events| events.daemon_status, Event::DaemonStarted); }
 
-    fn on_image_fetched(&self, size: usize, width: usize, height: usize) {
-        drop(self.event_sender.send(Event::ImageFetched { size, width, height }));
+    fn on_clip_fetched(&self, clip: &ClipEntry) {
+        let content = {
+            let config = self.config.read();
+            if !config.enable {
+                return;
+            }
+            match clip.as_ref() {
+                ClipboardContent::Image { width, height, bytes } if config.events.image_fetched => {
+                    FetchedContent::Image { width: *width, height: *height, bytes: bytes.clone() }
+                }
+                ClipboardContent::Plaintext(text)
+                    if config.events.plaintext_fetched && config.long_plaintext_length > 0 =>
+                {
+                    let character_count = text.chars().count();
+                    if character_count < config.long_plaintext_length {
+                        return;
+                    }
+                    FetchedContent::Plaintext {
+                        character_count,
+                        preview: truncate(text, config.preview_length),
+                    }
+                }
+                _ => return,
+            }
+        };
+        drop(self.event_sender.send(Event::ClipFetched(FetchedClip {
+            id: clip.id(),
+            kind: clip.kind(),
+            content,
+        })));
     }
 
-    fn on_plaintext_fetched(&self, character_count: usize) {
-        drop(self.event_sender.send(Event::PlaintextFetched { character_count }));
+    fn on_history_cleared(&self) {
+        self.send(|events| events.history_cleared, Event::HistoryCleared);
     }
 
-    fn on_history_cleared(&self) { drop(self.event_sender.send(Event::HistoryCleared)); }
-
-    fn on_watcher_enabled(&self) { drop(self.event_sender.send(Event::WatcherEnabled)); }
+    fn on_watcher_enabled(&self) {
+        self.send(|events| events.watcher_toggled, Event::WatcherEnabled);
+    }
 
-    fn on_watcher_disabled(&self) { drop(self.event_sender.send(Event::WatcherDisabled)); }
+    fn on_watcher_disabled(&self) {
+        self.send(|events| events.watcher_toggled, Event::WatcherDisabled);
+    }
 
     fn on_x11_connected<C>(&self, clipboard_kind: ClipboardKind, connection_info: C)
     where
         C: fmt::Display,
     {
-        drop(self.event_sender.send(Event::X11Connected {
-            clipboard_kind,
-            connection_info: connection_info.to_string(),
-        }));
+        self.send(
+            |events| events.backend_connected,
+            Event::X11Connected { clipboard_kind, connection_info: connection_info.to_string() },
+        );
     }
 
     fn on_wayland_connected<C>(&self, clipboard_kind: ClipboardKind, connection_info: C)
     where
         C: fmt::Display,
     {
-        drop(self.event_sender.send(Event::WaylandConnected {
-            clipboard_kind,
-            connection_info: connection_info.to_string(),
-        }));
+        self.send(
+            |events| events.backend_connected,
+            Event::WaylandConnected {
+                clipboard_kind,
+                connection_info: connection_info.to_string(),
+            },
+        );
+    }
+}
+
+/// Applies the actions invoked on notifications.
+struct ActionHandler {
+    clipboard_manager: Arc<Mutex<ClipboardManager<Notifier>>>,
+
+    watcher_toggle: ClipboardWatcherToggle<Notifier>,
+
+    audit_log: AuditLog,
+}
+
+impl ActionHandler {
+    async fn handle(&self, InvokedAction { action, clip_id, clip_kind }: InvokedAction) {
+        tracing::info!(
+            "Action `{action}` is invoked on clip (id: {clip_id:016x})",
+            action = action.identifier()
+        );
+        let peer = Peer::new(AuditTransport::DesktopNotification);
+        match action {
+            Action::UndoCapture => {
+                let result = {
+                    let mut clipboard_manager = self.clipboard_manager.lock().await;
+                    clipboard_manager.undo_capture(clip_id, clip_kind).await
+                };
+                self.audit_log.record(&peer, AuditOperation::Remove, vec![clip_id]).await;
+                if let Err(err) = result {
+                    tracing::warn!("Could not undo capture, error: {err}");
+                }
+            }
+            Action::Pin => {
+                let _ = self.clipboard_manager.lock().await.pin(clip_id);
+                self.audit_log.record(&peer, AuditOperation::Mark, vec![clip_id]).await;
+            }
+            Action::Remove => {
+                let _ = self.clipboard_manager.lock().await.remove(clip_id);
+                self.audit_log.record(&peer, AuditOperation::Remove, vec![clip_id]).await;
+            }
+            Action::PauseWatcher => self.watcher_toggle.disable(),
+        }
     }
 }
 
 pub struct Worker {
     event_receiver: mpsc::UnboundedReceiver<Event>,
 
-    icon: PathBuf,
+    config: Arc<RwLock<DesktopNotificationConfig>>,
 
-    timeout: Duration,
-
-    long_plaintext_length: usize,
+    action_handler: Option<ActionHandler>,
 }
 
 impl Worker {
+    /// Enables the action buttons on notifications of new clips, the actions
+    /// are applied to `clipboard_manager` and `watcher_toggle`, and recorded
+    /// in `audit_log`.
+    pub fn set_action_handler(
+        &mut self,
+        clipboard_manager: Arc<Mutex<ClipboardManager<Notifier>>>,
+        watcher_toggle: ClipboardWatcherToggle<Notifier>,
+        audit_log: AuditLog,
+    ) {
+        self.action_handler = Some(ActionHandler { clipboard_manager, watcher_toggle, audit_log });
+    }
+
     #[allow(clippy::redundant_pub_crate)]
     pub async fn serve(self, shutdown_signal: sigfinn::Shutdown) {
         let mut shutdown_signal = shutdown_signal.into_stream();
-        let Self { mut event_receiver, ref icon, timeout, long_plaintext_length } = self;
+        let Self { mut event_receiver, config, action_handler } = self;
+        let (action_sender, mut action_receiver) = mpsc::unbounded_channel();
         let pid = std::process::id();
 
+        // clips fetched within the coalescing interval and the end of the interval
+        let mut fetched_clips = Vec::new();
+        let mut flush_deadline = None;
+
         loop {
             let maybe_event = tokio::select! {
                 event = event_receiver.recv().fuse() => event,
+                Some(action) = action_receiver.recv() => {
+                    if let Some(ref action_handler) = action_handler {
+                        action_handler.handle(action).await;
+                    }
+                    continue;
+                }
+                () = tokio::time::sleep_until(flush_deadline.unwrap_or_else(Instant::now)),
+                    if flush_deadline.is_some() => {
+                    flush_deadline = None;
+                    let config = config.read().clone();
+                    let sender = action_handler.is_some().then_some(&action_sender);
+                    notify_fetched_clips(&config, std::mem::take(&mut fetched_clips), sender)
+                        .await;
+                    continue;
+                }
                 _ = shutdown_signal.next() => Some(Event::Shutdown),
             };
 
@@ -137,48 +325,24 @@ impl Worker {
                          {connection_info})"
                     )
                 }
-                Some(Event::ImageFetched { size, width, height }) => {
-                    format!(
-                        "Fetched a new image.\n(size: {size}, width: {width}, height: {height})",
-                        size = humansize::format_size(size, humansize::BINARY)
-                    )
-                }
-                Some(Event::PlaintextFetched { character_count }) => {
-                    if character_count >= long_plaintext_length && long_plaintext_length > 0 {
-                        format!("Fetched a long plaintext.\n(size: {character_count})")
-                    } else {
-                        continue;
+                Some(Event::ClipFetched(clip)) => {
+                    let coalesce_interval = config.read().coalesce_interval;
+                    fetched_clips.push(clip);
+                    if flush_deadline.is_none() {
+                        flush_deadline = Some(Instant::now() + coalesce_interval);
                     }
+                    continue;
                 }
                 Some(Event::Shutdown) | None => {
+                    if !config.read().events.daemon_status {
+                        break;
+                    }
                     prepare_to_shutdown = true;
                     format!("Daemon is shutting down.\n(version: {PROJECT_VERSION}, PID: {pid})")
                 }
             };
-            let notification = DesktopNotification::new()
-                .summary(clipcat_base::NOTIFICATION_SUMMARY)
-                .body(&body)
-                .icon(&icon.display().to_string())
-                .timeout(timeout)
-                .finalize();
-
-            #[cfg(all(
-                unix,
-                not(any(
-                    target_os = "macos",
-                    target_os = "ios",
-                    target_os = "android",
-                    target_os = "emscripten"
-                ))
-            ))]
-            if let Err(err) = notification.show_async().await {
-                tracing::warn!("Could not send desktop notification, error: {err}");
-            }
-
-            #[cfg(target_os = "macos")]
-            if let Err(err) = notification.show() {
-                tracing::warn!("Could not send desktop notification, error: {err}");
-            }
+            let config = config.read().clone();
+            let _handle = show(&new_notification(&config, &body)).await;
 
             if prepare_to_shutdown {
                 break;
@@ -187,20 +351,235 @@ impl Worker {
     }
 }
 
-impl clipcat_clipboard::EventObserver for Notification {
-    fn on_connected(
-        &self,
-        backend_kind: clipcat_clipboard::ListenerKind,
-        clipboard_kind: ClipboardKind,
-        connection_info: &str,
-    ) {
-        match backend_kind {
-            clipcat_clipboard::ListenerKind::X11 => {
-                traits::Notification::on_x11_connected(self, clipboard_kind, connection_info);
+fn new_notification(config: &DesktopNotificationConfig, body: &str) -> DesktopNotification {
+    DesktopNotification::new()
+        .summary(clipcat_base::NOTIFICATION_SUMMARY)
+        .body(body)
+        .icon(&config.icon.display().to_string())
+        .timeout(config.timeout)
+        .finalize()
+}
+
+/// Sends one notification of `clips`, the preview, the thumbnail and the
+/// actions are of the newest clip.
+async fn notify_fetched_clips(
+    config: &DesktopNotificationConfig,
+    mut clips: Vec<FetchedClip>,
+    action_sender: Option<&mpsc::UnboundedSender<InvokedAction>>,
+) {
+    let Some(clip) = clips.pop() else {
+        return;
+    };
+    let body = if clips.is_empty() {
+        clip.body()
+    } else {
+        format!("Fetched {count} new clips.\n{body}", count = clips.len() + 1, body = clip.body())
+    };
+    let mut notification = new_notification(config, &body);
+
+    #[cfg(all(
+        unix,
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android",
+            target_os = "emscripten"
+        ))
+    ))]
+    {
+        if let FetchedContent::Image { width, height, ref bytes } = clip.content {
+            if let Some(thumbnail) = thumbnail(width, height, bytes, config.thumbnail_size) {
+                let _ = notification.image_data(thumbnail);
             }
-            clipcat_clipboard::ListenerKind::Wayland => {
-                traits::Notification::on_wayland_connected(self, clipboard_kind, connection_info);
+        }
+
+        if let Some(action_sender) = action_sender.filter(|_| config.enable_actions) {
+            for action in Action::ALL {
+                let _ = notification.action(action.identifier(), action.label());
             }
+            if let Some(handle) = show(&notification).await {
+                wait_for_action(handle, clip.id, clip.kind, action_sender.clone());
+            }
+            return;
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    drop(action_sender);
+
+    let _handle = show(&notification).await;
+}
+
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "android",
+        target_os = "emscripten"
+    ))
+))]
+async fn show(notification: &DesktopNotification) -> Option<notify_rust::NotificationHandle> {
+    notification
+        .show_async()
+        .await
+        .map_err(|err| tracing::warn!("Could not send desktop notification, error: {err}"))
+        .ok()
+}
+
+#[cfg(target_os = "macos")]
+async fn show(notification: &DesktopNotification) -> Option<()> {
+    notification
+        .show()
+        .map(drop)
+        .map_err(|err| tracing::warn!("Could not send desktop notification, error: {err}"))
+        .ok()
+}
+
+// waiting for an action blocks until the notification is closed, a detached
+// thread is used for not blocking the shutdown of the runtime
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "android",
+        target_os = "emscripten"
+    ))
+))]
+fn wait_for_action(
+    handle: notify_rust::NotificationHandle,
+    clip_id: u64,
+    clip_kind: ClipboardKind,
+    action_sender: mpsc::UnboundedSender<InvokedAction>,
+) {
+    let result =
+        std::thread::Builder::new().name("notification-action".to_string()).spawn(move || {
+            handle.wait_for_action(|identifier| {
+                if let Some(action) = Action::from_identifier(identifier) {
+                    drop(action_sender.send(InvokedAction { action, clip_id, clip_kind }));
+                }
+            });
+        });
+    if let Err(err) = result {
+        tracing::warn!("Could not wait for actions of desktop notification, error: {err}");
+    }
+}
+
+/// Scales the image down to fit in `size` x `size`.
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "android",
+        target_os = "emscripten"
+    ))
+))]
+fn thumbnail(width: usize, height: usize, bytes: &[u8], size: u32) -> Option<notify_rust::Image> {
+    if size == 0 {
+        return None;
+    }
+    let (width, height) = (u32::try_from(width).ok()?, u32::try_from(height).ok()?);
+    let image = image::ImageBuffer::<image::Rgba<u8>, _>::from_raw(width, height, bytes)?;
+    let (thumbnail_width, thumbnail_height) = thumbnail_dimensions(width, height, size);
+    let thumbnail = image::imageops::thumbnail(&image, thumbnail_width, thumbnail_height);
+    notify_rust::Image::from_rgba(
+        i32::try_from(thumbnail_width).ok()?,
+        i32::try_from(thumbnail_height).ok()?,
+        thumbnail.into_raw(),
+    )
+    .ok()
+}
+
+fn thumbnail_dimensions(width: u32, height: u32, size: u32) -> (u32, u32) {
+    let longest = width.max(height);
+    if longest <= size {
+        return (width, height);
+    }
+    let scale = |length: u32| {
+        u32::try_from(u64::from(length) * u64::from(size) / u64::from(longest))
+            .unwrap_or(size)
+            .max(1)
+    };
+    (scale(width), scale(height))
+}
+
+pub(super) fn truncate(text: &str, max_length: usize) -> String {
+    if max_length == 0 {
+        return String::new();
+    }
+    let mut chars = text.trim().chars();
+    let mut preview = chars.by_ref().take(max_length).collect::<String>();
+    if chars.next().is_some() {
+        preview.push('…');
+    }
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use clipcat_base::{ClipEntry, ClipboardKind};
+
+    use super::{thumbnail_dimensions, truncate, Action, Notification};
+    use crate::{
+        config::{DesktopNotificationConfig, NotificationEvents},
+        notification::traits::Notification as _,
+    };
+
+    fn config(enable: bool) -> DesktopNotificationConfig {
+        DesktopNotificationConfig {
+            enable,
+            icon: PathBuf::new(),
+            timeout: Duration::from_secs(2),
+            long_plaintext_length: 1,
+            preview_length: 10,
+            thumbnail_size: 0,
+            enable_actions: false,
+            coalesce_interval: Duration::from_millis(100),
+            events: NotificationEvents::default(),
+        }
+    }
+
+    #[test]
+    fn test_disabled() {
+        let clip = ClipEntry::from_string("clipcat", ClipboardKind::Clipboard);
+
+        let (notification, mut worker) = Notification::new(config(false));
+        notification.on_started();
+        notification.on_clip_fetched(&clip);
+        // nothing is queued for the worker which is not spawned
+        assert!(worker.event_receiver.try_recv().is_err());
+
+        notification.update_settings(config(true));
+        notification.on_started();
+        notification.on_clip_fetched(&clip);
+        assert!(worker.event_receiver.try_recv().is_ok());
+        assert!(worker.event_receiver.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_thumbnail_dimensions() {
+        assert_eq!(thumbnail_dimensions(64, 32, 128), (64, 32));
+        assert_eq!(thumbnail_dimensions(1024, 512, 128), (128, 64));
+        assert_eq!(thumbnail_dimensions(300, 1200, 100), (25, 100));
+        assert_eq!(thumbnail_dimensions(10_000, 1, 100), (100, 1));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("  abc\n", 3), "abc");
+        assert_eq!(truncate("АБВГДЕ", 3), "АБВ…");
+        assert_eq!(truncate("abc", 0), "");
+    }
+
+    #[test]
+    fn test_action_identifier() {
+        for action in Action::ALL {
+            assert_eq!(Action::from_identifier(action.identifier()), Some(action));
         }
+        assert_eq!(Action::from_identifier("__closed"), None);
     }
 }
@@ -1,10 +1,14 @@
 mod desktop;
 mod dummy;
+mod notifier;
+mod sink;
 mod traits;
 
 #[cfg(test)]
 pub use self::dummy::Notification as DummyNotification;
 pub use self::{
     desktop::{Notification as DesktopNotification, Worker as DesktopNotificationWorker},
+    notifier::Notifier,
+    sink::{Notification as NotificationSinks, Worker as NotificationSinksWorker},
     traits::Notification,
 };
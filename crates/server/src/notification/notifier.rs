@@ -0,0 +1,93 @@
+use std::fmt;
+
+use clipcat_base::{ClipEntry, ClipboardKind};
+
+use crate::{
+    config::{DesktopNotificationConfig, NotificationSinksConfig},
+    notification::{desktop, sink, traits},
+};
+
+/// Notifies events with desktop notifications and the notification sinks.
+#[derive(Clone, Debug)]
+pub struct Notifier {
+    desktop: desktop::Notification,
+
+    sinks: sink::Notification,
+}
+
+impl Notifier {
+    pub const fn new(desktop: desktop::Notification, sinks: sink::Notification) -> Self {
+        Self { desktop, sinks }
+    }
+
+    /// Applies new settings to the notifications sent afterwards.
+    pub fn update_settings(
+        &self,
+        desktop_config: DesktopNotificationConfig,
+        sinks_config: NotificationSinksConfig,
+    ) {
+        self.desktop.update_settings(desktop_config);
+        self.sinks.update_settings(sinks_config);
+    }
+}
+
+impl traits::Notification for Notifier {
+    fn on_started(&self) {
+        self.desktop.on_started();
+        self.sinks.on_started();
+    }
+
+    fn on_clip_fetched(&self, clip: &ClipEntry) {
+        self.desktop.on_clip_fetched(clip);
+        self.sinks.on_clip_fetched(clip);
+    }
+
+    fn on_history_cleared(&self) {
+        self.desktop.on_history_cleared();
+        self.sinks.on_history_cleared();
+    }
+
+    fn on_watcher_enabled(&self) {
+        self.desktop.on_watcher_enabled();
+        self.sinks.on_watcher_enabled();
+    }
+
+    fn on_watcher_disabled(&self) {
+        self.desktop.on_watcher_disabled();
+        self.sinks.on_watcher_disabled();
+    }
+
+    fn on_x11_connected<C>(&self, clipboard_kind: ClipboardKind, connection_info: C)
+    where
+        C: fmt::Display,
+    {
+        self.desktop.on_x11_connected(clipboard_kind, &connection_info);
+        self.sinks.on_x11_connected(clipboard_kind, &connection_info);
+    }
+
+    fn on_wayland_connected<C>(&self, clipboard_kind: ClipboardKind, connection_info: C)
+    where
+        C: fmt::Display,
+    {
+        self.desktop.on_wayland_connected(clipboard_kind, &connection_info);
+        self.sinks.on_wayland_connected(clipboard_kind, &connection_info);
+    }
+}
+
+impl clipcat_clipboard::EventObserver for Notifier {
+    fn on_connected(
+        &self,
+        backend_kind: clipcat_clipboard::ListenerKind,
+        clipboard_kind: ClipboardKind,
+        connection_info: &str,
+    ) {
+        match backend_kind {
+            clipcat_clipboard::ListenerKind::X11 => {
+                traits::Notification::on_x11_connected(self, clipboard_kind, connection_info);
+            }
+            clipcat_clipboard::ListenerKind::Wayland => {
+                traits::Notification::on_wayland_connected(self, clipboard_kind, connection_info);
+            }
+        }
+    }
+}
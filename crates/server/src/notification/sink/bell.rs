@@ -0,0 +1,35 @@
+use std::path::{Path, PathBuf};
+
+use snafu::ResultExt;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+};
+
+use crate::error::{self, Error};
+
+const BELL: &[u8] = b"\x07";
+
+/// Rings the bell of a terminal for each event.
+#[derive(Debug)]
+pub struct Bell {
+    tty: PathBuf,
+
+    file: File,
+}
+
+impl Bell {
+    pub async fn open(tty: &Path) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .write(true)
+            .open(tty)
+            .await
+            .context(error::OpenBellTerminalSnafu { path: tty.to_path_buf() })?;
+        Ok(Self { tty: tty.to_path_buf(), file })
+    }
+
+    pub async fn ring(&mut self) -> Result<(), Error> {
+        self.file.write_all(BELL).await.context(error::RingBellSnafu { path: self.tty.clone() })?;
+        self.file.flush().await.context(error::RingBellSnafu { path: self.tty.clone() })
+    }
+}
@@ -0,0 +1,104 @@
+use std::process::Stdio;
+
+use snafu::ResultExt;
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    config::NotificationCommandConfig,
+    error::{self, Error},
+    notification::sink::Record,
+};
+
+/// Name of the environment variable holding the name of the event.
+const EVENT_ENV: &str = "CLIPCAT_EVENT";
+
+/// Runs a program for each event, the name of the event is passed in
+/// `CLIPCAT_EVENT` and the event is written to its standard input as JSON.
+#[derive(Debug)]
+pub struct Command {
+    config: NotificationCommandConfig,
+}
+
+impl Command {
+    pub const fn new(config: NotificationCommandConfig) -> Self { Self { config } }
+
+    /// Spawns the program without waiting for it to exit, so a slow program
+    /// does not delay the other sinks.
+    pub async fn send(&self, record: &Record) -> Result<(), Error> {
+        let NotificationCommandConfig { ref program, ref args } = self.config;
+        let mut child = tokio::process::Command::new(program)
+            .args(args)
+            .env(EVENT_ENV, record.event.name())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context(error::RunNotificationCommandSnafu { program: program.clone() })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            let mut line = record.to_json();
+            line.push('\n');
+            // the program may exit without reading its standard input
+            let _unused = stdin.write_all(line.as_bytes()).await;
+        }
+
+        let program = program.clone();
+        drop(tokio::spawn(async move {
+            match child.wait().await {
+                Ok(status) if !status.success() => tracing::warn!(
+                    "Notification command `{program}` exited with {status}",
+                    program = program.display()
+                ),
+                Ok(_) => {}
+                Err(err) => tracing::warn!(
+                    "Could not wait for notification command `{program}`, error: {err}",
+                    program = program.display()
+                ),
+            }
+        }));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use super::Command;
+    use crate::{
+        config::NotificationCommandConfig,
+        notification::sink::{Event, Record},
+    };
+
+    #[tokio::test]
+    async fn test_command() {
+        let directory = tempfile::tempdir().unwrap();
+        let output_path = directory.path().join("output");
+        // the output is renamed after it is written completely
+        let script = format!(
+            "{{ printf '%s\\n' \"$CLIPCAT_EVENT\"; cat; }} > {output}.tmp && mv {output}.tmp \
+             {output}",
+            output = output_path.display()
+        );
+        let command = Command::new(NotificationCommandConfig {
+            program: PathBuf::from("sh"),
+            args: vec!["-c".to_string(), script],
+        });
+        command.send(&Record::new(Event::HistoryCleared)).await.unwrap();
+
+        let output = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                if let Ok(output) = tokio::fs::read_to_string(&output_path).await {
+                    break output;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        let (name, json) = output.split_once('\n').unwrap();
+        assert_eq!(name, "history_cleared");
+        let value = serde_json::from_str::<serde_json::Value>(json.trim_end()).unwrap();
+        assert_eq!(value["event"], "history_cleared");
+    }
+}
@@ -0,0 +1,39 @@
+use clipcat_base::{DBUS_NOTIFICATION_INTERFACE, DBUS_NOTIFICATION_OBJECT_PATH};
+use snafu::ResultExt;
+use zbus::names::BusName;
+
+use crate::{
+    error::{self, Error},
+    notification::sink::Record,
+};
+
+/// Name of the signal, its arguments are the name of the event and the event
+/// as JSON.
+const SIGNAL_NAME: &str = "Event";
+
+/// Broadcasts events as D-Bus signals on the session bus, e.g. for status
+/// bars.
+#[derive(Debug)]
+pub struct DBus {
+    connection: zbus::Connection,
+}
+
+impl DBus {
+    pub async fn connect() -> Result<Self, Error> {
+        let connection = zbus::Connection::session().await?;
+        Ok(Self { connection })
+    }
+
+    pub async fn send(&self, record: &Record) -> Result<(), Error> {
+        self.connection
+            .emit_signal(
+                None::<BusName<'_>>,
+                DBUS_NOTIFICATION_OBJECT_PATH,
+                DBUS_NOTIFICATION_INTERFACE,
+                SIGNAL_NAME,
+                &(record.event.name(), record.to_json()),
+            )
+            .await
+            .context(error::EmitNotificationSignalSnafu)
+    }
+}
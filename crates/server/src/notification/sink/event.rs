@@ -0,0 +1,128 @@
+use clipcat_base::ClipboardKind;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+/// Event delivered to notification sinks.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    DaemonStarted {
+        version: &'static str,
+        pid: u32,
+    },
+
+    DaemonShuttingDown {
+        version: &'static str,
+        pid: u32,
+    },
+
+    HistoryCleared,
+
+    WatcherEnabled,
+
+    WatcherDisabled,
+
+    BackendConnected {
+        backend: &'static str,
+        #[serde(serialize_with = "serialize_clipboard_kind")]
+        clipboard_kind: ClipboardKind,
+        connection_info: String,
+    },
+
+    ClipFetched {
+        /// ID in hexadecimal, as it is printed by `clipcatctl`.
+        id: String,
+        #[serde(serialize_with = "serialize_clipboard_kind")]
+        clipboard_kind: ClipboardKind,
+        mime: String,
+        size: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        preview: Option<String>,
+    },
+}
+
+impl Event {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::DaemonStarted { .. } => "daemon_started",
+            Self::DaemonShuttingDown { .. } => "daemon_shutting_down",
+            Self::HistoryCleared => "history_cleared",
+            Self::WatcherEnabled => "watcher_enabled",
+            Self::WatcherDisabled => "watcher_disabled",
+            Self::BackendConnected { .. } => "backend_connected",
+            Self::ClipFetched { .. } => "clip_fetched",
+        }
+    }
+}
+
+/// [`Event`] with the time it occurred, serialized as one JSON object.
+#[derive(Clone, Debug, Serialize)]
+pub struct Record {
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+impl Record {
+    pub fn new(event: Event) -> Self { Self { timestamp: OffsetDateTime::now_utc(), event } }
+
+    pub fn to_json(&self) -> String { serde_json::to_string(self).expect("record is serializable") }
+}
+
+// `serialize_with` takes the field by reference
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn serialize_clipboard_kind<S>(kind: &ClipboardKind, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(&kind.as_str().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use clipcat_base::ClipboardKind;
+    use time::macros::datetime;
+
+    use super::{Event, Record};
+
+    #[test]
+    fn test_record_to_json() {
+        let record = Record {
+            timestamp: datetime!(2024-01-02 03:04:05 UTC),
+            event: Event::ClipFetched {
+                id: format!("{:016x}", 0xabcd_u64),
+                clipboard_kind: ClipboardKind::Primary,
+                mime: mime::TEXT_PLAIN_UTF_8.to_string(),
+                size: 5,
+                preview: None,
+            },
+        };
+        assert_eq!(
+            record.to_json(),
+            r#"{"timestamp":"2024-01-02T03:04:05Z","event":"clip_fetched","id":"000000000000abcd","clipboard_kind":"primary","mime":"text/plain; charset=utf-8","size":5}"#
+        );
+    }
+
+    #[test]
+    fn test_event_name() {
+        let events = [
+            Event::DaemonStarted { version: "0.0.0", pid: 1 },
+            Event::DaemonShuttingDown { version: "0.0.0", pid: 1 },
+            Event::HistoryCleared,
+            Event::WatcherEnabled,
+            Event::WatcherDisabled,
+            Event::BackendConnected {
+                backend: "x11",
+                clipboard_kind: ClipboardKind::Clipboard,
+                connection_info: String::new(),
+            },
+        ];
+        for event in events {
+            let name = event.name();
+            let value = serde_json::to_value(Record::new(event)).unwrap();
+            assert_eq!(value["event"], name);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use snafu::ResultExt;
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+};
+
+use crate::{
+    error::{self, Error},
+    notification::sink::Record,
+};
+
+/// Only the owner can read the event log.
+const FILE_MODE: u32 = 0o600;
+
+/// Appends events to a file, one JSON object per line.
+#[derive(Debug)]
+pub struct EventLog {
+    file_path: PathBuf,
+
+    file: File,
+}
+
+impl EventLog {
+    /// Opens the event log for appending, the parent directory is created if
+    /// it does not exist.
+    pub async fn open(file_path: &Path) -> Result<Self, Error> {
+        if let Some(parent) = file_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .context(error::OpenEventLogSnafu { path: parent.to_path_buf() })?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(FILE_MODE)
+            .open(file_path)
+            .await
+            .context(error::OpenEventLogSnafu { path: file_path.to_path_buf() })?;
+        Ok(Self { file_path: file_path.to_path_buf(), file })
+    }
+
+    pub async fn send(&mut self, record: &Record) -> Result<(), Error> {
+        let mut line = record.to_json();
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .await
+            .context(error::WriteEventLogSnafu { path: self.file_path.clone() })?;
+        self.file.flush().await.context(error::WriteEventLogSnafu { path: self.file_path.clone() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EventLog;
+    use crate::notification::sink::{Event, Record};
+
+    #[tokio::test]
+    async fn test_event_log() {
+        let directory = tempfile::tempdir().unwrap();
+        // the parent directory is created
+        let file_path = directory.path().join("clipcat").join("events.log");
+
+        let mut event_log = EventLog::open(&file_path).await.unwrap();
+        event_log.send(&Record::new(Event::HistoryCleared)).await.unwrap();
+        drop(event_log);
+        // events are appended to the existing file
+        let mut event_log = EventLog::open(&file_path).await.unwrap();
+        event_log.send(&Record::new(Event::WatcherDisabled)).await.unwrap();
+
+        let data = std::fs::read_to_string(&file_path).unwrap();
+        let events = data
+            .lines()
+            .map(|line| {
+                let value = serde_json::from_str::<serde_json::Value>(line).unwrap();
+                value["event"].as_str().unwrap().to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(events, ["history_cleared", "watcher_disabled"]);
+    }
+}
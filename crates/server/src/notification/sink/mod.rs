@@ -0,0 +1,304 @@
+mod bell;
+mod command;
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "android",
+        target_os = "emscripten"
+    ))
+))]
+mod dbus;
+mod event;
+mod event_log;
+
+use std::{fmt, sync::Arc};
+
+use clipcat_base::{ClipEntry, ClipboardContent, ClipboardKind, PROJECT_VERSION};
+use futures::{FutureExt, StreamExt};
+use parking_lot::RwLock;
+use tokio::sync::mpsc;
+
+pub use self::event::{Event, Record};
+use self::{bell::Bell, command::Command, event_log::EventLog};
+use crate::{
+    config::{NotificationEvents, NotificationSinksConfig},
+    error::Error,
+    notification::{desktop, traits},
+};
+
+/// Delivers the events of desktop notifications to the sinks enabled in
+/// [`NotificationSinksConfig`], e.g. for status bars and logging pipelines.
+#[derive(Clone, Debug)]
+pub struct Notification {
+    event_sender: mpsc::UnboundedSender<Event>,
+
+    config: Arc<RwLock<NotificationSinksConfig>>,
+
+    // events are not sent if no sink is opened, nobody receives them
+    has_sinks: bool,
+}
+
+impl Notification {
+    /// Opens the sinks enabled in `config`, sinks which could not be opened
+    /// are skipped with a warning.
+    ///
+    /// The worker is `None` if no sink is opened.
+    pub async fn open(config: NotificationSinksConfig) -> (Self, Option<Worker>) {
+        let mut sinks = Vec::new();
+        if let Some(ref file_path) = config.event_log {
+            tracing::info!("Event log file path: `{path}`", path = file_path.display());
+            push_opened(&mut sinks, EventLog::open(file_path).await.map(Sink::EventLog));
+        }
+        if let Some(ref command) = config.command {
+            sinks.push(Sink::Command(Command::new(command.clone())));
+        }
+        if config.dbus {
+            #[cfg(all(
+                unix,
+                not(any(
+                    target_os = "macos",
+                    target_os = "ios",
+                    target_os = "android",
+                    target_os = "emscripten"
+                ))
+            ))]
+            push_opened(&mut sinks, dbus::DBus::connect().await.map(Sink::DBus));
+
+            #[cfg(not(all(
+                unix,
+                not(any(
+                    target_os = "macos",
+                    target_os = "ios",
+                    target_os = "android",
+                    target_os = "emscripten"
+                ))
+            )))]
+            tracing::warn!("D-Bus notification sink is not supported on this platform");
+        }
+        if let Some(ref tty) = config.bell {
+            push_opened(&mut sinks, Bell::open(tty).await.map(Sink::Bell));
+        }
+
+        let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        let config = Arc::new(RwLock::new(config));
+        let has_sinks = !sinks.is_empty();
+        let worker = has_sinks.then(|| Worker { event_receiver, config: config.clone(), sinks });
+        (Self { event_sender, config, has_sinks }, worker)
+    }
+
+    /// Applies new settings to the events sent afterwards, the sinks are not
+    /// opened again.
+    pub fn update_settings(&self, config: NotificationSinksConfig) {
+        *self.config.write() = config;
+    }
+
+    fn send<F>(&self, is_enabled: F, event: Event)
+    where
+        F: FnOnce(&NotificationEvents) -> bool,
+    {
+        if self.has_sinks && is_enabled(&self.config.read().events) {
+            drop(self.event_sender.send(event));
+        }
+    }
+}
+
+impl traits::Notification for Notification {
+    fn on_started(&self) {
+        self.send(
+            |events| events.daemon_status,
+            Event::DaemonStarted { version: PROJECT_VERSION, pid: std::process::id() },
+        );
+    }
+
+    fn on_clip_fetched(&self, clip: &ClipEntry) {
+        if !self.has_sinks {
+            return;
+        }
+        let preview = {
+            let config = self.config.read();
+            let preview = match clip.as_ref() {
+                ClipboardContent::Image { .. } if config.events.image_fetched => None,
+                ClipboardContent::Plaintext(text) if config.events.plaintext_fetched => {
+                    Some(desktop::truncate(text, config.preview_length))
+                }
+                _ => return,
+            };
+            preview.filter(|preview| !preview.is_empty())
+        };
+        drop(self.event_sender.send(Event::ClipFetched {
+            id: format!("{:016x}", clip.id()),
+            clipboard_kind: clip.kind(),
+            mime: clip.mime().to_string(),
+            size: clip.len(),
+            preview,
+        }));
+    }
+
+    fn on_history_cleared(&self) {
+        self.send(|events| events.history_cleared, Event::HistoryCleared);
+    }
+
+    fn on_watcher_enabled(&self) {
+        self.send(|events| events.watcher_toggled, Event::WatcherEnabled);
+    }
+
+    fn on_watcher_disabled(&self) {
+        self.send(|events| events.watcher_toggled, Event::WatcherDisabled);
+    }
+
+    fn on_x11_connected<C>(&self, clipboard_kind: ClipboardKind, connection_info: C)
+    where
+        C: fmt::Display,
+    {
+        self.send(
+            |events| events.backend_connected,
+            Event::BackendConnected {
+                backend: "x11",
+                clipboard_kind,
+                connection_info: connection_info.to_string(),
+            },
+        );
+    }
+
+    fn on_wayland_connected<C>(&self, clipboard_kind: ClipboardKind, connection_info: C)
+    where
+        C: fmt::Display,
+    {
+        self.send(
+            |events| events.backend_connected,
+            Event::BackendConnected {
+                backend: "wayland",
+                clipboard_kind,
+                connection_info: connection_info.to_string(),
+            },
+        );
+    }
+}
+
+#[derive(Debug)]
+enum Sink {
+    EventLog(EventLog),
+
+    Command(Command),
+
+    #[cfg(all(
+        unix,
+        not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "android",
+            target_os = "emscripten"
+        ))
+    ))]
+    DBus(dbus::DBus),
+
+    Bell(Bell),
+}
+
+impl Sink {
+    async fn send(&mut self, record: &Record) -> Result<(), Error> {
+        match self {
+            Self::EventLog(event_log) => event_log.send(record).await,
+            Self::Command(command) => command.send(record).await,
+            #[cfg(all(
+                unix,
+                not(any(
+                    target_os = "macos",
+                    target_os = "ios",
+                    target_os = "android",
+                    target_os = "emscripten"
+                ))
+            ))]
+            Self::DBus(dbus) => dbus.send(record).await,
+            Self::Bell(bell) => bell.ring().await,
+        }
+    }
+}
+
+pub struct Worker {
+    event_receiver: mpsc::UnboundedReceiver<Event>,
+
+    config: Arc<RwLock<NotificationSinksConfig>>,
+
+    sinks: Vec<Sink>,
+}
+
+impl Worker {
+    #[allow(clippy::redundant_pub_crate)]
+    pub async fn serve(self, shutdown_signal: sigfinn::Shutdown) {
+        let mut shutdown_signal = shutdown_signal.into_stream();
+        let Self { mut event_receiver, config, mut sinks } = self;
+
+        loop {
+            let event = tokio::select! {
+                event = event_receiver.recv().fuse() => event,
+                _ = shutdown_signal.next() => None,
+            };
+            let Some(event) = event else {
+                if config.read().events.daemon_status {
+                    let event = Event::DaemonShuttingDown {
+                        version: PROJECT_VERSION,
+                        pid: std::process::id(),
+                    };
+                    send(&mut sinks, &Record::new(event)).await;
+                }
+                break;
+            };
+            send(&mut sinks, &Record::new(event)).await;
+        }
+    }
+}
+
+fn push_opened(sinks: &mut Vec<Sink>, sink: Result<Sink, Error>) {
+    match sink {
+        Ok(sink) => sinks.push(sink),
+        Err(err) => tracing::warn!("Notification sink is disabled, error: {err}"),
+    }
+}
+
+// failures of a sink are logged and do not affect the other sinks
+async fn send(sinks: &mut [Sink], record: &Record) {
+    for sink in sinks {
+        if let Err(err) = sink.send(record).await {
+            tracing::warn!("{err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use clipcat_base::{ClipEntry, ClipboardKind};
+
+    use super::Notification;
+    use crate::{config::NotificationSinksConfig, notification::traits::Notification as _};
+
+    #[tokio::test]
+    async fn test_open() {
+        // no event is queued without sinks
+        let (notification, worker) = Notification::open(NotificationSinksConfig::default()).await;
+        assert!(worker.is_none());
+        notification.on_clip_fetched(&ClipEntry::from_string("clipcat", ClipboardKind::Clipboard));
+
+        // sinks which could not be opened are skipped
+        let directory = tempfile::tempdir().unwrap();
+        let config = NotificationSinksConfig {
+            bell: Some(directory.path().join("nonexistent-tty")),
+            ..NotificationSinksConfig::default()
+        };
+        let (_notification, worker) = Notification::open(config).await;
+        assert!(worker.is_none());
+
+        let config = NotificationSinksConfig {
+            event_log: Some(directory.path().join("events.log")),
+            bell: Some(PathBuf::from("/nonexistent/tty")),
+            ..NotificationSinksConfig::default()
+        };
+        let (notification, worker) = Notification::open(config).await;
+        assert_eq!(worker.map(|worker| worker.sinks.len()), Some(1));
+        notification.on_started();
+    }
+}
@@ -1,13 +1,11 @@
 use std::fmt;
 
-use clipcat_base::ClipboardKind;
+use clipcat_base::{ClipEntry, ClipboardKind};
 
 pub trait Notification: Send + Sync {
     fn on_started(&self) {}
 
-    fn on_image_fetched(&self, _size: usize, _width: usize, _height: usize) {}
-
-    fn on_plaintext_fetched(&self, _character_count: usize) {}
+    fn on_clip_fetched(&self, _clip: &ClipEntry) {}
 
     fn on_history_cleared(&self) {}
 
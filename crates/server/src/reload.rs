@@ -0,0 +1,407 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use notify::{RecursiveMode, Watcher};
+use snafu::{OptionExt, ResultExt};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::{mpsc, oneshot, Mutex},
+};
+
+use crate::{
+    config::{Config, DesktopNotificationConfig, NotificationSinksConfig},
+    error::{self, Error},
+    manager::ClipboardManager,
+    notification::Notifier,
+    snippets::{self, SnippetWatcherEventReceiver},
+    watcher::SharedClipFilter,
+    Result,
+};
+
+// editors may write a file more than once while saving it
+const FILE_CHANGE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Loads the configuration again while the server is running.
+pub struct ConfigLoader {
+    load: Box<dyn Fn() -> std::result::Result<Config, String> + Send + Sync>,
+
+    watch_file_path: Option<PathBuf>,
+}
+
+impl ConfigLoader {
+    pub fn new<F>(load: F) -> Self
+    where
+        F: Fn() -> std::result::Result<Config, String> + Send + Sync + 'static,
+    {
+        Self { load: Box::new(load), watch_file_path: None }
+    }
+
+    /// Reloads the configuration whenever the file at `path` is changed.
+    #[must_use]
+    pub fn with_watch_file_path(mut self, path: PathBuf) -> Self {
+        self.watch_file_path = Some(path);
+        self
+    }
+
+    #[inline]
+    pub(crate) fn watch_file_path(&self) -> Option<&Path> { self.watch_file_path.as_deref() }
+
+    fn load(&self) -> Result<Config> {
+        (self.load)().map_err(|message| Error::LoadConfig { message })
+    }
+}
+
+pub type Responder = oneshot::Sender<Result<Vec<&'static str>>>;
+
+/// Requests the clipboard worker to reload the configuration.
+#[derive(Clone, Debug)]
+pub struct ReloadHandle {
+    request_sender: mpsc::UnboundedSender<Responder>,
+}
+
+impl ReloadHandle {
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<Responder>) {
+        let (request_sender, request_receiver) = mpsc::unbounded_channel();
+        (Self { request_sender }, request_receiver)
+    }
+
+    /// Reloads the configuration, returns the settings which take effect only
+    /// after restarting the server.
+    pub async fn reload(&self) -> Result<Vec<&'static str>> {
+        let (responder, response) = oneshot::channel();
+        self.request_sender.send(responder).map_err(|_| Error::ReloadConfigWorkerClosed)?;
+        response.await.map_err(|_| Error::ReloadConfigWorkerClosed)?
+    }
+}
+
+pub struct Reloader {
+    loader: Option<ConfigLoader>,
+
+    // the configuration in effect
+    config: Config,
+
+    clip_filter: SharedClipFilter,
+
+    notifier: Notifier,
+
+    snippets_watcher: notify::RecommendedWatcher,
+}
+
+impl Reloader {
+    pub const fn new(
+        loader: Option<ConfigLoader>,
+        config: Config,
+        clip_filter: SharedClipFilter,
+        notifier: Notifier,
+        snippets_watcher: notify::RecommendedWatcher,
+    ) -> Self {
+        Self { loader, config, clip_filter, notifier, snippets_watcher }
+    }
+
+    /// Applies the settings which can be changed at runtime, nothing is applied
+    /// if the new configuration is invalid.
+    pub async fn reload(
+        &mut self,
+        clipboard_manager: &Mutex<ClipboardManager<Notifier>>,
+    ) -> Result<(Vec<&'static str>, SnippetWatcherEventReceiver)> {
+        let config = self.loader.as_ref().context(error::NoConfigLoaderSnafu)?.load()?;
+        let clip_filter = Arc::new(
+            config.watcher.generate_clip_filter().context(error::GenerateClipFilterSnafu)?,
+        );
+        let ((snippets_watcher, snippet_event_receiver), snippets) =
+            snippets::load_and_create_watcher(&config.snippets).await?;
+
+        self.clip_filter.store(clip_filter);
+        {
+            let mut clipboard_manager = clipboard_manager.lock().await;
+            clipboard_manager.set_capacity(config.max_history);
+            clipboard_manager.replace_snippets(&snippets);
+        }
+        self.notifier
+            .update_settings(config.desktop_notification.clone(), config.notifications.clone());
+        self.snippets_watcher = snippets_watcher;
+        tracing::info!(
+            "Configuration is reloaded, capacity: {}, snippets: {}",
+            config.max_history,
+            snippets.len()
+        );
+
+        let restart_required = restart_required(&self.config, &config);
+        apply_reloadable(&mut self.config, config);
+        Ok((restart_required, snippet_event_receiver))
+    }
+}
+
+/// Returns the settings which are changed in `new` but take effect only after
+/// restarting the server.
+pub fn restart_required(running: &Config, new: &Config) -> Vec<&'static str> {
+    let Config {
+        grpc_listen_address,
+        grpc_local_socket,
+        grpc_local_socket_access,
+        grpc_access_token,
+        grpc_scoped_access_tokens,
+        grpc_tls,
+        max_history: _,
+        backend,
+        tmux,
+        synchronization,
+        history_file_path,
+        watcher,
+        dbus,
+        desktop_notification,
+        notifications,
+        metrics,
+        web_ui,
+        http_api,
+        audit_log,
+        snippets: _,
+    } = running;
+
+    [
+        (
+            "grpc",
+            *grpc_listen_address != new.grpc_listen_address
+                || *grpc_local_socket != new.grpc_local_socket
+                || *grpc_local_socket_access != new.grpc_local_socket_access
+                || *grpc_access_token != new.grpc_access_token
+                || *grpc_scoped_access_tokens != new.grpc_scoped_access_tokens
+                || *grpc_tls != new.grpc_tls,
+        ),
+        ("backend", *backend != new.backend),
+        ("tmux", *tmux != new.tmux),
+        ("synchronization", *synchronization != new.synchronization),
+        ("history_file_path", *history_file_path != new.history_file_path),
+        ("watcher.enable_clipboard", watcher.enable_clipboard != new.watcher.enable_clipboard),
+        ("watcher.enable_primary", watcher.enable_primary != new.watcher.enable_primary),
+        ("watcher.enable_secondary", watcher.enable_secondary != new.watcher.enable_secondary),
+        (
+            "watcher.sensitive_mime_types",
+            watcher.sensitive_mime_types != new.watcher.sensitive_mime_types,
+        ),
+        ("watcher.primary_debounce_ms", watcher.primary_debounce != new.watcher.primary_debounce),
+        (
+            "watcher.primary_wait_for_button_release",
+            watcher.primary_wait_for_button_release != new.watcher.primary_wait_for_button_release,
+        ),
+        ("dbus", *dbus != new.dbus),
+        (
+            "desktop_notification.enable",
+            desktop_notification.enable != new.desktop_notification.enable,
+        ),
+        ("notifications.event_log", notifications.event_log != new.notifications.event_log),
+        ("notifications.command", notifications.command != new.notifications.command),
+        ("notifications.dbus", notifications.dbus != new.notifications.dbus),
+        ("notifications.bell", notifications.bell != new.notifications.bell),
+        ("metrics", *metrics != new.metrics),
+        ("web_ui", *web_ui != new.web_ui),
+        ("http_api", *http_api != new.http_api),
+        ("audit_log", *audit_log != new.audit_log),
+    ]
+    .into_iter()
+    .filter_map(|(name, changed)| changed.then_some(name))
+    .collect()
+}
+
+fn apply_reloadable(running: &mut Config, new: Config) {
+    running.max_history = new.max_history;
+    running.snippets = new.snippets;
+    running.watcher.capture_image = new.watcher.capture_image;
+    running.watcher.filter_text_min_length = new.watcher.filter_text_min_length;
+    running.watcher.filter_text_max_length = new.watcher.filter_text_max_length;
+    running.watcher.filter_image_max_size = new.watcher.filter_image_max_size;
+    running.watcher.denied_text_regex_patterns = new.watcher.denied_text_regex_patterns;
+    running.desktop_notification = DesktopNotificationConfig {
+        enable: running.desktop_notification.enable,
+        ..new.desktop_notification
+    };
+    running.notifications = NotificationSinksConfig {
+        events: new.notifications.events,
+        preview_length: new.notifications.preview_length,
+        ..running.notifications.clone()
+    };
+}
+
+/// Reloads the configuration on `SIGHUP` and, if `watch_file_path` is set,
+/// whenever the file is changed.
+pub async fn serve_triggers<Signal>(
+    handle: ReloadHandle,
+    watch_file_path: Option<PathBuf>,
+    shutdown_signal: Signal,
+) -> Result<()>
+where
+    Signal: std::future::Future<Output = ()>,
+{
+    let mut hangup = signal(SignalKind::hangup()).context(error::CreateSignalListenerSnafu)?;
+    let (file_change_sender, mut file_change_receiver) = mpsc::unbounded_channel();
+    let _file_watcher =
+        watch_file_path.map(|path| watch_file(&path, file_change_sender.clone())).transpose()?;
+    let mut shutdown_signal = std::pin::pin!(shutdown_signal);
+
+    loop {
+        tokio::select! {
+            () = &mut shutdown_signal => break,
+            _ = hangup.recv() => tracing::info!("`SIGHUP` received, reload configuration"),
+            Some(()) = file_change_receiver.recv() => {
+                tokio::time::sleep(FILE_CHANGE_DEBOUNCE).await;
+                while file_change_receiver.try_recv().is_ok() {}
+                tracing::info!("Configuration file is changed, reload configuration");
+            }
+        }
+
+        reload_and_report(&handle).await;
+    }
+
+    Ok(())
+}
+
+async fn reload_and_report(handle: &ReloadHandle) {
+    match handle.reload().await {
+        Ok(restart_required) if restart_required.is_empty() => {}
+        Ok(restart_required) => tracing::warn!(
+            "Changes of {} take effect after restarting {}",
+            restart_required.join(", "),
+            clipcat_base::DAEMON_PROGRAM_NAME
+        ),
+        Err(err) => tracing::warn!("{err}"),
+    }
+}
+
+fn watch_file(
+    path: &Path,
+    file_change_sender: mpsc::UnboundedSender<()>,
+) -> Result<notify::RecommendedWatcher> {
+    // the directory is watched as editors may replace the file instead of
+    // writing it
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let file_name = path.file_name().map(ToOwned::to_owned);
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+            Ok(event) if event.kind.is_access() => {}
+            Ok(event) => {
+                if event.paths.iter().any(|path| path.file_name() == file_name.as_deref()) {
+                    let _unused = file_change_sender.send(());
+                }
+            }
+            Err(err) => tracing::warn!("Error occurs while watching file system, error: {err:?}"),
+        })
+        .context(error::CreateFileWatcherSnafu)?;
+    watcher
+        .watch(&directory, RecursiveMode::NonRecursive)
+        .context(error::CreateFileWatcherSnafu)?;
+    tracing::info!("Watch configuration file `{}` for changes", path.display());
+    Ok(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, path::PathBuf, time::Duration};
+
+    use crate::{
+        config::{
+            BackendConfig, Config, DBusConfig, DesktopNotificationConfig, HttpApiConfig,
+            LocalSocketAccessConfig, MetricsConfig, NotificationEvents, NotificationSinksConfig,
+            SynchronizationConfig, TmuxConfig, WebUiConfig,
+        },
+        reload::{apply_reloadable, restart_required},
+        ClipboardWatcherOptions,
+    };
+
+    fn config() -> Config {
+        Config {
+            grpc_listen_address: None,
+            grpc_local_socket: Some(PathBuf::from("/tmp/clipcat.sock")),
+            grpc_local_socket_access: LocalSocketAccessConfig::default(),
+            grpc_access_token: None,
+            grpc_scoped_access_tokens: Vec::new(),
+            grpc_tls: None,
+            max_history: 50,
+            backend: BackendConfig::Default,
+            tmux: TmuxConfig {
+                enable: false,
+                socket_path: None,
+                clipboard_kind: clipcat_base::ClipboardKind::Clipboard,
+                polling_interval: Duration::from_secs(1),
+                set_buffer_on_store: false,
+            },
+            synchronization: SynchronizationConfig::default(),
+            history_file_path: PathBuf::from("/tmp/clipcat/history"),
+            watcher: ClipboardWatcherOptions::default(),
+            dbus: DBusConfig { enable: true, identifier: None, klipper_compatible: false },
+            desktop_notification: DesktopNotificationConfig {
+                enable: true,
+                icon: PathBuf::from("accessories-clipboard"),
+                timeout: Duration::from_secs(2),
+                long_plaintext_length: 2000,
+                preview_length: 100,
+                thumbnail_size: 128,
+                enable_actions: true,
+                coalesce_interval: Duration::from_millis(500),
+                events: NotificationEvents::default(),
+            },
+            notifications: NotificationSinksConfig::default(),
+            metrics: MetricsConfig {
+                enable: false,
+                listen_address: "127.0.0.1:45047".parse().unwrap(),
+            },
+            web_ui: WebUiConfig {
+                enable: false,
+                listen_address: "127.0.0.1:45048".parse().unwrap(),
+            },
+            http_api: HttpApiConfig::default(),
+            audit_log: None,
+            snippets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_restart_required() {
+        let running = config();
+        assert!(restart_required(&running, &running.clone()).is_empty());
+
+        let mut new = running.clone();
+        new.max_history = 100;
+        new.watcher.filter_text_min_length = 5;
+        new.watcher.denied_text_regex_patterns = HashSet::from(["^password".to_string()]);
+        new.desktop_notification.timeout = Duration::from_secs(5);
+        new.notifications.preview_length = 50;
+        assert!(restart_required(&running, &new).is_empty());
+
+        new.grpc_access_token = Some("token".to_string());
+        new.watcher.enable_secondary = true;
+        new.desktop_notification.enable = false;
+        new.notifications.dbus = true;
+        assert_eq!(
+            restart_required(&running, &new),
+            [
+                "grpc",
+                "watcher.enable_secondary",
+                "desktop_notification.enable",
+                "notifications.dbus"
+            ]
+        );
+
+        // settings which require restarting are still reported after reloading
+        let mut running = running;
+        apply_reloadable(&mut running, new.clone());
+        assert_eq!(running.max_history, 100);
+        assert_eq!(running.watcher.filter_text_min_length, 5);
+        assert_eq!(running.notifications.preview_length, 50);
+        assert!(!running.notifications.dbus);
+        assert_eq!(
+            restart_required(&running, &new),
+            [
+                "grpc",
+                "watcher.enable_secondary",
+                "desktop_notification.enable",
+                "notifications.dbus"
+            ]
+        );
+    }
+}
@@ -7,6 +7,8 @@ use clipcat_base::ClipEntry;
 use notify::{event, Event, EventKind};
 use tokio::sync::mpsc;
 
+use crate::metrics;
+
 pub enum SnippetWatcherEvent {
     Add(ClipEntry),
     Remove(u64),
@@ -37,6 +39,7 @@ impl EventHandler {
             tracing::info!("Snippet `{}` is modified", file_path.display());
             // insert new snippet to clipboard manager
             if let Some(clip) = load(&file_path) {
+                metrics::snippets::RELOADS_TOTAL.inc();
                 let id = clip.id();
                 if let Some(id) = self.file_path_to_id.insert(file_path.clone(), id) {
                     // remove old snippet from clipboard manager
@@ -0,0 +1,237 @@
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
+
+use clipcat_base::{ClipEntry, ClipFilter, ClipboardContent, ClipboardKind};
+use tokio::{
+    sync::{mpsc, Mutex},
+    time::Instant,
+};
+
+use crate::{
+    config::{SynchronizationConfig, SynchronizationRule},
+    manager::ClipboardManager,
+    notification,
+    watcher::ClipboardWatcherCurrentContents,
+};
+
+/// Synchronizes clips between clipboard kinds according to the
+/// synchronization rules.
+pub struct Synchronizer<Notification> {
+    rules: Vec<Rule>,
+
+    clipboard_manager: Arc<Mutex<ClipboardManager<Notification>>>,
+
+    current_contents: ClipboardWatcherCurrentContents,
+
+    clip_receiver: mpsc::UnboundedReceiver<ClipEntry>,
+}
+
+struct Rule {
+    from: ClipboardKind,
+
+    to: ClipboardKind,
+
+    debounce: Duration,
+
+    filter: ClipFilter,
+}
+
+impl Rule {
+    fn new(
+        from: ClipboardKind,
+        to: ClipboardKind,
+        rule: &SynchronizationRule,
+    ) -> Result<Option<Self>, regex::Error> {
+        if !rule.enable {
+            return Ok(None);
+        }
+
+        let mut filter = ClipFilter::new();
+        filter.set_text_min_length(rule.filter_text_min_length);
+        filter.set_text_max_length(rule.filter_text_max_length);
+        filter.set_image_max_size(usize::MAX);
+        filter.deny_image(!rule.synchronize_image);
+        filter.set_regex_patterns(regex::RegexSet::new(&rule.denied_text_regex_patterns)?);
+        Ok(Some(Self { from, to, debounce: rule.debounce, filter }))
+    }
+}
+
+struct PendingClip {
+    id: u64,
+
+    content: ClipboardContent,
+
+    deadline: Instant,
+}
+
+impl<Notification> Synchronizer<Notification>
+where
+    Notification: notification::Notification,
+{
+    /// # Errors
+    ///
+    /// This function will return an error if the regular expressions in the
+    /// rules are invalid.
+    pub fn new(
+        config: &SynchronizationConfig,
+        clipboard_manager: Arc<Mutex<ClipboardManager<Notification>>>,
+        current_contents: ClipboardWatcherCurrentContents,
+    ) -> Result<(Self, mpsc::UnboundedSender<ClipEntry>), regex::Error> {
+        let SynchronizationConfig {
+            clipboard_to_primary,
+            primary_to_clipboard,
+            clipboard_to_secondary,
+            secondary_to_clipboard,
+        } = config;
+        let rules = [
+            Rule::new(ClipboardKind::Clipboard, ClipboardKind::Primary, clipboard_to_primary)?,
+            Rule::new(ClipboardKind::Primary, ClipboardKind::Clipboard, primary_to_clipboard)?,
+            Rule::new(ClipboardKind::Clipboard, ClipboardKind::Secondary, clipboard_to_secondary)?,
+            Rule::new(ClipboardKind::Secondary, ClipboardKind::Clipboard, secondary_to_clipboard)?,
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        for rule in &rules {
+            tracing::info!(
+                "Synchronize {from} with {to} (debounce: {debounce:?})",
+                from = rule.from,
+                to = rule.to,
+                debounce = rule.debounce
+            );
+        }
+
+        let (clip_sender, clip_receiver) = mpsc::unbounded_channel();
+        Ok((Self { rules, clipboard_manager, current_contents, clip_receiver }, clip_sender))
+    }
+
+    pub async fn serve<Signal>(mut self, shutdown_signal: Signal)
+    where
+        Signal: Future<Output = ()>,
+    {
+        let mut pending_clips = HashMap::<(ClipboardKind, ClipboardKind), PendingClip>::new();
+        let mut shutdown_signal = std::pin::pin!(shutdown_signal);
+
+        loop {
+            let next_deadline = pending_clips.values().map(|pending| pending.deadline).min();
+            let sleep = async move {
+                match next_deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                () = &mut shutdown_signal => break,
+                maybe_clip = self.clip_receiver.recv() => {
+                    let Some(clip) = maybe_clip else {
+                        break;
+                    };
+                    for rule in self.rules.iter().filter(|rule| rule.from == clip.kind()) {
+                        let key = (rule.from, rule.to);
+                        if rule.filter.filter_clipboard_content(clip.as_ref()) {
+                            // the newest contents of the source selection are filtered out, an
+                            // older pending contents is outdated
+                            drop(pending_clips.remove(&key));
+                        } else if rule.debounce.is_zero() {
+                            self.synchronize(clip.id(), clip.as_ref().clone(), rule.to).await;
+                        } else {
+                            let _unused = pending_clips.insert(key, PendingClip {
+                                id: clip.id(),
+                                content: clip.as_ref().clone(),
+                                deadline: Instant::now() + rule.debounce,
+                            });
+                        }
+                    }
+                }
+                () = sleep => {
+                    let now = Instant::now();
+                    let expired = pending_clips
+                        .iter()
+                        .filter(|(_, pending)| pending.deadline <= now)
+                        .map(|(&key, _)| key)
+                        .collect::<Vec<_>>();
+                    for key in expired {
+                        if let Some(PendingClip { id, content, .. }) = pending_clips.remove(&key) {
+                            self.synchronize(id, content, key.1).await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn synchronize(&self, id: u64, content: ClipboardContent, to: ClipboardKind) {
+        let mut clipboard_manager = self.clipboard_manager.lock().await;
+        if clipboard_manager.get(id).is_none() {
+            return;
+        }
+
+        // the watcher ignores the change caused by the following store, so the clip is
+        // not captured again and synchronized back
+        self.current_contents.set(to, content);
+        if let Err(err) = clipboard_manager.mark(id, to).await {
+            tracing::warn!("{err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc, time::Duration};
+
+    use clipcat_base::{ClipEntry, ClipboardContent, ClipboardKind};
+    use tokio::sync::Mutex;
+
+    use super::Synchronizer;
+    use crate::{
+        backend::{ClipboardBackend, LocalClipboardBackend},
+        config::{SynchronizationConfig, SynchronizationRule},
+        manager::ClipboardManager,
+        notification::DummyNotification,
+        watcher::ClipboardWatcherCurrentContents,
+    };
+
+    #[tokio::test]
+    async fn test_debounce_and_filter() {
+        let backend = Arc::new(LocalClipboardBackend::new());
+        let manager = Arc::new(Mutex::new(ClipboardManager::new(
+            backend.clone(),
+            DummyNotification::default(),
+        )));
+        let config = SynchronizationConfig {
+            primary_to_clipboard: SynchronizationRule {
+                enable: true,
+                debounce: Duration::from_millis(100),
+                denied_text_regex_patterns: HashSet::from(["^secret".to_string()]),
+                ..SynchronizationRule::default()
+            },
+            ..SynchronizationConfig::default()
+        };
+        let current_contents = ClipboardWatcherCurrentContents::default();
+        let (synchronizer, clip_sender) =
+            Synchronizer::new(&config, manager.clone(), current_contents.clone()).unwrap();
+        let serve = tokio::spawn(synchronizer.serve(std::future::pending()));
+
+        for text in ["c", "cl", "clip", "secret"] {
+            let clip = ClipEntry::from_string(text, ClipboardKind::Primary);
+            let _id = manager.lock().await.insert(clip.clone());
+            clip_sender.send(clip).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        // the last selection is filtered out, nothing is synchronized
+        assert!(backend.load(ClipboardKind::Clipboard, None).await.is_err());
+
+        for text in ["c", "cl", "clip"] {
+            let clip = ClipEntry::from_string(text, ClipboardKind::Primary);
+            let _id = manager.lock().await.insert(clip.clone());
+            clip_sender.send(clip).unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let content = ClipboardContent::Plaintext("clip".to_string());
+        assert_eq!(backend.load(ClipboardKind::Clipboard, None).await.unwrap(), content);
+        assert!(!current_contents.replace_if_changed(ClipboardKind::Clipboard, &content));
+
+        serve.abort();
+    }
+}
@@ -0,0 +1,220 @@
+//! Integration with systemd, the protocols are implemented without
+//! `libsystemd`, see `sd_notify(3)` and `sd_listen_fds(3)`.
+
+use std::{
+    env,
+    ffi::OsString,
+    io,
+    os::{
+        fd::{FromRawFd, OwnedFd, RawFd},
+        unix::{ffi::OsStrExt, net::UnixDatagram},
+    },
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    time::Duration,
+};
+
+/// The first file descriptor passed by socket activation.
+const LISTEN_FDS_START: RawFd = 3;
+
+/// File descriptors passed by socket activation are taken only once.
+static LISTEN_FDS_TAKEN: AtomicBool = AtomicBool::new(false);
+
+static ENVIRONMENT: OnceLock<Environment> = OnceLock::new();
+
+/// Variables set by systemd, `LISTEN_FDNAMES` is removed but not used.
+const VARIABLES: [&str; 6] = [
+    "NOTIFY_SOCKET",
+    "WATCHDOG_PID",
+    "WATCHDOG_USEC",
+    "LISTEN_PID",
+    "LISTEN_FDS",
+    "LISTEN_FDNAMES",
+];
+
+/// Variables set by systemd for `clipcatd`, they are removed from the
+/// environment once they are read, so processes spawned by `clipcatd`, e.g.
+/// the command notification sink, do not notify systemd on behalf of
+/// `clipcatd` or take the passed file descriptors.
+#[derive(Debug, Default)]
+struct Environment {
+    notify_socket: Option<OsString>,
+
+    watchdog_pid: Option<OsString>,
+
+    watchdog_usec: Option<OsString>,
+
+    listen_pid: Option<OsString>,
+
+    listen_fds: Option<OsString>,
+}
+
+impl Environment {
+    fn take() -> Self {
+        let environment = Self::from_lookup(|name| env::var_os(name));
+        for name in VARIABLES {
+            env::remove_var(name);
+        }
+        environment
+    }
+
+    fn from_lookup<F>(mut lookup: F) -> Self
+    where
+        F: FnMut(&str) -> Option<OsString>,
+    {
+        Self {
+            notify_socket: lookup("NOTIFY_SOCKET"),
+            watchdog_pid: lookup("WATCHDOG_PID"),
+            watchdog_usec: lookup("WATCHDOG_USEC"),
+            listen_pid: lookup("LISTEN_PID"),
+            listen_fds: lookup("LISTEN_FDS"),
+        }
+    }
+}
+
+/// Reads the variables set by systemd and removes them from the environment.
+///
+/// Modifying the environment is not thread-safe, it should be called before
+/// any thread is spawned, otherwise the variables are taken on first use.
+pub fn take_environment() { let _unused = environment(); }
+
+fn environment() -> &'static Environment { ENVIRONMENT.get_or_init(Environment::take) }
+
+/// Tells systemd that `clipcatd` is ready.
+pub fn notify_ready() { notify("READY=1"); }
+
+/// Tells systemd that `clipcatd` is shutting down.
+pub fn notify_stopping() { notify("STOPPING=1"); }
+
+/// Tells systemd that `clipcatd` is alive.
+pub fn notify_watchdog() { notify("WATCHDOG=1"); }
+
+fn notify(state: &str) {
+    let Some(ref socket_path) = environment().notify_socket else {
+        return;
+    };
+    if let Err(err) = send_notification(socket_path, state) {
+        tracing::warn!("Could not notify systemd with `{state}`, error: {err}");
+    }
+}
+
+fn send_notification(socket_path: &OsString, state: &str) -> io::Result<()> {
+    let socket = UnixDatagram::unbound()?;
+    let _len = match socket_path.as_bytes().strip_prefix(b"@") {
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+            socket.send_to_addr(state.as_bytes(), &SocketAddr::from_abstract_name(name)?)?
+        }
+        #[cfg(not(target_os = "linux"))]
+        Some(_) => return Err(io::ErrorKind::Unsupported.into()),
+        None => socket.send_to(state.as_bytes(), socket_path)?,
+    };
+    Ok(())
+}
+
+/// Returns the interval of pinging the watchdog if `WatchdogSec` is set,
+/// it is half of the timeout as recommended by `sd_watchdog_enabled(3)`.
+pub fn watchdog_interval() -> Option<Duration> {
+    let environment = environment();
+    if environment.watchdog_pid.is_some()
+        && parse::<u32>(environment.watchdog_pid.as_ref())? != std::process::id()
+    {
+        return None;
+    }
+    let timeout = parse::<u64>(environment.watchdog_usec.as_ref())?;
+    (timeout > 0).then(|| Duration::from_micros(timeout / 2))
+}
+
+/// Takes the Unix domain socket passed by socket activation, the first
+/// passed file descriptor is used if there are many.
+pub fn take_unix_listener() -> Option<tokio::net::UnixListener> {
+    let listener = std::os::unix::net::UnixListener::from(take_listen_fds().into_iter().next()?);
+    let result = listener
+        .local_addr()
+        .and_then(|_| listener.set_nonblocking(true))
+        .and_then(|()| tokio::net::UnixListener::from_std(listener));
+    match result {
+        Ok(listener) => Some(listener),
+        Err(err) => {
+            tracing::warn!("File descriptor passed by systemd is not a Unix socket, error: {err}");
+            None
+        }
+    }
+}
+
+fn take_listen_fds() -> Vec<OwnedFd> {
+    let environment = environment();
+    let Some(pid) = parse::<u32>(environment.listen_pid.as_ref()) else {
+        return Vec::new();
+    };
+    // the file descriptors are passed to another process
+    if pid != std::process::id() || LISTEN_FDS_TAKEN.swap(true, Ordering::AcqRel) {
+        return Vec::new();
+    }
+    let count = parse::<RawFd>(environment.listen_fds.as_ref());
+    (LISTEN_FDS_START..LISTEN_FDS_START + count.unwrap_or_default()).map(own_fd).collect()
+}
+
+fn parse<T>(value: Option<&OsString>) -> Option<T>
+where
+    T: FromStr,
+{
+    value?.to_str()?.parse().ok()
+}
+
+// SAFETY: the file descriptors are passed by systemd and owned by nobody else,
+// they are taken only once
+#[allow(unsafe_code)]
+fn own_fd(fd: RawFd) -> OwnedFd {
+    unsafe {
+        // systemd does not set `FD_CLOEXEC` on the file descriptors
+        let _unused = libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        OwnedFd::from_raw_fd(fd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, ffi::OsString, os::unix::net::UnixDatagram};
+
+    use super::{Environment, VARIABLES};
+
+    // the process environment is not modified, other tests spawn processes
+    // concurrently
+    #[test]
+    fn test_environment_from_lookup() {
+        let variables = HashMap::from([
+            ("NOTIFY_SOCKET", "/run/systemd/notify"),
+            ("LISTEN_PID", "42"),
+            ("LISTEN_FDS", "1"),
+        ]);
+        let mut names = Vec::new();
+        let environment = Environment::from_lookup(|name| {
+            names.push(name.to_string());
+            variables.get(name).map(OsString::from)
+        });
+        assert_eq!(environment.notify_socket, Some(OsString::from("/run/systemd/notify")));
+        assert_eq!(environment.listen_pid, Some(OsString::from("42")));
+        assert_eq!(environment.listen_fds, Some(OsString::from("1")));
+        assert!(environment.watchdog_usec.is_none());
+
+        // every variable read is removed by `Environment::take`
+        assert!(names.iter().all(|name| VARIABLES.contains(&name.as_str())));
+    }
+
+    #[test]
+    fn test_send_notification() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket_path = dir.path().join("notify");
+        let socket = UnixDatagram::bind(&socket_path).unwrap();
+        super::send_notification(&socket_path.into_os_string(), "READY=1").unwrap();
+
+        let mut buf = [0; 16];
+        let len = socket.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"READY=1");
+    }
+}
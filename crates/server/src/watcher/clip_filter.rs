@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use clipcat_base::ClipFilter;
+use parking_lot::RwLock;
+
+/// The filter applied by the watcher to new contents.
+///
+/// The filter is replaced as a whole while the configuration is reloaded, the
+/// watcher keeps using the previous filter until the new one is stored.
+#[derive(Clone, Debug)]
+pub struct SharedClipFilter(Arc<RwLock<Arc<ClipFilter>>>);
+
+impl SharedClipFilter {
+    #[inline]
+    pub fn new(clip_filter: Arc<ClipFilter>) -> Self { Self(Arc::new(RwLock::new(clip_filter))) }
+
+    /// Returns the current filter.
+    #[inline]
+    pub fn load(&self) -> Arc<ClipFilter> { self.0.read().clone() }
+
+    /// Replaces the current filter with `clip_filter`.
+    #[inline]
+    pub fn store(&self, clip_filter: Arc<ClipFilter>) { *self.0.write() = clip_filter; }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use clipcat_base::{ClipFilter, ClipboardContent};
+
+    use super::SharedClipFilter;
+
+    #[test]
+    fn test_store() {
+        let shared = SharedClipFilter::new(Arc::new(ClipFilter::new()));
+        let content = ClipboardContent::Plaintext("abc".to_string());
+        assert!(!shared.load().filter_clipboard_content(&content));
+
+        let mut filter = ClipFilter::new();
+        filter.set_text_min_length(4);
+        let filter = Arc::new(filter);
+        let other = shared.clone();
+        other.store(filter.clone());
+        assert!(Arc::ptr_eq(&shared.load(), &filter));
+        assert!(shared.load().filter_clipboard_content(&content));
+    }
+}
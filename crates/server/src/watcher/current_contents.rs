@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use clipcat_base::{ClipboardContent, ClipboardKind};
+use parking_lot::Mutex;
+
+/// The latest contents seen by the watcher for each clipboard kind.
+///
+/// A change to contents equal to the current contents is not treated as a new
+/// clip, so writers that store contents into a clipboard on purpose (e.g. the
+/// selection synchronizer) record them here first to keep the watcher from
+/// capturing their own writes again.
+#[derive(Clone, Debug, Default)]
+pub struct CurrentContents(Arc<Mutex<[ClipboardContent; ClipboardKind::MAX_LENGTH]>>);
+
+impl CurrentContents {
+    /// Records `content` as the current contents of `kind`.
+    #[inline]
+    pub fn set(&self, kind: ClipboardKind, content: ClipboardContent) {
+        self.0.lock()[usize::from(kind)] = content;
+    }
+
+    /// Records `content` as the current contents of `kind`, returns `false` if
+    /// it is already the current contents.
+    #[inline]
+    pub fn replace_if_changed(&self, kind: ClipboardKind, content: &ClipboardContent) -> bool {
+        let current = &mut self.0.lock()[usize::from(kind)];
+        if current == content {
+            false
+        } else {
+            current.clone_from(content);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clipcat_base::{ClipboardContent, ClipboardKind};
+
+    use super::CurrentContents;
+
+    #[test]
+    fn test_replace_if_changed() {
+        let contents = CurrentContents::default();
+        let content = ClipboardContent::Plaintext("clipcat".to_string());
+        assert!(contents.replace_if_changed(ClipboardKind::Primary, &content));
+        assert!(!contents.replace_if_changed(ClipboardKind::Primary, &content));
+        assert!(contents.replace_if_changed(ClipboardKind::Clipboard, &content));
+
+        contents.set(ClipboardKind::Secondary, content.clone());
+        assert!(!contents.replace_if_changed(ClipboardKind::Secondary, &content));
+    }
+}
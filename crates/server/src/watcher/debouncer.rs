@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use clipcat_base::ClipboardContent;
+use tokio::time::Instant;
+
+/// How long to wait for the last change after the pointer button is released.
+const RELEASE_DELAY: Duration = Duration::from_millis(50);
+
+/// Holds back the contents of a selection until it is complete.
+///
+/// The primary selection changes on every movement while the user drags over
+/// text, contents are released once they have not changed for the delay and
+/// the user is not selecting anymore, or shortly after the pointer button is
+/// released.
+#[derive(Debug)]
+pub struct Debouncer {
+    delay: Duration,
+
+    pending: Option<Pending>,
+
+    selecting: bool,
+}
+
+#[derive(Debug)]
+struct Pending {
+    content: ClipboardContent,
+
+    deadline: Instant,
+}
+
+impl Debouncer {
+    pub const fn new(delay: Duration) -> Self { Self { delay, pending: None, selecting: false } }
+
+    /// Replaces the pending contents, the delay starts over.
+    pub fn push(&mut self, content: ClipboardContent, now: Instant) {
+        self.pending = Some(Pending { content, deadline: now + self.delay });
+    }
+
+    /// Drops the pending contents.
+    pub fn clear(&mut self) { self.pending = None; }
+
+    /// Updates whether the user is selecting, pending contents are held back
+    /// until the selection is complete.
+    pub fn set_selecting(&mut self, selecting: bool, now: Instant) {
+        if self.selecting && !selecting {
+            if let Some(pending) = self.pending.as_mut() {
+                pending.deadline = pending.deadline.min(now + RELEASE_DELAY);
+            }
+        }
+        self.selecting = selecting;
+    }
+
+    /// Returns the instant at which [`Debouncer::poll`] should be called next,
+    /// `None` if there is nothing to release or the user is still selecting.
+    pub fn next_wakeup(&self) -> Option<Instant> {
+        self.pending.as_ref().filter(|_| !self.selecting).map(|pending| pending.deadline)
+    }
+
+    /// Takes the pending contents if the selection is complete.
+    pub fn poll(&mut self, now: Instant) -> Option<ClipboardContent> {
+        if self.next_wakeup()? <= now {
+            self.pending.take().map(|pending| pending.content)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use clipcat_base::ClipboardContent;
+    use tokio::time::Instant;
+
+    use super::{Debouncer, RELEASE_DELAY};
+
+    fn text(s: &str) -> ClipboardContent { ClipboardContent::Plaintext(s.to_string()) }
+
+    #[test]
+    fn test_stable_selection() {
+        let delay = Duration::from_millis(300);
+        let mut debouncer = Debouncer::new(delay);
+        let now = Instant::now();
+        debouncer.push(text("c"), now);
+        debouncer.push(text("cl"), now + Duration::from_millis(100));
+        debouncer.push(text("clip"), now + Duration::from_millis(200));
+        assert_eq!(debouncer.poll(now + delay), None);
+        assert_eq!(debouncer.next_wakeup(), Some(now + Duration::from_millis(200) + delay));
+        assert_eq!(debouncer.poll(now + Duration::from_millis(500)), Some(text("clip")));
+        assert_eq!(debouncer.poll(now + Duration::from_millis(600)), None);
+        assert_eq!(debouncer.next_wakeup(), None);
+    }
+
+    #[test]
+    fn test_pointer_button() {
+        let delay = Duration::from_millis(300);
+        let mut debouncer = Debouncer::new(delay);
+        let now = Instant::now();
+        debouncer.set_selecting(true, now);
+        debouncer.push(text("clip"), now);
+
+        // the selection is not complete while the button is held down
+        let later = now + Duration::from_secs(1);
+        assert_eq!(debouncer.next_wakeup(), None);
+        assert_eq!(debouncer.poll(later), None);
+        debouncer.push(text("clipcat"), later);
+
+        // the selection is complete shortly after the button is released
+        let released = later + Duration::from_millis(10);
+        debouncer.set_selecting(false, released);
+        assert_eq!(debouncer.next_wakeup(), Some(released + RELEASE_DELAY));
+        assert_eq!(debouncer.poll(released), None);
+        assert_eq!(debouncer.poll(released + RELEASE_DELAY), Some(text("clipcat")));
+    }
+
+    #[test]
+    fn test_pointer_button_without_delay() {
+        let mut debouncer = Debouncer::new(Duration::ZERO);
+        let now = Instant::now();
+        debouncer.push(text("clip"), now);
+        assert_eq!(debouncer.poll(now), Some(text("clip")));
+
+        debouncer.set_selecting(true, now);
+        debouncer.push(text("clipcat"), now);
+        assert_eq!(debouncer.poll(now + Duration::from_secs(1)), None);
+        debouncer.set_selecting(false, now + Duration::from_secs(1));
+        assert_eq!(debouncer.poll(now + Duration::from_secs(1)), Some(text("clipcat")));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut debouncer = Debouncer::new(Duration::from_millis(300));
+        let now = Instant::now();
+        debouncer.push(text("clip"), now);
+        debouncer.clear();
+        assert_eq!(debouncer.next_wakeup(), None);
+        assert_eq!(debouncer.poll(now + Duration::from_secs(1)), None);
+    }
+}
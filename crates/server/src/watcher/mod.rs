@@ -1,3 +1,6 @@
+mod clip_filter;
+mod current_contents;
+mod debouncer;
 mod error;
 mod options;
 mod toggle;
@@ -7,12 +10,15 @@ use std::sync::{
     Arc,
 };
 
-use clipcat_base::{ClipEntry, ClipFilter, ClipboardContent, ClipboardKind};
+use clipcat_base::{ClipEntry, ClipboardContent, ClipboardKind};
 use futures::{FutureExt, StreamExt};
 use snafu::OptionExt;
-use tokio::sync::broadcast;
+use tokio::{sync::broadcast, time::Instant};
 
+use self::debouncer::Debouncer;
 pub use self::{
+    clip_filter::SharedClipFilter,
+    current_contents::CurrentContents as ClipboardWatcherCurrentContents,
     error::Error,
     options::{Error as ClipboardWatcherOptionsError, Options as ClipboardWatcherOptions},
     toggle::Toggle as ClipboardWatcherToggle,
@@ -20,12 +26,15 @@ pub use self::{
 };
 use crate::{
     backend::{ClipboardBackend, Error as BackendError},
-    notification,
+    events::EventBus,
+    metrics, notification,
 };
 
 pub struct ClipboardWatcher<Notification> {
     is_watching: Arc<AtomicBool>,
+    event_bus: EventBus,
     clip_sender: broadcast::Sender<ClipEntry>,
+    current_contents: ClipboardWatcherCurrentContents,
     notification: Notification,
 }
 
@@ -36,35 +45,60 @@ where
     pub fn new(
         backend: Arc<dyn ClipboardBackend>,
         opts: ClipboardWatcherOptions,
-        clip_filter: Arc<ClipFilter>,
+        clip_filter: SharedClipFilter,
+        event_bus: EventBus,
         notification: Notification,
     ) -> (Self, ClipboardWatcherWorker) {
         let (clip_sender, _event_receiver) = broadcast::channel(16);
         let is_watching = Arc::new(AtomicBool::new(true));
+        metrics::watcher::ENABLED.set(1);
+        let current_contents = ClipboardWatcherCurrentContents::default();
         let watcher = Self {
             is_watching: is_watching.clone(),
+            event_bus,
             clip_sender: clip_sender.clone(),
+            current_contents: current_contents.clone(),
             notification,
         };
-        let worker =
-            ClipboardWatcherWorker { backend, clip_sender, clip_filter, is_watching, opts };
+        let worker = ClipboardWatcherWorker {
+            backend,
+            clip_sender,
+            clip_filter,
+            is_watching,
+            current_contents,
+            opts,
+        };
         (watcher, worker)
     }
 
     #[inline]
     pub fn subscribe(&self) -> broadcast::Receiver<ClipEntry> { self.clip_sender.subscribe() }
 
+    #[inline]
+    pub fn current_contents(&self) -> ClipboardWatcherCurrentContents {
+        self.current_contents.clone()
+    }
+
+    /// Returns the flag which is set while the watcher is watching.
+    #[inline]
+    pub fn watching_flag(&self) -> Arc<AtomicBool> { self.is_watching.clone() }
+
     #[inline]
     pub fn get_toggle(&self) -> ClipboardWatcherToggle<Notification> {
-        ClipboardWatcherToggle::new(self.is_watching.clone(), self.notification.clone())
+        ClipboardWatcherToggle::new(
+            self.is_watching.clone(),
+            self.event_bus.clone(),
+            self.notification.clone(),
+        )
     }
 }
 
 pub struct Worker {
     backend: Arc<dyn ClipboardBackend>,
     clip_sender: broadcast::Sender<ClipEntry>,
-    clip_filter: Arc<ClipFilter>,
+    clip_filter: SharedClipFilter,
     is_watching: Arc<AtomicBool>,
+    current_contents: ClipboardWatcherCurrentContents,
     opts: ClipboardWatcherOptions,
 }
 
@@ -73,28 +107,92 @@ impl Worker {
     #[allow(clippy::cognitive_complexity, clippy::redundant_pub_crate)]
     pub async fn serve(self, shutdown_signal: sigfinn::Shutdown) -> Result<(), Error> {
         let enabled_kinds = self.opts.get_enable_kinds();
-        let Self { backend, is_watching, clip_sender, clip_filter, .. } = self;
-        let mut subscriber = backend.subscribe()?;
+        let mut subscriber = self.backend.subscribe()?;
         let mut shutdown_signal = shutdown_signal.into_stream();
-        let mut current_contents: [ClipboardContent; ClipboardKind::MAX_LENGTH] =
-            [ClipboardContent::default(), ClipboardContent::default(), ClipboardContent::default()];
 
+        self.load_current_contents(&enabled_kinds).await?;
+
+        let mut primary_debouncer = Debouncer::new(self.opts.primary_debounce);
+        let mut selecting = if self.opts.primary_wait_for_button_release {
+            self.backend.watch_selecting()
+        } else {
+            None
+        };
+        if let Some(selecting) = selecting.as_mut() {
+            primary_debouncer.set_selecting(*selecting.borrow_and_update(), Instant::now());
+        }
+        // intermediate selections are replaced before they reach the history
+        let debounce_primary = !self.opts.primary_debounce.is_zero() || selecting.is_some();
+        loop {
+            let wakeup = primary_debouncer.next_wakeup();
+            let sleep = async move {
+                match wakeup {
+                    Some(wakeup) => tokio::time::sleep_until(wakeup).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let selecting_changed = async {
+                match selecting.as_mut() {
+                    Some(selecting) => {
+                        selecting.changed().await.ok().map(|()| *selecting.borrow_and_update())
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                event = subscriber.next() => {
+                    let (kind, mime) = event.context(error::SubscriberClosedSnafu)?;
+                    if !self.is_watching.load(Ordering::Relaxed)
+                        || !enabled_kinds[usize::from(kind)]
+                    {
+                        continue;
+                    }
+                    let Some(content) = self.load_changed_content(kind, mime).await else {
+                        continue;
+                    };
+                    if kind == ClipboardKind::Primary && debounce_primary {
+                        primary_debouncer.push(content, Instant::now());
+                    } else {
+                        self.send_clip(content, kind)?;
+                    }
+                }
+                _ = shutdown_signal.next() => return Ok(()),
+                is_selecting = selecting_changed => {
+                    // the pointer is not followed anymore if the sender is dropped
+                    let is_selecting = is_selecting.unwrap_or_else(|| {
+                        selecting = None;
+                        false
+                    });
+                    primary_debouncer.set_selecting(is_selecting, Instant::now());
+                }
+                () = sleep => {
+                    if !self.is_watching.load(Ordering::Relaxed) {
+                        // the selection is not captured after the watcher is disabled
+                        primary_debouncer.clear();
+                    } else if let Some(content) = primary_debouncer.poll(Instant::now()) {
+                        self.send_clip(content, ClipboardKind::Primary)?;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn load_current_contents(
+        &self,
+        enabled_kinds: &[bool; ClipboardKind::MAX_LENGTH],
+    ) -> Result<(), Error> {
         for (kind, enable) in enabled_kinds
             .iter()
             .enumerate()
             .map(|(kind, &enable)| (ClipboardKind::from(kind), enable))
         {
             if enable {
-                match backend.load(kind, None).await {
+                match self.backend.load(kind, None).await {
                     Ok(data) => {
-                        if !clip_filter.filter_clipboard_content(data.as_ref()) {
-                            current_contents[usize::from(kind)] = data.clone();
-                            if let Err(_err) = clip_sender
-                                .send(ClipEntry::from_clipboard_content(data, kind, None))
-                            {
-                                tracing::info!("ClipEntry receiver is closed.");
-                                return Err(Error::SendClipEntry);
-                            }
+                        if self.accepts(&data) {
+                            self.current_contents.set(kind, data.clone());
+                            self.send_clip(data, kind)?;
                         }
                     }
                     Err(
@@ -109,37 +207,50 @@ impl Worker {
                 }
             }
         }
+        Ok(())
+    }
 
-        loop {
-            let maybe_event = tokio::select! {
-                event = subscriber.next() => event,
-                _ = shutdown_signal.next() => return Ok(()),
-            };
-            let (kind, mime) = maybe_event.context(error::SubscriberClosedSnafu)?;
-            if is_watching.load(Ordering::Relaxed) && enabled_kinds[usize::from(kind)] {
-                match backend.load(kind, Some(mime)).await {
-                    Ok(new_content)
-                        if !clip_filter.filter_clipboard_content(new_content.as_ref())
-                            && current_contents[usize::from(kind)] != new_content =>
-                    {
-                        current_contents[usize::from(kind)] = new_content.clone();
-                        let clip = ClipEntry::from_clipboard_content(new_content, kind, None);
-                        if let Err(_err) = clip_sender.send(clip) {
-                            tracing::info!("ClipEntry receiver is closed.");
-                            return Err(Error::SendClipEntry);
-                        }
-                    }
-                    Ok(_)
-                    | Err(
-                        BackendError::EmptyClipboard
-                        | BackendError::MatchMime { .. }
-                        | BackendError::UnknownContentType,
-                    ) => {}
-                    Err(error) => {
-                        tracing::error!("Failed to load clipboard, error: {error}");
-                    }
-                }
+    async fn load_changed_content(
+        &self,
+        kind: ClipboardKind,
+        mime: mime::Mime,
+    ) -> Option<ClipboardContent> {
+        match self.backend.load(kind, Some(mime)).await {
+            Ok(new_content)
+                if self.accepts(&new_content)
+                    && self.current_contents.replace_if_changed(kind, &new_content) =>
+            {
+                Some(new_content)
             }
+            Ok(_)
+            | Err(
+                BackendError::EmptyClipboard
+                | BackendError::MatchMime { .. }
+                | BackendError::UnknownContentType,
+            ) => None,
+            Err(error) => {
+                tracing::error!("Failed to load clipboard, error: {error}");
+                None
+            }
+        }
+    }
+
+    fn accepts(&self, content: &ClipboardContent) -> bool {
+        self.clip_filter.load().rejection_reason(content).map_or(true, |reason| {
+            metrics::watcher::CLIPS_REJECTED_TOTAL.with_label_values(&[reason.as_str()]).inc();
+            false
+        })
+    }
+
+    fn send_clip(&self, content: ClipboardContent, kind: ClipboardKind) -> Result<(), Error> {
+        let entry = ClipEntry::from_clipboard_content(content, kind, None);
+        metrics::watcher::CLIPS_CAPTURED_TOTAL
+            .with_label_values(&[kind.as_str(), entry.mime().essence_str()])
+            .inc();
+        if self.clip_sender.send(entry).is_err() {
+            tracing::info!("ClipEntry receiver is closed.");
+            return Err(Error::SendClipEntry);
         }
+        Ok(())
     }
 }
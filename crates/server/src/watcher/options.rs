@@ -1,11 +1,11 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
 use clipcat_base::{ClipFilter, ClipboardKind};
 use snafu::Snafu;
 
 // SAFETY: user may use bool to enable/disable the functions
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Options {
     pub enable_clipboard: bool,
 
@@ -24,6 +24,15 @@ pub struct Options {
     pub denied_text_regex_patterns: HashSet<String>,
 
     pub sensitive_mime_types: HashSet<String>,
+
+    /// Waits until the primary selection has not changed for this duration
+    /// before capturing it, intermediate selections are not captured.
+    pub primary_debounce: Duration,
+
+    /// Holds back the primary selection while a pointer button is pressed and
+    /// captures it once the button is released, regardless of
+    /// `primary_debounce`.
+    pub primary_wait_for_button_release: bool,
 }
 
 impl Options {
@@ -68,6 +77,8 @@ impl Default for Options {
             filter_image_max_size: 5 * (1 << 20),
             denied_text_regex_patterns: HashSet::new(),
             sensitive_mime_types: HashSet::new(),
+            primary_debounce: Duration::from_millis(300),
+            primary_wait_for_button_release: true,
         }
     }
 }
@@ -5,10 +5,14 @@ use std::sync::{
 
 use clipcat_base::ClipboardWatcherState;
 
-use crate::notification;
+use crate::{
+    events::{Event, EventBus},
+    metrics, notification,
+};
 
 pub struct Toggle<Notification> {
     is_watching: Arc<AtomicBool>,
+    event_bus: EventBus,
     notification: Notification,
 }
 
@@ -16,20 +20,32 @@ impl<Notification> Toggle<Notification>
 where
     Notification: notification::Notification,
 {
-    pub const fn new(is_watching: Arc<AtomicBool>, notification: Notification) -> Self {
-        Self { is_watching, notification }
+    pub const fn new(
+        is_watching: Arc<AtomicBool>,
+        event_bus: EventBus,
+        notification: Notification,
+    ) -> Self {
+        Self { is_watching, event_bus, notification }
     }
 
     #[inline]
     pub fn enable(&self) {
-        self.is_watching.store(true, Ordering::Release);
+        metrics::watcher::ENABLED.set(1);
+        if !self.is_watching.swap(true, Ordering::AcqRel) {
+            self.event_bus
+                .publish(Event::WatcherStateChanged { state: ClipboardWatcherState::Enabled });
+        }
         self.notification.on_watcher_enabled();
         tracing::info!("ClipboardWatcher is watching for clipboard event");
     }
 
     #[inline]
     pub fn disable(&self) {
-        self.is_watching.store(false, Ordering::Release);
+        metrics::watcher::ENABLED.set(0);
+        if self.is_watching.swap(false, Ordering::AcqRel) {
+            self.event_bus
+                .publish(Event::WatcherStateChanged { state: ClipboardWatcherState::Disabled });
+        }
         self.notification.on_watcher_disabled();
         tracing::info!("ClipboardWatcher is not watching for clipboard event");
     }